@@ -3,13 +3,133 @@ event-activated = تم التفعيل
 event-deactivated = تم التعطيل
 event-started = تم البدء
 event-stopped = تم الإيقاف
+event-suspended = تم التعليق
+event-resumed = تم الاستئناف
 open = فتح
 cancel = إلغاء
 quit = خروج
 save = حفظ
 help = مساعدة
-edit = تحرير
 delete = حذف
 error-save-failed = تعذر حفظ التغييرات!
 saving-data = جاري حفظ البيانات...
 activity = نشاط
+export = تصدير…
+error-export-failed = تعذر تصدير النصوص!
+loading-data = جاري تحميل الأنشطة...
+error-load-failed = تعذر تحميل الأنشطة!
+import = استيراد…
+error-import-failed = تعذر استيراد النصوص!
+import-skipped = لم يتم العثور على هذه الأنشطة وتم تخطيها:
+import-summary = اكتمل الاستيراد مع تحذيرات
+import-missing-scripts = تعذر العثور على هذه النصوص:
+menu = القائمة
+multi-select = تحرير عدة أنشطة في وقت واحد
+activity-script-count = { $name } — { $count }/{ $total }
+files-changed = تغيّرت السكربتات على القرص. إعادة التحميل تتجاهل التعديلات غير المحفوظة.
+reload = إعادة التحميل
+recover-title = استعادة التغييرات غير المحفوظة؟
+recover-detail = لم يتم حفظ بعض تعيينات السكربتات عند آخر إغلاق للتطبيق.
+restore = استعادة
+discard = تجاهل
+unexpected-root = لا يبدو أن المجلد الهدف هو مجلد أنشطة kactivitymanagerd — هل تريد المتابعة؟
+continue = متابعة
+confirm-remove-files = سيؤدي الحفظ إلى حذف هذه الملفات، وهي ليست روابط إلى سكربتاتك:
+test-run = تشغيل تجريبي
+test-run-finished = انتهى السكربت بالرمز { $code }
+error-test-run-failed = تعذّر تشغيل السكربت!
+details = تفاصيل السكربت
+details-target = الهدف
+details-target-exists = الهدف موجود
+details-size = الحجم
+details-bytes = { $bytes } بايت
+details-modified = آخر تعديل
+details-executable = قابل للتنفيذ
+yes = نعم
+no = لا
+create-script = إنشاء سكربت…
+error-create-script-failed = تعذّر إنشاء السكربت!
+copy-details = نسخ التفاصيل
+close = إغلاق
+confirm-overwrite-links = سيؤدي الحفظ إلى استبدال هذه الروابط، التي تم تغييرها خارج kas-selector:
+replace = استبدال
+sort-by-name = الترتيب حسب الاسم
+about = حول
+about-comments = تعيين سكربتات لأحداث دورة حياة أنشطة KDE.
+website = الموقع الإلكتروني
+activity-manager-stopped = مدير أنشطة KDE لا يعمل
+activity-manager-stopped-detail = قد لا تعمل السكربتات المحفوظة الآن حتى تتم إعادة تشغيل kactivitymanagerd.
+title-summary = { title } — { $configured } من { $total } مُعدّة
+quit-button = _خروج
+save-button = _حفظ
+help-button = _مساعدة
+status-folder = المجلد:
+status-script-name = اسم السكربت:
+open-folder = فتح المجلد
+save-permission-denied = الحفظ يتطلب صلاحيات المسؤول
+save-permission-denied-detail = لا يمكن لمستخدمك الكتابة في مجلد الأنشطة. ستشغّل إعادة المحاولة هذا الأمر:
+retry-as-admin = إعادة المحاولة كمسؤول
+broken-links = بعض السكربتات مفقودة
+confirm-delete-script = إزالة السكربت لحدث { $event }؟
+confirm-delete-script-detail = يُحتفظ بملف السكربت نفسه. يُكتب التغيير عند الحفظ.
+script-missing = هذا السكربت لم يعد موجودًا. اختر سكربتًا آخر أو أزله.
+search-activities = البحث في الأنشطة
+undo = تراجع (Ctrl+Z)
+redo = إعادة (Ctrl+Y)
+unsaved-changes = هل تريد حفظ التغييرات قبل الخروج؟
+unsaved-changes-detail = لم يتم حفظ تعيينات السكربتات التي غيّرتها بعد.
+save-and-quit = حفظ وخروج
+copy-scripts-from = نسخ السكربتات من نشاط آخر
+error-activities-unreachable = تعذّر الوصول إلى kactivities-cli لعرض أنشطتك!
+filter-shell-scripts = سكربتات الصدفة
+filter-all-files = كل الملفات
+error-script-not-a-file = يمكن تعيين الملفات العادية فقط كسكربتات.
+error-script-not-shell = يجب أن تنتهي السكربتات بـ ‎.sh. اضبط KAS_ALLOW_ANY_SCRIPT=1 للسماح بملفات أخرى.
+not-executable = { $name } غير قابل للتنفيذ
+not-executable-detail = لا يستطيع مدير الأنشطة تشغيل إلا السكربتات المعلَّمة كقابلة للتنفيذ.
+make-executable = اجعله قابلاً للتنفيذ
+error-make-executable-failed = تعذّر جعل السكربت قابلاً للتنفيذ!
+import-conflicts = أحداث لها سكربت مختلف بالفعل: { $count }
+import-conflicts-detail = اختر ما إذا كانت السكربتات المستوردة ستحل محل السكربتات المعيّنة هنا. تُملأ الأحداث التي بلا سكربت في كلتا الحالتين.
+keep-current-scripts = الإبقاء على الحالية
+use-imported-scripts = استخدام المستوردة
+error-read-only-filesystem = السكربت موجود على نظام ملفات للقراءة فقط، لذا لا يمكن جعله قابلاً للتنفيذ هنا!
+sort-by-status = الأنشطة العاملة أولاً
+clean-up-orphans = تنظيف الأنشطة المحذوفة
+confirm-clean-up-orphans = { $count ->
+        [one] حذف سكربتات نشاط محذوف واحد؟
+       *[other] حذف سكربتات { $count } من الأنشطة المحذوفة؟
+    }
+confirm-clean-up-orphans-detail = تعود هذه المجلدات إلى أنشطة لم تعد موجودة. ستُحذف فوراً مع كل محتوياتها:
+error-clean-up-failed = تعذّر تنظيف الأنشطة المحذوفة!
+reload-activities = إعادة تحميل الأنشطة والسكربتات
+confirm-discard-reload = تجاهل التغييرات غير المحفوظة وإعادة التحميل؟
+script-syntax-error = يحتوي { $name } على خطأ في الصياغة
+script-syntax-error-detail = من المرجح أن يفشل السكربت عند تشغيل حدث النشاط. أبلغ bash عن:
+assign-anyway = التعيين على أي حال
+copy-scripts-to = نسخ السكربتات إلى…
+copy-scripts-to-detail = نسخ سكربتات { $name } إلى:
+overwrite-existing-scripts = استبدال السكربتات الموجودة لدى هذه الأنشطة
+copy = نسخ
+not-executable-scripts = بعض السكربتات غير قابلة للتنفيذ
+fix-permissions = إصلاح الأذونات
+open-in-editor = فتح في محرر النصوص
+test-run-timed-out = تم إيقاف السكربت بعد { $seconds } ثانية
+language = اللغة
+no-activities = لم يتم العثور على أي أنشطة — هل KDE Plasma قيد التشغيل؟
+error-startup-failed = تعذر بدء محدد نصوص أنشطة KDE!
+create-root = مجلد الأنشطة غير موجود بعد.
+create-root-detail = هل تريد إنشاء { $path } الآن؟ تُربط النصوص هناك لكل نشاط.
+create-folder = إنشاء المجلد
+assign-to-all = تعيين لكل الأنشطة
+confirm-assign-to-all = استخدام هذا النص لحدث «{ $event }» في الأنشطة ({ $count })؟
+copy-path = نسخ المسار
+add-script = إضافة سكربت
+remove-script = إزالة السكربت
+event-scripts = سكربتات هذا الحدث
+event-script-count = عدد السكربتات: { $count }
+error-open-in-editor-failed = تعذّر فتح السكربت في محرر!
+confirm-replace-copies = سيستبدل الحفظ هذه النسخ من السكربتات بروابط:
+error-save-nothing-changed = لم يتغير شيء على القرص.
+error-save-undone = تم التراجع عن التغييرات التي أُجريت بالفعل.
+error-save-incomplete = تعذر التراجع عن بعض التغييرات. تحقق من مجلدات الأنشطة المذكورة أدناه.
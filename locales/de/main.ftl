@@ -3,13 +3,139 @@ event-activated = Aktiviert
 event-deactivated = Deaktiviert
 event-started = Gestartet
 event-stopped = Gestoppt
+event-suspended = Angehalten
+event-resumed = Fortgesetzt
 open = Öffnen
 cancel = Abbrechen
 quit = Beenden
 save = Speichern
 help = Hilfe
-edit = Bearbeiten
 delete = Löschen
 error-save-failed = Änderungen konnten nicht gespeichert werden!
 saving-data = Daten werden gespeichert...
 activity = Aktivität
+export = Exportieren…
+error-export-failed = Skripte konnten nicht exportiert werden!
+loading-data = Aktivitäten werden geladen...
+error-load-failed = Aktivitäten konnten nicht geladen werden!
+import = Importieren…
+error-import-failed = Skripte konnten nicht importiert werden!
+import-skipped = Diese Aktivitäten wurden nicht gefunden und übersprungen:
+import-summary = Import mit Warnungen abgeschlossen
+import-missing-scripts = Diese Skripte wurden nicht gefunden:
+menu = Menü
+multi-select = Mehrere Aktivitäten gleichzeitig bearbeiten
+activity-script-count = { $name } — { $count }/{ $total }
+files-changed = Skripte wurden auf der Festplatte geändert. Neu laden verwirft ungespeicherte Änderungen.
+reload = Neu laden
+recover-title = Ungespeicherte Änderungen wiederherstellen?
+recover-detail = Einige Skriptzuweisungen wurden beim letzten Schließen von kas-selector nicht gespeichert.
+restore = Wiederherstellen
+discard = Verwerfen
+unexpected-root = Das Zielverzeichnis sieht nicht wie ein kactivitymanagerd-Aktivitätenordner aus — fortfahren?
+continue = Fortfahren
+confirm-remove-files = Beim Speichern werden diese Dateien gelöscht, die keine Verknüpfungen zu Ihren Skripten sind:
+test-run = Testlauf
+test-run-finished = Skript beendet mit Code { $code }
+error-test-run-failed = Das Skript konnte nicht ausgeführt werden!
+details = Skriptdetails
+details-target = Ziel
+details-target-exists = Ziel vorhanden
+details-size = Größe
+details-bytes = { $bytes } Bytes
+details-modified = Geändert
+details-executable = Ausführbar
+yes = Ja
+no = Nein
+create-script = Skript erstellen…
+error-create-script-failed = Das Skript konnte nicht erstellt werden!
+copy-details = Details kopieren
+close = Schließen
+confirm-overwrite-links = Beim Speichern werden diese Verknüpfungen ersetzt, die außerhalb von kas-selector geändert wurden:
+replace = Ersetzen
+sort-by-name = Nach Name sortieren
+about = Über
+about-comments = Skripte den Lebenszyklus-Ereignissen von KDE-Aktivitäten zuweisen.
+website = Webseite
+activity-manager-stopped = Der KDE-Aktivitätenmanager läuft nicht
+activity-manager-stopped-detail = Jetzt gespeicherte Skripte werden möglicherweise erst nach einem Neustart von kactivitymanagerd ausgeführt.
+title-summary = { title } — { $configured } von { $total } eingerichtet
+quit-button = _Beenden
+save-button = _Speichern
+help-button = _Hilfe
+status-folder = Ordner:
+status-script-name = Skriptname:
+open-folder = Ordner öffnen
+save-permission-denied = Zum Speichern sind Administratorrechte nötig
+save-permission-denied-detail = Dein Benutzer kann nicht in den Aktivitätsordner schreiben. Ein erneuter Versuch führt diesen Befehl aus:
+retry-as-admin = Als Administrator wiederholen
+broken-links = Einige Skripte fehlen
+confirm-delete-script = Skript für { $event } entfernen?
+confirm-delete-script-detail = Die Skriptdatei selbst bleibt erhalten. Die Änderung wird beim Speichern geschrieben.
+script-missing = Dieses Skript existiert nicht mehr. Wähle ein anderes oder entferne es.
+search-activities = Aktivitäten durchsuchen
+undo = Rückgängig (Strg+Z)
+redo = Wiederholen (Strg+Y)
+unsaved-changes = Änderungen vor dem Beenden speichern?
+unsaved-changes-detail = Geänderte Skriptzuweisungen wurden noch nicht gespeichert.
+save-and-quit = Speichern und beenden
+copy-scripts-from = Skripte von einer anderen Aktivität kopieren
+error-activities-unreachable = kactivities-cli konnte nicht erreicht werden, um deine Aktivitäten aufzulisten!
+filter-shell-scripts = Shell-Skripte
+filter-all-files = Alle Dateien
+error-script-not-a-file = Nur normale Dateien können als Skripte zugewiesen werden.
+error-script-not-shell = Skripte müssen auf .sh enden. Setze KAS_ALLOW_ANY_SCRIPT=1, um andere Dateien zu erlauben.
+not-executable = { $name } ist nicht ausführbar
+not-executable-detail = Der Aktivitätenmanager kann nur Skripte ausführen, die als ausführbar markiert sind.
+make-executable = Ausführbar machen
+error-make-executable-failed = Das Skript konnte nicht ausführbar gemacht werden!
+import-conflicts = Ereignisse mit bereits anderem Skript: { $count }
+import-conflicts-detail = Wähle, ob die importierten Skripte die hier zugewiesenen ersetzen. Ereignisse ohne Skript werden in jedem Fall ergänzt.
+keep-current-scripts = Aktuelle behalten
+use-imported-scripts = Importierte verwenden
+error-read-only-filesystem = Das Skript liegt auf einem schreibgeschützten Dateisystem und kann hier nicht ausführbar gemacht werden!
+sort-by-status = Laufende Aktivitäten zuerst
+clean-up-orphans = Verwaiste Aktivitäten aufräumen
+confirm-clean-up-orphans = { $count ->
+        [one] Skripte von { $count } entfernten Aktivität löschen?
+       *[other] Skripte von { $count } entfernten Aktivitäten löschen?
+    }
+confirm-clean-up-orphans-detail = Diese Ordner gehören zu Aktivitäten, die es nicht mehr gibt. Sie werden sofort mit ihrem gesamten Inhalt gelöscht:
+error-clean-up-failed = Verwaiste Aktivitäten konnten nicht aufgeräumt werden!
+reload-activities = Aktivitäten und Skripte neu laden
+confirm-discard-reload = Ungespeicherte Änderungen verwerfen und neu laden?
+script-syntax-error = { $name } enthält einen Syntaxfehler
+script-syntax-error-detail = Das Skript würde beim Aktivitätsereignis wahrscheinlich fehlschlagen. bash meldete:
+assign-anyway = Trotzdem zuweisen
+copy-scripts-to = Skripte kopieren nach…
+copy-scripts-to-detail = Skripte von { $name } kopieren nach:
+overwrite-existing-scripts = Vorhandene Skripte dieser Aktivitäten ersetzen
+copy = Kopieren
+not-executable-scripts = Einige Skripte sind nicht ausführbar
+fix-permissions = Berechtigungen korrigieren
+open-in-editor = Im Texteditor öffnen
+test-run-timed-out = Das Skript wurde nach { $seconds } Sekunden beendet
+language = Sprache
+no-activities = Keine Aktivitäten gefunden – läuft KDE Plasma?
+error-startup-failed = KDE-Aktivitätsskript-Auswahl konnte nicht gestartet werden!
+create-root = Der Aktivitätenordner existiert noch nicht.
+create-root-detail = { $path } jetzt erstellen? Dort werden die Skripte für jede Aktivität verknüpft.
+create-folder = Ordner erstellen
+assign-to-all = Allen Aktivitäten zuweisen
+confirm-assign-to-all = { $count ->
+        [one] Dieses Skript für „{ $event }“ in { $count } Aktivität verwenden?
+       *[other] Dieses Skript für „{ $event }“ in { $count } Aktivitäten verwenden?
+    }
+copy-path = Pfad kopieren
+add-script = Skript hinzufügen
+remove-script = Skript entfernen
+event-scripts = Skripte für dieses Ereignis
+event-script-count = { $count ->
+        [one] { $count } Skript
+       *[other] { $count } Skripte
+    }
+error-open-in-editor-failed = Das Skript konnte nicht in einem Editor geöffnet werden!
+confirm-replace-copies = Beim Speichern werden diese Kopien Ihrer Skripte durch Verknüpfungen ersetzt:
+error-save-nothing-changed = Auf dem Datenträger wurde nichts geändert.
+error-save-undone = Die bereits vorgenommenen Änderungen wurden rückgängig gemacht.
+error-save-incomplete = Einige Änderungen konnten nicht rückgängig gemacht werden. Prüfen Sie die unten genannten Aktivitätsordner.
@@ -3,13 +3,145 @@ event-activated = Activated
 event-deactivated = Deactivated
 event-started = Started
 event-stopped = Stopped
+event-suspended = Suspended
+event-resumed = Resumed
 open = Open
 cancel = Cancel
 quit = Quit
 save = Save
 help = Help
-edit = Edit
 delete = Delete
 error-save-failed = Could not save changes!
 saving-data = Saving data...
 activity = Activity
+export = Export…
+error-export-failed = Could not export scripts!
+loading-data = Loading activities...
+error-load-failed = Could not load activities!
+import = Import…
+error-import-failed = Could not import scripts!
+import-skipped = These activities were not found and were skipped:
+import-summary = Import finished with warnings
+import-missing-scripts = These scripts could not be found:
+menu = Menu
+multi-select = Edit several activities at once
+activity-script-count = { $name } — { $count }/{ $total }
+files-changed = Scripts changed on disk. Reloading discards unsaved edits.
+reload = Reload
+recover-title = Restore unsaved changes?
+recover-detail = Some script assignments were not saved the last time kas-selector closed.
+restore = Restore
+discard = Discard
+unexpected-root = The target directory does not look like a kactivitymanagerd activities folder — continue?
+continue = Continue
+confirm-remove-files = Saving will delete these files, which are not links to your scripts:
+test-run = Test run
+test-run-finished = Script exited with code { $code }
+error-test-run-failed = Could not run the script!
+details = Script details
+details-target = Target
+details-target-exists = Target exists
+details-size = Size
+details-bytes = { $bytes } bytes
+details-modified = Modified
+details-executable = Executable
+yes = Yes
+no = No
+create-script = Create script…
+error-create-script-failed = Could not create the script!
+copy-details = Copy details
+close = Close
+confirm-overwrite-links = Saving will replace these links, which were changed outside kas-selector:
+replace = Replace
+sort-by-name = Sort by name
+about = About
+about-comments = Assign scripts to KDE Activity lifecycle events.
+website = Website
+activity-manager-stopped = The KDE activity manager is not running
+activity-manager-stopped-detail = Scripts saved now may not run until kactivitymanagerd is restarted.
+title-summary = { title } — { $configured } of { $total ->
+        [one] { $total } activity
+       *[other] { $total } activities
+    } configured
+quit-button = _Quit
+save-button = _Save
+help-button = _Help
+status-folder = Folder:
+status-script-name = Script name:
+open-folder = Open folder
+save-permission-denied = Saving needs administrator rights
+save-permission-denied-detail = The activities folder can't be written by your user. Retrying will run this command:
+retry-as-admin = Retry as administrator
+broken-links = Some scripts are missing
+confirm-delete-script = Remove script for { $event }?
+confirm-delete-script-detail = The script file itself is kept. The change is written when you save.
+script-missing = This script no longer exists. Choose another one or remove it.
+search-activities = Search activities
+undo = Undo (Ctrl+Z)
+redo = Redo (Ctrl+Y)
+unsaved-changes = Save changes before quitting?
+unsaved-changes-detail = Script assignments you changed have not been saved yet.
+save-and-quit = Save and Quit
+copy-scripts-from = Copy scripts from another activity
+error-activities-unreachable = Could not reach kactivities-cli to list your activities!
+filter-shell-scripts = Shell scripts
+filter-all-files = All files
+error-script-not-a-file = Only regular files can be assigned as scripts.
+error-script-not-shell = Scripts must end in .sh. Set KAS_ALLOW_ANY_SCRIPT=1 to allow other files.
+not-executable = { $name } is not executable
+not-executable-detail = The activity manager can only run scripts that are marked executable.
+make-executable = Make Executable
+error-make-executable-failed = Could not make the script executable!
+import-conflicts = { $count ->
+        [one] { $count } event already has a different script
+       *[other] { $count } events already have a different script
+    }
+import-conflicts-detail = Choose whether the imported scripts replace the ones assigned here. Events without a script are filled in either way.
+keep-current-scripts = Keep Current
+use-imported-scripts = Use Imported
+error-read-only-filesystem = The script is on a read-only file system, so it can't be made executable here!
+sort-by-status = Running activities first
+clean-up-orphans = Clean up orphaned activities
+confirm-clean-up-orphans = { $count ->
+        [one] Delete the scripts of { $count } removed activity?
+       *[other] Delete the scripts of { $count } removed activities?
+    }
+confirm-clean-up-orphans-detail = These folders belong to activities that no longer exist. They are deleted with everything in them right away:
+error-clean-up-failed = Could not clean up orphaned activities!
+reload-activities = Reload activities and scripts
+confirm-discard-reload = Discard unsaved changes and reload?
+script-syntax-error = { $name } has a syntax error
+script-syntax-error-detail = The script would likely fail when the activity event runs. bash reported:
+assign-anyway = Assign anyway
+copy-scripts-to = Copy scripts to…
+copy-scripts-to-detail = Copy the scripts of { $name } to:
+overwrite-existing-scripts = Replace scripts these activities already have
+copy = Copy
+not-executable-scripts = Some scripts are not executable
+fix-permissions = Fix Permissions
+open-in-editor = Open in text editor
+test-run-timed-out = The script was stopped after { $seconds } seconds
+language = Language
+no-activities = No activities found — is KDE Plasma running?
+error-startup-failed = Could not start KDE Activity Script Selector!
+create-root = The activities folder doesn't exist yet.
+create-root-detail = Create { $path } now? Scripts are linked there for each activity.
+create-folder = Create folder
+assign-to-all = Assign to all activities
+confirm-assign-to-all = { $count ->
+        [one] Use this script for { $event } in { $count } activity?
+       *[other] Use this script for { $event } in { $count } activities?
+    }
+copy-path = Copy path
+add-script = Add script
+remove-script = Remove script
+event-scripts = Scripts for this event
+event-script-count = { $count ->
+        [one] { $count } script
+       *[other] { $count } scripts
+    }
+error-open-in-editor-failed = Could not open the script in an editor!
+confirm-replace-copies = Saving will replace these copies of your scripts with links:
+error-save-nothing-changed = Nothing on disk was changed.
+error-save-undone = The changes already made were undone.
+error-save-incomplete = Some changes could not be undone. Check the activity folders listed below.
@@ -3,13 +3,145 @@ event-activated = Activado
 event-deactivated = Desactivado
 event-started = Iniciado
 event-stopped = Detenido
+event-suspended = Suspendido
+event-resumed = Reanudado
 open = Abrir
 cancel = Cancelar
 quit = Salir
 save = Guardar
 help = Ayuda
-edit = Editar
 delete = Eliminar
 error-save-failed = ¡No se pudieron guardar los cambios!
 saving-data = Guardando datos...
 activity = Actividad
+export = Exportar…
+error-export-failed = ¡No se pudieron exportar los scripts!
+loading-data = Cargando actividades...
+error-load-failed = ¡No se pudieron cargar las actividades!
+import = Importar…
+error-import-failed = ¡No se pudieron importar los scripts!
+import-skipped = Estas actividades no se encontraron y se omitieron:
+import-summary = Importación completada con advertencias
+import-missing-scripts = No se encontraron estos scripts:
+menu = Menú
+multi-select = Editar varias actividades a la vez
+activity-script-count = { $name } — { $count }/{ $total }
+files-changed = Los scripts cambiaron en el disco. Recargar descarta los cambios sin guardar.
+reload = Recargar
+recover-title = ¿Restaurar los cambios sin guardar?
+recover-detail = Algunas asignaciones de scripts no se guardaron la última vez que se cerró kas-selector.
+restore = Restaurar
+discard = Descartar
+unexpected-root = El directorio de destino no parece una carpeta de actividades de kactivitymanagerd — ¿continuar?
+continue = Continuar
+confirm-remove-files = Al guardar se eliminarán estos archivos, que no son enlaces a sus scripts:
+test-run = Ejecución de prueba
+test-run-finished = El script terminó con el código { $code }
+error-test-run-failed = ¡No se pudo ejecutar el script!
+details = Detalles del script
+details-target = Destino
+details-target-exists = El destino existe
+details-size = Tamaño
+details-bytes = { $bytes } bytes
+details-modified = Modificado
+details-executable = Ejecutable
+yes = Sí
+no = No
+create-script = Crear script…
+error-create-script-failed = ¡No se pudo crear el script!
+copy-details = Copiar detalles
+close = Cerrar
+confirm-overwrite-links = Al guardar se reemplazarán estos enlaces, que se modificaron fuera de kas-selector:
+replace = Reemplazar
+sort-by-name = Ordenar por nombre
+about = Acerca de
+about-comments = Asigna scripts a los eventos del ciclo de vida de las actividades de KDE.
+website = Sitio web
+activity-manager-stopped = El gestor de actividades de KDE no se está ejecutando
+activity-manager-stopped-detail = Es posible que los scripts guardados ahora no se ejecuten hasta que se reinicie kactivitymanagerd.
+title-summary = { title } — { $configured } de { $total } { $total ->
+        [one] configurada
+       *[other] configuradas
+    }
+quit-button = _Salir
+save-button = _Guardar
+help-button = _Ayuda
+status-folder = Carpeta:
+status-script-name = Nombre del script:
+open-folder = Abrir carpeta
+save-permission-denied = Guardar requiere permisos de administrador
+save-permission-denied-detail = Tu usuario no puede escribir en la carpeta de actividades. Reintentar ejecutará este comando:
+retry-as-admin = Reintentar como administrador
+broken-links = Faltan algunos scripts
+confirm-delete-script = ¿Quitar el script de { $event }?
+confirm-delete-script-detail = El archivo del script se conserva. El cambio se escribe al guardar.
+script-missing = Este script ya no existe. Elige otro o quítalo.
+search-activities = Buscar actividades
+undo = Deshacer (Ctrl+Z)
+redo = Rehacer (Ctrl+Y)
+unsaved-changes = ¿Guardar los cambios antes de salir?
+unsaved-changes-detail = Las asignaciones de scripts que cambiaste aún no se han guardado.
+save-and-quit = Guardar y salir
+copy-scripts-from = Copiar scripts de otra actividad
+error-activities-unreachable = ¡No se pudo contactar con kactivities-cli para listar tus actividades!
+filter-shell-scripts = Scripts de shell
+filter-all-files = Todos los archivos
+error-script-not-a-file = Solo se pueden asignar archivos normales como scripts.
+error-script-not-shell = Los scripts deben terminar en .sh. Define KAS_ALLOW_ANY_SCRIPT=1 para permitir otros archivos.
+not-executable = { $name } no es ejecutable
+not-executable-detail = El gestor de actividades solo puede ejecutar scripts marcados como ejecutables.
+make-executable = Hacer ejecutable
+error-make-executable-failed = ¡No se pudo hacer ejecutable el script!
+import-conflicts = { $count ->
+        [one] { $count } evento ya tiene otro script
+       *[other] { $count } eventos ya tienen otro script
+    }
+import-conflicts-detail = Elige si los scripts importados reemplazan a los asignados aquí. Los eventos sin script se completan en ambos casos.
+keep-current-scripts = Mantener actuales
+use-imported-scripts = Usar importados
+error-read-only-filesystem = ¡El script está en un sistema de archivos de solo lectura, así que no se puede hacer ejecutable aquí!
+sort-by-status = Actividades en ejecución primero
+clean-up-orphans = Limpiar actividades huérfanas
+confirm-clean-up-orphans = { $count ->
+        [one] ¿Eliminar los scripts de { $count } actividad eliminada?
+       *[other] ¿Eliminar los scripts de { $count } actividades eliminadas?
+    }
+confirm-clean-up-orphans-detail = Estas carpetas pertenecen a actividades que ya no existen. Se eliminan de inmediato con todo su contenido:
+error-clean-up-failed = ¡No se pudieron limpiar las actividades huérfanas!
+reload-activities = Recargar actividades y scripts
+confirm-discard-reload = ¿Descartar los cambios sin guardar y recargar?
+script-syntax-error = { $name } tiene un error de sintaxis
+script-syntax-error-detail = Es probable que el script falle cuando se produzca el evento de la actividad. bash informó:
+assign-anyway = Asignar de todos modos
+copy-scripts-to = Copiar scripts a…
+copy-scripts-to-detail = Copiar los scripts de { $name } a:
+overwrite-existing-scripts = Reemplazar los scripts que ya tienen estas actividades
+copy = Copiar
+not-executable-scripts = Algunos scripts no son ejecutables
+fix-permissions = Corregir permisos
+open-in-editor = Abrir en el editor de texto
+test-run-timed-out = El script se detuvo tras { $seconds } segundos
+language = Idioma
+no-activities = No se encontraron actividades — ¿se está ejecutando KDE Plasma?
+error-startup-failed = ¡No se pudo iniciar el Selector de Scripts de Actividad KDE!
+create-root = La carpeta de actividades aún no existe.
+create-root-detail = ¿Crear { $path } ahora? Los scripts se enlazan allí para cada actividad.
+create-folder = Crear carpeta
+assign-to-all = Asignar a todas las actividades
+confirm-assign-to-all = { $count ->
+        [one] ¿Usar este script para «{ $event }» en { $count } actividad?
+       *[other] ¿Usar este script para «{ $event }» en { $count } actividades?
+    }
+copy-path = Copiar ruta
+add-script = Añadir script
+remove-script = Quitar script
+event-scripts = Scripts de este evento
+event-script-count = { $count ->
+        [one] { $count } script
+       *[other] { $count } scripts
+    }
+error-open-in-editor-failed = ¡No se pudo abrir el script en un editor!
+confirm-replace-copies = Al guardar, estas copias de sus scripts se sustituirán por enlaces:
+error-save-nothing-changed = No se cambió nada en el disco.
+error-save-undone = Se deshicieron los cambios ya realizados.
+error-save-incomplete = Algunos cambios no se pudieron deshacer. Revisa las carpetas de actividades indicadas abajo.
@@ -3,13 +3,145 @@ event-activated = Activé
 event-deactivated = Désactivé
 event-started = Démarré
 event-stopped = Arrêté
+event-suspended = Suspendu
+event-resumed = Repris
 open = Ouvrir
 cancel = Annuler
 quit = Quitter
 save = Enregistrer
 help = Aide
-edit = Modifier
 delete = Supprimer
 error-save-failed = Impossible d'enregistrer les modifications !
 saving-data = Enregistrement des données...
 activity = Activité
+export = Exporter…
+error-export-failed = Impossible d'exporter les scripts !
+loading-data = Chargement des activités...
+error-load-failed = Impossible de charger les activités !
+import = Importer…
+error-import-failed = Impossible d'importer les scripts !
+import-skipped = Ces activités sont introuvables et ont été ignorées :
+import-summary = Importation terminée avec des avertissements
+import-missing-scripts = Ces scripts sont introuvables :
+menu = Menu
+multi-select = Modifier plusieurs activités à la fois
+activity-script-count = { $name } — { $count }/{ $total }
+files-changed = Les scripts ont changé sur le disque. Recharger annule les modifications non enregistrées.
+reload = Recharger
+recover-title = Restaurer les modifications non enregistrées ?
+recover-detail = Certaines affectations de scripts n'ont pas été enregistrées lors de la dernière fermeture de kas-selector.
+restore = Restaurer
+discard = Ignorer
+unexpected-root = Le dossier cible ne ressemble pas à un dossier d'activités kactivitymanagerd — continuer ?
+continue = Continuer
+confirm-remove-files = L'enregistrement supprimera ces fichiers, qui ne sont pas des liens vers vos scripts :
+test-run = Exécution de test
+test-run-finished = Le script s'est terminé avec le code { $code }
+error-test-run-failed = Impossible d'exécuter le script !
+details = Détails du script
+details-target = Cible
+details-target-exists = La cible existe
+details-size = Taille
+details-bytes = { $bytes } octets
+details-modified = Modifié
+details-executable = Exécutable
+yes = Oui
+no = Non
+create-script = Créer un script…
+error-create-script-failed = Impossible de créer le script !
+copy-details = Copier les détails
+close = Fermer
+confirm-overwrite-links = L'enregistrement remplacera ces liens, qui ont été modifiés en dehors de kas-selector :
+replace = Remplacer
+sort-by-name = Trier par nom
+about = À propos
+about-comments = Associer des scripts aux événements du cycle de vie des activités KDE.
+website = Site web
+activity-manager-stopped = Le gestionnaire d'activités KDE n'est pas en cours d'exécution
+activity-manager-stopped-detail = Les scripts enregistrés maintenant risquent de ne pas s'exécuter avant le redémarrage de kactivitymanagerd.
+title-summary = { title } — { $configured } sur { $total } { $configured ->
+        [one] configurée
+       *[other] configurées
+    }
+quit-button = _Quitter
+save-button = _Enregistrer
+help-button = _Aide
+status-folder = Dossier :
+status-script-name = Nom du script :
+open-folder = Ouvrir le dossier
+save-permission-denied = L'enregistrement nécessite des droits d'administrateur
+save-permission-denied-detail = Votre utilisateur ne peut pas écrire dans le dossier des activités. Réessayer exécutera cette commande :
+retry-as-admin = Réessayer en tant qu'administrateur
+broken-links = Certains scripts sont introuvables
+confirm-delete-script = Retirer le script pour { $event } ?
+confirm-delete-script-detail = Le fichier du script est conservé. La modification est écrite à l'enregistrement.
+script-missing = Ce script n'existe plus. Choisissez-en un autre ou retirez-le.
+search-activities = Rechercher des activités
+undo = Annuler (Ctrl+Z)
+redo = Rétablir (Ctrl+Y)
+unsaved-changes = Enregistrer les modifications avant de quitter ?
+unsaved-changes-detail = Les affectations de scripts modifiées n'ont pas encore été enregistrées.
+save-and-quit = Enregistrer et quitter
+copy-scripts-from = Copier les scripts d'une autre activité
+error-activities-unreachable = Impossible de joindre kactivities-cli pour lister vos activités !
+filter-shell-scripts = Scripts shell
+filter-all-files = Tous les fichiers
+error-script-not-a-file = Seuls des fichiers ordinaires peuvent être affectés comme scripts.
+error-script-not-shell = Les scripts doivent se terminer par .sh. Définissez KAS_ALLOW_ANY_SCRIPT=1 pour autoriser d'autres fichiers.
+not-executable = { $name } n'est pas exécutable
+not-executable-detail = Le gestionnaire d'activités ne peut exécuter que les scripts marqués comme exécutables.
+make-executable = Rendre exécutable
+error-make-executable-failed = Impossible de rendre le script exécutable !
+import-conflicts = { $count ->
+        [one] { $count } événement a déjà un autre script
+       *[other] { $count } événements ont déjà un autre script
+    }
+import-conflicts-detail = Choisissez si les scripts importés remplacent ceux affectés ici. Les événements sans script sont complétés dans tous les cas.
+keep-current-scripts = Garder les actuels
+use-imported-scripts = Utiliser les importés
+error-read-only-filesystem = Le script se trouve sur un système de fichiers en lecture seule et ne peut pas être rendu exécutable ici !
+sort-by-status = Activités en cours d’abord
+clean-up-orphans = Nettoyer les activités orphelines
+confirm-clean-up-orphans = { $count ->
+        [one] Supprimer les scripts de { $count } activité supprimée ?
+       *[other] Supprimer les scripts de { $count } activités supprimées ?
+    }
+confirm-clean-up-orphans-detail = Ces dossiers appartiennent à des activités qui n’existent plus. Ils sont supprimés immédiatement avec tout leur contenu :
+error-clean-up-failed = Impossible de nettoyer les activités orphelines !
+reload-activities = Recharger les activités et les scripts
+confirm-discard-reload = Abandonner les modifications non enregistrées et recharger ?
+script-syntax-error = { $name } contient une erreur de syntaxe
+script-syntax-error-detail = Le script échouera probablement lors de l’événement de l’activité. bash a signalé :
+assign-anyway = Attribuer quand même
+copy-scripts-to = Copier les scripts vers…
+copy-scripts-to-detail = Copier les scripts de { $name } vers :
+overwrite-existing-scripts = Remplacer les scripts que ces activités ont déjà
+copy = Copier
+not-executable-scripts = Certains scripts ne sont pas exécutables
+fix-permissions = Corriger les permissions
+open-in-editor = Ouvrir dans l’éditeur de texte
+test-run-timed-out = Le script a été arrêté après { $seconds } secondes
+language = Langue
+no-activities = Aucune activité trouvée — KDE Plasma est-il en cours d'exécution ?
+error-startup-failed = Impossible de démarrer le sélecteur de script d'activité KDE !
+create-root = Le dossier des activités n'existe pas encore.
+create-root-detail = Créer { $path } maintenant ? Les scripts y sont liés pour chaque activité.
+create-folder = Créer le dossier
+assign-to-all = Assigner à toutes les activités
+confirm-assign-to-all = { $count ->
+        [one] Utiliser ce script pour « { $event } » dans { $count } activité ?
+       *[other] Utiliser ce script pour « { $event } » dans { $count } activités ?
+    }
+copy-path = Copier le chemin
+add-script = Ajouter un script
+remove-script = Retirer le script
+event-scripts = Scripts de cet événement
+event-script-count = { $count ->
+        [one] { $count } script
+       *[other] { $count } scripts
+    }
+error-open-in-editor-failed = Impossible d'ouvrir le script dans un éditeur !
+confirm-replace-copies = L'enregistrement remplacera ces copies de vos scripts par des liens :
+error-save-nothing-changed = Rien n'a été modifié sur le disque.
+error-save-undone = Les modifications déjà effectuées ont été annulées.
+error-save-incomplete = Certaines modifications n'ont pas pu être annulées. Vérifiez les dossiers d'activité indiqués ci-dessous.
@@ -3,13 +3,133 @@ event-activated = Активировано
 event-deactivated = Деактивировано
 event-started = Запущено
 event-stopped = Остановлено
+event-suspended = Приостановлено
+event-resumed = Возобновлено
 open = Открыть
 cancel = Отмена
 quit = Выход
 save = Сохранить
 help = Помощь
-edit = Редактировать
 delete = Удалить
 error-save-failed = Не удалось сохранить изменения!
 saving-data = Сохранение данных...
 activity = Активность
+export = Экспорт…
+error-export-failed = Не удалось экспортировать скрипты!
+loading-data = Загрузка активностей...
+error-load-failed = Не удалось загрузить активности!
+import = Импорт…
+error-import-failed = Не удалось импортировать скрипты!
+import-skipped = Эти активности не найдены и были пропущены:
+import-summary = Импорт завершён с предупреждениями
+import-missing-scripts = Эти скрипты не найдены:
+menu = Меню
+multi-select = Редактировать несколько активностей одновременно
+activity-script-count = { $name } — { $count }/{ $total }
+files-changed = Скрипты изменились на диске. Перезагрузка отменит несохранённые изменения.
+reload = Перезагрузить
+recover-title = Восстановить несохранённые изменения?
+recover-detail = Некоторые назначения скриптов не были сохранены при последнем закрытии kas-selector.
+restore = Восстановить
+discard = Отменить
+unexpected-root = Целевой каталог не похож на папку активностей kactivitymanagerd — продолжить?
+continue = Продолжить
+confirm-remove-files = При сохранении будут удалены эти файлы, которые не являются ссылками на ваши скрипты:
+test-run = Тестовый запуск
+test-run-finished = Скрипт завершился с кодом { $code }
+error-test-run-failed = Не удалось запустить скрипт!
+details = Сведения о скрипте
+details-target = Цель
+details-target-exists = Цель существует
+details-size = Размер
+details-bytes = { $bytes } байт
+details-modified = Изменён
+details-executable = Исполняемый
+yes = Да
+no = Нет
+create-script = Создать скрипт…
+error-create-script-failed = Не удалось создать скрипт!
+copy-details = Копировать подробности
+close = Закрыть
+confirm-overwrite-links = При сохранении будут заменены эти ссылки, изменённые вне kas-selector:
+replace = Заменить
+sort-by-name = Сортировать по имени
+about = О программе
+about-comments = Назначение скриптов событиям жизненного цикла комнат KDE.
+website = Веб-сайт
+activity-manager-stopped = Диспетчер комнат KDE не запущен
+activity-manager-stopped-detail = Сохранённые сейчас скрипты могут не запускаться до перезапуска kactivitymanagerd.
+title-summary = { title } — настроено { $configured } из { $total }
+quit-button = _Выход
+save-button = _Сохранить
+help-button = _Помощь
+status-folder = Папка:
+status-script-name = Имя скрипта:
+open-folder = Открыть папку
+save-permission-denied = Для сохранения нужны права администратора
+save-permission-denied-detail = Ваш пользователь не может писать в папку активностей. Повторная попытка выполнит эту команду:
+retry-as-admin = Повторить от имени администратора
+broken-links = Некоторые скрипты отсутствуют
+confirm-delete-script = Удалить скрипт для события «{ $event }»?
+confirm-delete-script-detail = Сам файл скрипта сохраняется. Изменение записывается при сохранении.
+script-missing = Этот скрипт больше не существует. Выберите другой или удалите его.
+search-activities = Поиск активностей
+undo = Отменить (Ctrl+Z)
+redo = Повторить (Ctrl+Y)
+unsaved-changes = Сохранить изменения перед выходом?
+unsaved-changes-detail = Изменённые назначения скриптов ещё не сохранены.
+save-and-quit = Сохранить и выйти
+copy-scripts-from = Скопировать скрипты из другой активности
+error-activities-unreachable = Не удалось обратиться к kactivities-cli, чтобы получить список активностей!
+filter-shell-scripts = Скрипты оболочки
+filter-all-files = Все файлы
+error-script-not-a-file = Назначать скриптами можно только обычные файлы.
+error-script-not-shell = Имя скрипта должно оканчиваться на .sh. Задайте KAS_ALLOW_ANY_SCRIPT=1, чтобы разрешить другие файлы.
+not-executable = { $name } не является исполняемым
+not-executable-detail = Диспетчер активностей может запускать только скрипты, помеченные как исполняемые.
+make-executable = Сделать исполняемым
+error-make-executable-failed = Не удалось сделать скрипт исполняемым!
+import-conflicts = Событий с уже назначенным другим скриптом: { $count }
+import-conflicts-detail = Выберите, заменят ли импортированные скрипты уже назначенные. События без скрипта будут заполнены в любом случае.
+keep-current-scripts = Оставить текущие
+use-imported-scripts = Использовать импортированные
+error-read-only-filesystem = Скрипт находится в файловой системе только для чтения, поэтому его нельзя сделать исполняемым!
+sort-by-status = Сначала запущенные активности
+clean-up-orphans = Очистить удалённые активности
+confirm-clean-up-orphans = { $count ->
+        [one] Удалить скрипты { $count } удалённой активности?
+       *[other] Удалить скрипты удалённых активностей ({ $count })?
+    }
+confirm-clean-up-orphans-detail = Эти папки относятся к активностям, которых больше нет. Они будут сразу удалены вместе со всем содержимым:
+error-clean-up-failed = Не удалось очистить удалённые активности!
+reload-activities = Перезагрузить активности и скрипты
+confirm-discard-reload = Отменить несохранённые изменения и перезагрузить?
+script-syntax-error = В { $name } есть синтаксическая ошибка
+script-syntax-error-detail = Скрипт, скорее всего, завершится с ошибкой при событии активности. bash сообщил:
+assign-anyway = Всё равно назначить
+copy-scripts-to = Копировать скрипты в…
+copy-scripts-to-detail = Копировать скрипты { $name } в:
+overwrite-existing-scripts = Заменить скрипты, которые уже есть у этих активностей
+copy = Копировать
+not-executable-scripts = Некоторые скрипты не являются исполняемыми
+fix-permissions = Исправить права
+open-in-editor = Открыть в текстовом редакторе
+test-run-timed-out = Скрипт остановлен через { $seconds } с
+language = Язык
+no-activities = Активности не найдены — запущена ли KDE Plasma?
+error-startup-failed = Не удалось запустить выбор скрипта активности KDE!
+create-root = Папка активностей ещё не существует.
+create-root-detail = Создать { $path } сейчас? В ней связываются скрипты для каждой активности.
+create-folder = Создать папку
+assign-to-all = Назначить всем активностям
+confirm-assign-to-all = Использовать этот скрипт для события «{ $event }» в активностях ({ $count })?
+copy-path = Копировать путь
+add-script = Добавить скрипт
+remove-script = Убрать скрипт
+event-scripts = Скрипты этого события
+event-script-count = Скриптов: { $count }
+error-open-in-editor-failed = Не удалось открыть скрипт в редакторе!
+confirm-replace-copies = При сохранении эти копии ваших скриптов будут заменены ссылками:
+error-save-nothing-changed = Ничего на диске не изменилось.
+error-save-undone = Уже внесённые изменения были отменены.
+error-save-incomplete = Некоторые изменения не удалось отменить. Проверьте папки действий, указанные ниже.
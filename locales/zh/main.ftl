@@ -3,13 +3,130 @@ event-activated = 已激活
 event-deactivated = 已停用
 event-started = 已启动
 event-stopped = 已停止
+event-suspended = 已挂起
+event-resumed = 已恢复
 open = 打开
 cancel = 取消
 quit = 退出
 save = 保存
 help = 帮助
-edit = 编辑
 delete = 删除
 error-save-failed = 无法保存更改！
 saving-data = 正在保存数据...
 activity = 活动
+export = 导出…
+error-export-failed = 无法导出脚本！
+loading-data = 正在加载活动...
+error-load-failed = 无法加载活动！
+import = 导入…
+error-import-failed = 无法导入脚本！
+import-skipped = 未找到以下活动，已跳过：
+import-summary = 导入完成，但有警告
+import-missing-scripts = 找不到以下脚本：
+menu = 菜单
+multi-select = 同时编辑多个活动
+activity-script-count = { $name } — { $count }/{ $total }
+files-changed = 磁盘上的脚本已更改。重新加载将丢弃未保存的修改。
+reload = 重新加载
+recover-title = 恢复未保存的更改？
+recover-detail = 上次关闭 kas-selector 时有部分脚本分配未保存。
+restore = 恢复
+discard = 丢弃
+unexpected-root = 目标目录看起来不像 kactivitymanagerd 活动文件夹——是否继续？
+continue = 继续
+confirm-remove-files = 保存将删除以下文件，它们不是指向您脚本的链接：
+test-run = 试运行
+test-run-finished = 脚本退出，代码 { $code }
+error-test-run-failed = 无法运行脚本！
+details = 脚本详情
+details-target = 目标
+details-target-exists = 目标存在
+details-size = 大小
+details-bytes = { $bytes } 字节
+details-modified = 修改时间
+details-executable = 可执行
+yes = 是
+no = 否
+create-script = 创建脚本…
+error-create-script-failed = 无法创建脚本！
+copy-details = 复制详情
+close = 关闭
+confirm-overwrite-links = 保存将替换以下在 kas-selector 之外被更改的链接：
+replace = 替换
+sort-by-name = 按名称排序
+about = 关于
+about-comments = 为 KDE 活动的生命周期事件分配脚本。
+website = 网站
+activity-manager-stopped = KDE 活动管理器未运行
+activity-manager-stopped-detail = 现在保存的脚本可能要等到 kactivitymanagerd 重启后才会运行。
+title-summary = { title } — 已配置 { $configured }/{ $total }
+quit-button = 退出(_Q)
+save-button = 保存(_S)
+help-button = 帮助(_H)
+status-folder = 文件夹：
+status-script-name = 脚本名称：
+open-folder = 打开文件夹
+save-permission-denied = 保存需要管理员权限
+save-permission-denied-detail = 当前用户无法写入活动文件夹。重试将运行以下命令：
+retry-as-admin = 以管理员身份重试
+broken-links = 部分脚本缺失
+confirm-delete-script = 移除“{ $event }”的脚本？
+confirm-delete-script-detail = 脚本文件本身会保留。更改将在保存时写入。
+script-missing = 此脚本已不存在。请选择其他脚本或将其移除。
+search-activities = 搜索活动
+undo = 撤销 (Ctrl+Z)
+redo = 重做 (Ctrl+Y)
+unsaved-changes = 退出前保存更改吗？
+unsaved-changes-detail = 您更改的脚本分配尚未保存。
+save-and-quit = 保存并退出
+copy-scripts-from = 从其他活动复制脚本
+error-activities-unreachable = 无法访问 kactivities-cli 来列出您的活动！
+filter-shell-scripts = Shell 脚本
+filter-all-files = 所有文件
+error-script-not-a-file = 只能将普通文件分配为脚本。
+error-script-not-shell = 脚本必须以 .sh 结尾。设置 KAS_ALLOW_ANY_SCRIPT=1 以允许其他文件。
+not-executable = { $name } 不可执行
+not-executable-detail = 活动管理器只能运行标记为可执行的脚本。
+make-executable = 设为可执行
+error-make-executable-failed = 无法将脚本设为可执行！
+import-conflicts = { $count } 个事件已分配了其他脚本
+import-conflicts-detail = 选择是否用导入的脚本替换此处已分配的脚本。没有脚本的事件在两种情况下都会被填充。
+keep-current-scripts = 保留当前
+use-imported-scripts = 使用导入的
+error-read-only-filesystem = 脚本位于只读文件系统上，因此无法在此设为可执行！
+sort-by-status = 正在运行的活动优先
+clean-up-orphans = 清理孤立的活动
+confirm-clean-up-orphans = 删除 { $count } 个已移除活动的脚本？
+confirm-clean-up-orphans-detail = 这些文件夹属于已不存在的活动。它们及其中的所有内容将立即被删除：
+error-clean-up-failed = 无法清理孤立的活动！
+reload-activities = 重新加载活动和脚本
+confirm-discard-reload = 放弃未保存的更改并重新加载？
+script-syntax-error = { $name } 存在语法错误
+script-syntax-error-detail = 活动事件触发时该脚本很可能会失败。bash 报告：
+assign-anyway = 仍然分配
+copy-scripts-to = 复制脚本到…
+copy-scripts-to-detail = 将 { $name } 的脚本复制到：
+overwrite-existing-scripts = 替换这些活动已有的脚本
+copy = 复制
+not-executable-scripts = 部分脚本不可执行
+fix-permissions = 修复权限
+open-in-editor = 在文本编辑器中打开
+test-run-timed-out = 脚本在 { $seconds } 秒后被停止
+language = 语言
+no-activities = 未找到任何活动——KDE Plasma 是否正在运行？
+error-startup-failed = 无法启动 KDE 活动脚本选择器！
+create-root = 活动文件夹尚不存在。
+create-root-detail = 现在创建 { $path } 吗？每个活动的脚本都链接在那里。
+create-folder = 创建文件夹
+assign-to-all = 分配给所有活动
+confirm-assign-to-all = 在 { $count } 个活动中为“{ $event }”使用此脚本？
+copy-path = 复制路径
+add-script = 添加脚本
+remove-script = 移除脚本
+event-scripts = 此事件的脚本
+event-script-count = { $count } 个脚本
+error-open-in-editor-failed = 无法在编辑器中打开脚本！
+confirm-replace-copies = 保存时将用链接替换这些脚本副本：
+error-save-nothing-changed = 磁盘上的内容没有任何更改。
+error-save-undone = 已进行的更改已被撤销。
+error-save-incomplete = 部分更改无法撤销。请检查下面列出的活动文件夹。
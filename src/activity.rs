@@ -1,34 +1,114 @@
 use std::{
-    collections::HashMap,
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
     fs::{self, read_link},
-    os::unix::fs::symlink,
-    path::{Path, PathBuf},
-    process::Command,
-    sync::LazyLock,
+    io,
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::{Component, Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use derive_getters::Getters;
-use regex::Regex;
-use strum::{Display, EnumIter, IntoEnumIterator, IntoStaticStr};
+use derive_more::Deref;
+use derive_new::new;
+use feruca::{Collator, Tailoring};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
 
-use crate::{error, locale, shell_script_filename::ShellScriptFilename};
+use crate::{
+    activity_source::{ActivitySource, CliSource, ListedActivity, SourceKind},
+    error,
+    file_system::{EntryKind, FileSystem, RealFileSystem},
+    locale,
+    shell_script_filename::ShellScriptFilename,
+};
+
+pub type EventMap = HashMap<ActivityEvent, Scripts>;
+pub type ScriptMap = HashMap<String, EventMap>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deref, Serialize, Deserialize)]
+#[serde(from = "OneOrMany", into = "OneOrMany")]
+pub struct Scripts(Vec<PathBuf>);
+
+impl From<PathBuf> for Scripts {
+    fn from(script: PathBuf) -> Self {
+        Self(vec![script])
+    }
+}
+
+impl From<Vec<PathBuf>> for Scripts {
+    fn from(scripts: Vec<PathBuf>) -> Self {
+        Self(scripts)
+    }
+}
+
+/// How [`Scripts`] are written, a lone script as a plain path like before events had several.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(PathBuf),
+    Many(Vec<PathBuf>),
+}
+
+impl From<OneOrMany> for Scripts {
+    fn from(scripts: OneOrMany) -> Self {
+        match scripts {
+            OneOrMany::One(script) => script.into(),
+            OneOrMany::Many(scripts) => scripts.into(),
+        }
+    }
+}
+
+impl From<Scripts> for OneOrMany {
+    fn from(scripts: Scripts) -> Self {
+        match <[PathBuf; 1]>::try_from(scripts.0) {
+            Ok([script]) => Self::One(script),
+            Err(scripts) => Self::Many(scripts),
+        }
+    }
+}
 
-type EventMap = HashMap<ActivityEvent, PathBuf>;
-type ScriptMap = HashMap<String, EventMap>;
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum LinkMode {
+    #[default]
+    Symlink,
+    RelativeSymlink,
+    /// A copy of the script, which keeps working if the original is moved or unmounted.
+    Copy,
+}
 
-#[allow(clippy::expect_used)]
-static ACTIVITY_DATA_RX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^\s*\[\w+\]\s+(?P<id>[a-f0-9\-]+)\s+(?P<name>.+?)\s+\([^\n]+\)\s*$")
-        .expect("ValidRx")
-});
+/// Directory under the root where links are made before being moved into place.
+pub const STAGING_DIR: &str = ".kas-staging";
+/// Directory under the staging area holding the entries a save replaces or removes, so a
+/// failed save can put them back.
+const PREVIOUS_DIR: &str = ".previous";
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, IntoStaticStr)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EnumIter,
+    EnumString,
+    Display,
+    IntoStaticStr,
+    Serialize,
+    Deserialize,
+)]
 #[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
 pub enum ActivityEvent {
     Activated,
     Deactivated,
     Started,
     Stopped,
+    Suspended,
+    Resumed,
 }
 
 impl ActivityEvent {
@@ -39,69 +119,226 @@ impl ActivityEvent {
             Self::Deactivated => K::EventDeactivated,
             Self::Started => K::EventStarted,
             Self::Stopped => K::EventStopped,
+            Self::Suspended => K::EventSuspended,
+            Self::Resumed => K::EventResumed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptStatus {
+    Unassigned,
+    Ok,
+    Missing,
+}
+
+#[derive(Debug, Getters)]
+pub struct ScriptMetadata {
+    target: PathBuf,
+    target_exists: bool,
+    size: Option<u64>,
+    modified: Option<SystemTime>,
+    executable: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManagedEntry {
+    Link,
+    /// A regular file with the same content as the assigned script.
+    OwnedCopy,
+    Foreign,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString, Serialize)]
+#[strum(serialize_all = "UPPERCASE")]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityState {
+    Current,
+    Running,
+    Starting,
+    Stopping,
+    Stopped,
+    #[default]
+    #[strum(disabled)]
+    Unknown,
+}
+
+impl ActivityState {
+    pub const fn rank(self) -> u8 {
+        match self {
+            Self::Current => 0,
+            Self::Running | Self::Starting => 1,
+            Self::Stopping | Self::Stopped | Self::Unknown => 2,
         }
     }
 }
 
-#[derive(Debug, Getters, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    ByName,
+    ByStatus,
+    ByStatusThenName,
+}
+
+pub fn sort_activities(activities: &mut [Activity], order: SortOrder) {
+    match order {
+        SortOrder::ByName => activities.sort_by(Activity::compare_by_name),
+        SortOrder::ByStatus => activities.sort_by_key(|a| a.state.rank()),
+        SortOrder::ByStatusThenName => activities.sort_by(|a, b| {
+            a.state
+                .rank()
+                .cmp(&b.state.rank())
+                .then_with(|| a.compare_by_name(b))
+        }),
+    }
+}
+
+#[derive(Debug, Getters, Clone, new)]
 pub struct Activity {
     name: String,
     id: String,
     #[getter(skip)]
     event_scripts: EventMap,
+    #[new(default)]
+    #[getter(copy)]
+    state: ActivityState,
+    #[new(default)]
+    icon: String,
 }
 
 impl Activity {
     pub fn get_script(&self, event: &ActivityEvent) -> Option<&PathBuf> {
-        self.event_scripts.get(event)
+        self.event_scripts.get(event)?.first()
+    }
+    pub fn scripts(&self, event: &ActivityEvent) -> &[PathBuf] {
+        self.event_scripts
+            .get(event)
+            .map(|scripts| scripts.as_slice())
+            .unwrap_or_default()
     }
     pub fn set_script(&mut self, event: ActivityEvent, script: PathBuf) {
-        self.event_scripts.insert(event, script);
+        self.set_scripts(event, vec![script]);
+    }
+    pub fn set_scripts(&mut self, event: ActivityEvent, scripts: Vec<PathBuf>) {
+        if scripts.is_empty() {
+            self.event_scripts.remove(&event);
+        } else {
+            self.event_scripts.insert(event, scripts.into());
+        }
+    }
+    pub fn add_script(&mut self, event: ActivityEvent, script: PathBuf) {
+        let scripts = &mut self.event_scripts.entry(event).or_default().0;
+        if !scripts.contains(&script) {
+            scripts.push(script);
+        }
+    }
+    pub fn remove_script(&mut self, event: ActivityEvent, script: &Path) {
+        let mut scripts = self.scripts(&event).to_vec();
+        scripts.retain(|s| s != script);
+        self.set_scripts(event, scripts);
     }
     pub fn delete_script(&mut self, event: ActivityEvent) {
         self.event_scripts.remove(&event);
     }
+    pub fn scripts_by_event(&self) -> BTreeMap<ActivityEvent, Scripts> {
+        self.event_scripts
+            .iter()
+            .map(|(event, scripts)| (*event, scripts.clone()))
+            .collect()
+    }
+    pub fn copy_scripts_from(&mut self, other: &Self) {
+        self.merge_scripts_from(other, true);
+    }
+    pub fn merge_scripts_from(&mut self, other: &Self, overwrite: bool) {
+        for (event, script) in &other.event_scripts {
+            if overwrite || !self.event_scripts.contains_key(event) {
+                self.event_scripts.insert(*event, script.clone());
+            }
+        }
+    }
+    pub fn script_status(&self, event: &ActivityEvent) -> ScriptStatus {
+        let scripts = self.scripts(event);
+        if scripts.is_empty() {
+            ScriptStatus::Unassigned
+        } else if scripts.iter().all(|script| script.exists()) {
+            ScriptStatus::Ok
+        } else {
+            ScriptStatus::Missing
+        }
+    }
+    pub fn script_count(&self) -> usize {
+        self.event_scripts.len()
+    }
     pub fn from_env(
         root_folder: &Path,
         script_filename: &ShellScriptFilename,
-    ) -> Result<Vec<Self>, error::Application> {
-        let output = Command::new("kactivities-cli")
-            .arg("--list-activities")
-            .output()
-            .map_err(|e| error::Application::CommandFailed {
-                command: "kactivities-cli",
-                error_text: e.to_string(),
-            })?;
-
-        if !output.status.success() {
-            let error_text = String::from_utf8_lossy(&output.stderr).to_string();
-            return Err(error::Application::CommandFailed {
-                command: "kactivities-cli",
-                error_text,
-            });
+    ) -> Result<(Vec<Self>, Vec<String>), error::Application> {
+        Self::from_source(
+            &SourceKind::from_env()?,
+            &RealFileSystem,
+            root_folder,
+            script_filename,
+        )
+    }
+    pub fn from_source(
+        source: &impl ActivitySource,
+        file_system: &impl FileSystem,
+        root: &Path,
+        script_filename: &ShellScriptFilename,
+    ) -> Result<(Vec<Self>, Vec<String>), error::Application> {
+        let listed = source.list_activities()?;
+        let scripts = Self::load_scripts(file_system, root, script_filename)?;
+        let mut orphans: Vec<String> = scripts.keys().cloned().collect();
+        let activities = Self::from_listed(listed, &scripts);
+        orphans.retain(|id| !activities.iter().any(|a| a.id() == id));
+        orphans.sort();
+        Ok((activities, orphans))
+    }
+    pub fn remove_orphans(
+        file_system: &impl FileSystem,
+        root: &Path,
+        orphans: &[String],
+    ) -> Result<(), error::Application> {
+        for id in orphans {
+            // Ids come from folder names, but never let one reach outside the root
+            let mut components = Path::new(id).components();
+            if !matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(_)), None)
+            ) {
+                return Err(error::InvalidValue {
+                    category: "orphaned activity id",
+                    value: id.clone(),
+                });
+            }
+            file_system
+                .remove_dir_all(&root.join(id))
+                .map_err(|e| error::Io {
+                    context: "removing orphaned activity folder",
+                    source: e,
+                })?;
         }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let scripts = Self::load_scripts(root_folder, script_filename)?;
-        Self::from_activity_data(&stdout, scripts)
+        Ok(())
     }
     fn load_scripts(
+        file_system: &impl FileSystem,
         root: &Path,
         script_filename: &ShellScriptFilename,
     ) -> Result<ScriptMap, error::Application> {
         let mut scripts = ScriptMap::new();
-        for entry in fs::read_dir(root).map_err(|e| error::InvalidValue {
-            category: "reading root script directory",
-            value: e.to_string(),
-        })? {
-            let activity_dir = entry
-                .map_err(|e| error::InvalidValue {
-                    category: "reading entry in root script directory",
-                    value: e.to_string(),
-                })?
-                .path();
-
-            if !activity_dir.is_dir() {
+        let entries = match file_system.read_dir(root) {
+            Ok(entries) => entries,
+            // Nothing has been assigned yet on a fresh install
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(scripts),
+            Err(e) => {
+                return Err(error::Io {
+                    context: "reading root script directory",
+                    source: e,
+                });
+            }
+        };
+        for activity_dir in entries {
+            if !file_system.is_dir(&activity_dir) {
                 continue;
             }
             let activity_id = activity_dir
@@ -114,17 +351,11 @@ impl Activity {
                 .to_string();
 
             let mut event_map = EventMap::new();
-            for event_entry in fs::read_dir(&activity_dir).map_err(|e| error::InvalidValue {
-                category: "reading event folder list",
-                value: e.to_string(),
+            for event_path in file_system.read_dir(&activity_dir).map_err(|e| error::Io {
+                context: "reading event folder list",
+                source: e,
             })? {
-                let event_path = event_entry
-                    .map_err(|e| error::InvalidValue {
-                        category: "reading event folder entry",
-                        value: e.to_string(),
-                    })?
-                    .path();
-                if !event_path.is_dir() {
+                if !file_system.is_dir(&event_path) {
                     continue;
                 }
                 let event_name = event_path
@@ -140,11 +371,14 @@ impl Activity {
                     "deactivated" => Some(ActivityEvent::Deactivated),
                     "started" => Some(ActivityEvent::Started),
                     "stopped" => Some(ActivityEvent::Stopped),
+                    "suspended" => Some(ActivityEvent::Suspended),
+                    "resumed" => Some(ActivityEvent::Resumed),
                     _ => None,
-                } && let Some(script_path) =
-                    get_script_path(&activity_id, root, script_filename, &event)
-                {
-                    event_map.insert(event, script_path);
+                } {
+                    let scripts = get_script_paths(file_system, &event_path, script_filename);
+                    if !scripts.is_empty() {
+                        event_map.insert(event, scripts.into());
+                    }
                 }
             }
             if !event_map.is_empty() {
@@ -157,186 +391,1461 @@ impl Activity {
         data: &str,
         scripts: ScriptMap,
     ) -> Result<Vec<Self>, error::Application> {
-        data.lines()
-            .filter_map(|line| ACTIVITY_DATA_RX.captures(line))
-            .map(|cap| {
-                let id = cap
-                    .name("id")
-                    .ok_or_else(|| error::InvalidValue {
-                        category: "Activity Data id",
-                        value: data.to_string(),
-                    })?
-                    .as_str()
-                    .to_string();
-                let name = cap
-                    .name("name")
-                    .ok_or_else(|| error::InvalidValue {
-                        category: "Activity Data name",
-                        value: data.to_string(),
-                    })?
-                    .as_str()
-                    .to_string();
-                let event_scripts = scripts.get(&id).cloned().unwrap_or_default();
-                Ok(Self {
+        Ok(Self::from_listed(CliSource::parse(data)?, &scripts))
+    }
+    pub fn from_listed(listed: Vec<ListedActivity>, scripts: &ScriptMap) -> Vec<Self> {
+        listed
+            .into_iter()
+            .map(|listed| {
+                let event_scripts = scripts.get(listed.id()).cloned().unwrap_or_default();
+                let (id, name, icon, state) = listed.dissolve();
+                Self {
                     name,
                     id,
                     event_scripts,
-                })
-            })
-            .collect::<Result<Vec<Self>, error::Application>>()
-            .map(|mut list| {
-                list.sort_by_key(|activity| activity.name().to_lowercase());
-                list
+                    state,
+                    icon,
+                }
             })
+            .collect()
+    }
+    /// Names are collated, so accented letters sort with their base letter, and compared in
+    /// lowercase so names differing only in case fall back to the id.
+    pub fn compare_by_name(&self, other: &Self) -> Ordering {
+        Collator::new(Tailoring::default(), true, false)
+            .collate(&self.name.to_lowercase(), &other.name.to_lowercase())
+            .then_with(|| self.id.cmp(&other.id))
     }
     pub fn save_activities(
+        file_system: &impl FileSystem,
         root: &Path,
         script_filename: &ShellScriptFilename,
         activities: &[Self],
-    ) -> Result<(), error::Application> {
+        backup: bool,
+        link_mode: LinkMode,
+    ) -> Result<SaveReport, error::Application> {
+        Self::save_activities_with_progress(
+            file_system,
+            root,
+            script_filename,
+            activities,
+            backup,
+            link_mode,
+            |_, _| {},
+        )
+    }
+    pub fn broken_links(
+        file_system: &impl FileSystem,
+        root: &Path,
+        script_filename: &ShellScriptFilename,
+        activities: &[Self],
+    ) -> Vec<error::Application> {
+        activities
+            .iter()
+            .flat_map(|activity| {
+                activity
+                    .placements(root, script_filename)
+                    .into_iter()
+                    .filter_map(move |(event, dest_path, script)| {
+                        let is_link = matches!(
+                            file_system.symlink_metadata(&dest_path),
+                            Ok(EntryKind::Symlink)
+                        );
+                        (is_link && !file_system.exists(script)).then(|| error::BrokenSymlink {
+                            activity_id: activity.id.clone(),
+                            event: event.into(),
+                            path: dest_path.to_string_lossy().into(),
+                        })
+                    })
+            })
+            .collect()
+    }
+    pub fn non_executable_scripts(activities: &[Self]) -> Vec<PathBuf> {
+        let mut scripts: Vec<PathBuf> = activities
+            .iter()
+            .flat_map(|activity| activity.event_scripts.values().flat_map(|s| s.iter()))
+            .filter(|script| executable_status(script) == ExecutableStatus::NotExecutable)
+            .cloned()
+            .collect();
+        scripts.sort();
+        scripts.dedup();
+        scripts
+    }
+    pub fn planned_file_removals(
+        file_system: &impl FileSystem,
+        root: &Path,
+        script_filename: &ShellScriptFilename,
+        activities: &[Self],
+        backup: bool,
+        link_mode: LinkMode,
+    ) -> Vec<(PathBuf, ManagedEntry)> {
+        if backup {
+            return Vec::new();
+        }
+        activities
+            .iter()
+            .flat_map(|activity| {
+                activity
+                    .changes(file_system, root, script_filename, link_mode)
+                    .into_iter()
+                    .filter(|change| change.existing == Some(EntryKind::File))
+                    .map(|change| {
+                        let entry = change.managed_entry(file_system);
+                        (change.dest_path, entry)
+                    })
+            })
+            .collect()
+    }
+    pub fn planned_overwrites(
+        file_system: &impl FileSystem,
+        root: &Path,
+        script_filename: &ShellScriptFilename,
+        activities: &[Self],
+        loaded: &[Self],
+    ) -> Vec<(PathBuf, PathBuf)> {
+        activities
+            .iter()
+            .flat_map(|activity| {
+                let loaded = loaded.iter().find(|a| a.id == activity.id);
+                // Only links are looked at, which every link mode replaces alike
+                activity
+                    .changes(file_system, root, script_filename, LinkMode::Symlink)
+                    .into_iter()
+                    .filter(|change| change.existing == Some(EntryKind::Symlink))
+                    .filter_map(move |change| {
+                        let current = resolve_link(file_system, &change.dest_path)?;
+                        let known =
+                            loaded.is_some_and(|a| a.scripts(&change.event).contains(&current));
+                        (change.script.as_ref() != Some(&current) && !known)
+                            .then_some((change.dest_path, current))
+                    })
+            })
+            .collect()
+    }
+    pub fn plan_save(
+        file_system: &impl FileSystem,
+        root: &Path,
+        script_filename: &ShellScriptFilename,
+        activities: &[Self],
+        link_mode: LinkMode,
+    ) -> Result<Vec<PlannedChange>, error::Application> {
         for activity in activities {
-            for event in ActivityEvent::iter() {
-                let script = activity.get_script(&event);
-                let dest_path = get_script_dest_path(&activity.id, root, script_filename, &event);
-                let dest_dir = dest_path.parent().ok_or_else(|| error::SaveDataError {
-                    activity: activity.name().clone(),
-                    event: event.into(),
-                    script_path: dest_path.to_string_lossy().into(),
-                })?;
-                if dest_path.exists() {
-                    fs::remove_file(&dest_path).map_err(|_| error::SaveDataError {
-                        activity: activity.name().clone(),
-                        event: event.into(),
-                        script_path: dest_path.to_string_lossy().into(),
-                    })?;
+            activity.check_cross_activity_links(file_system, root, script_filename, link_mode)?;
+        }
+        Ok(activities
+            .iter()
+            .flat_map(|activity| {
+                activity
+                    .changes(file_system, root, script_filename, link_mode)
+                    .into_iter()
+                    .map(|change| PlannedChange {
+                        activity: activity.name.clone(),
+                        event: change.event,
+                        kind: change.kind(),
+                        dest_path: change.dest_path,
+                        script: change.script,
+                    })
+            })
+            .collect())
+    }
+    /// New links are first made in a staging directory under `root` and then renamed into
+    /// place, so a link that can't be made leaves every existing entry as it was, and a link
+    /// being replaced is never missing in between. When a rename fails, the ones already done
+    /// are undone in reverse order.
+    pub fn save_activities_with_progress(
+        file_system: &impl FileSystem,
+        root: &Path,
+        script_filename: &ShellScriptFilename,
+        activities: &[Self],
+        backup: bool,
+        link_mode: LinkMode,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<SaveReport, error::Application> {
+        for activity in activities {
+            activity.check_cross_activity_links(file_system, root, script_filename, link_mode)?;
+        }
+        let staging = root.join(STAGING_DIR);
+        // Anything left there by an interrupted save is stale
+        remove_staging(file_system, &staging);
+        let result = Self::save_staged(
+            file_system,
+            root,
+            &staging,
+            script_filename,
+            activities,
+            backup,
+            link_mode,
+            on_progress,
+        );
+        remove_staging(file_system, &staging);
+        result
+    }
+    #[allow(clippy::too_many_arguments)]
+    fn save_staged(
+        file_system: &impl FileSystem,
+        root: &Path,
+        staging: &Path,
+        script_filename: &ShellScriptFilename,
+        activities: &[Self],
+        backup: bool,
+        link_mode: LinkMode,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<SaveReport, error::Application> {
+        let changes: Vec<Vec<Change>> = activities
+            .iter()
+            .map(|activity| activity.changes(file_system, root, script_filename, link_mode))
+            .collect();
+        for (activity, changes) in activities.iter().zip(&changes) {
+            activity.stage(file_system, staging, changes, backup, link_mode)?;
+        }
+        let total = activities.len();
+        let mut report = SaveReport::default();
+        let mut applied = Vec::new();
+        for (index, (activity, changes)) in activities.iter().zip(&changes).enumerate() {
+            let changed_scripts = changes.iter().filter(|c| c.script.is_some()).count();
+            let scripts: usize = activity.event_scripts.values().map(|s| s.len()).sum();
+            report.unchanged += scripts - changed_scripts;
+            if let Err(mut e) = activity.commit(
+                file_system,
+                staging,
+                changes,
+                backup,
+                &mut report,
+                &mut applied,
+            ) {
+                if !roll_back(file_system, &applied)
+                    && let error::SaveDataError { step, .. } = &mut e
+                {
+                    *step = error::SaveStep::Incomplete;
                 }
-                if let Some(script_path) = script {
-                    fs::create_dir_all(dest_dir).map_err(|_| error::SaveDataError {
-                        activity: activity.name().clone(),
-                        event: event.into(),
-                        script_path: dest_path.to_string_lossy().into(),
-                    })?;
-                    symlink(script_path, &dest_path).map_err(|_| error::SaveDataError {
-                        activity: activity.name().clone(),
-                        event: event.into(),
-                        script_path: dest_path.to_string_lossy().into(),
-                    })?;
+                return Err(e);
+            }
+            on_progress(index + 1, total);
+        }
+        Ok(report)
+    }
+    /// Rejects newly assigned scripts that sit inside another activity's event directories,
+    /// which would break as soon as that activity is cleared.
+    ///
+    /// Copies don't depend on their source once made, so they may come from anywhere.
+    fn check_cross_activity_links(
+        &self,
+        file_system: &impl FileSystem,
+        root: &Path,
+        script_filename: &ShellScriptFilename,
+        link_mode: LinkMode,
+    ) -> Result<(), error::Application> {
+        if link_mode == LinkMode::Copy {
+            return Ok(());
+        }
+        for (_, dest_path, script) in self.placements(root, script_filename) {
+            let already_linked = resolve_link(file_system, &dest_path).as_ref() == Some(script);
+            if !already_linked && links_into_other_activity(root, script, &dest_path) {
+                return Err(error::InvalidValue {
+                    category: "script inside another activity",
+                    value: script.to_string_lossy().into(),
+                });
+            }
+        }
+        Ok(())
+    }
+    fn placements(
+        &self,
+        root: &Path,
+        script_filename: &ShellScriptFilename,
+    ) -> Vec<(ActivityEvent, PathBuf, &PathBuf)> {
+        ActivityEvent::iter()
+            .flat_map(|event| {
+                let scripts = self.scripts(&event);
+                let dest_dir = root.join(&self.id).join(event.to_string());
+                dest_paths(&dest_dir, script_filename, scripts)
+                    .into_iter()
+                    .zip(scripts)
+                    .map(move |(dest_path, script)| (event, dest_path, script))
+            })
+            .collect()
+    }
+    /// In copy mode a copy whose content matches its script counts as that script, since the
+    /// loaded activities name the copy while edited ones still name the original.
+    fn changes(
+        &self,
+        file_system: &impl FileSystem,
+        root: &Path,
+        script_filename: &ShellScriptFilename,
+        link_mode: LinkMode,
+    ) -> Vec<Change> {
+        let placements = self.placements(root, script_filename);
+        let mut changes: Vec<Change> = placements
+            .iter()
+            // Scripts already in the event directory stay as they are
+            .filter(|(_, dest_path, script)| dest_path != *script)
+            .filter_map(|(event, dest_path, script)| {
+                let existing = file_system.symlink_metadata(dest_path).ok();
+                let current_target = match existing {
+                    Some(EntryKind::Symlink) => resolve_link(file_system, dest_path),
+                    // A copy is its own script
+                    Some(EntryKind::File) => Some(dest_path.clone()),
+                    Some(EntryKind::Dir) | None => None,
+                };
+                let unchanged = current_target.as_ref() == Some(*script)
+                    || (link_mode == LinkMode::Copy
+                        && existing == Some(EntryKind::File)
+                        && same_content(file_system, dest_path, script));
+                (!unchanged).then(|| Change {
+                    event: *event,
+                    dest_dir: root.join(&self.id).join(event.to_string()),
+                    dest_path: dest_path.clone(),
+                    existing,
+                    script: Some((*script).clone()),
+                })
+            })
+            .collect();
+        for event in ActivityEvent::iter() {
+            let dest_dir = root.join(&self.id).join(event.to_string());
+            let mut entries = file_system.read_dir(&dest_dir).unwrap_or_default();
+            entries.sort();
+            for dest_path in entries {
+                let placed = placements.iter().any(|(_, placed, _)| *placed == dest_path);
+                let existing = file_system.symlink_metadata(&dest_path).ok();
+                if placed
+                    || !is_script_entry(&dest_path, script_filename)
+                    || matches!(existing, Some(EntryKind::Dir) | None)
+                {
+                    continue;
                 }
+                changes.push(Change {
+                    event,
+                    dest_dir: dest_dir.clone(),
+                    dest_path,
+                    existing,
+                    script: None,
+                });
             }
         }
-
+        changes
+    }
+    fn stage(
+        &self,
+        file_system: &impl FileSystem,
+        staging: &Path,
+        changes: &[Change],
+        backup: bool,
+        link_mode: LinkMode,
+    ) -> Result<(), error::Application> {
+        for change in changes {
+            let Some(script_path) = &change.script else {
+                continue;
+            };
+            if !change.backed_up(backup) {
+                self.preserve(file_system, staging, change)?;
+            }
+            let staged = staged_path(staging, &self.id, change);
+            file_system
+                .create_dir_all(staged.parent().unwrap_or(staging))
+                .and_then(|()| match link_mode {
+                    LinkMode::Symlink => file_system.symlink(script_path, &staged),
+                    LinkMode::RelativeSymlink => {
+                        file_system.symlink(&relative_path(&change.dest_dir, script_path), &staged)
+                    }
+                    LinkMode::Copy => file_system.copy(script_path, &staged),
+                })
+                .map_err(|e| self.save_error(change, e, error::SaveStep::Staging))?;
+        }
         Ok(())
     }
+    /// Keeps a copy of an entry that renaming the new one into place will replace.
+    fn preserve(
+        &self,
+        file_system: &impl FileSystem,
+        staging: &Path,
+        change: &Change,
+    ) -> Result<(), error::Application> {
+        let previous = previous_path(staging, &self.id, change);
+        let result = match change.existing {
+            Some(EntryKind::Symlink) => file_system
+                .create_dir_all(previous.parent().unwrap_or(staging))
+                .and_then(|()| file_system.read_link(&change.dest_path))
+                .and_then(|target| file_system.symlink(&target, &previous)),
+            Some(EntryKind::File) => file_system
+                .create_dir_all(previous.parent().unwrap_or(staging))
+                .and_then(|()| file_system.copy(&change.dest_path, &previous)),
+            Some(EntryKind::Dir) | None => Ok(()),
+        };
+        result.map_err(|e| self.save_error(change, e, error::SaveStep::Staging))
+    }
+    fn commit(
+        &self,
+        file_system: &impl FileSystem,
+        staging: &Path,
+        changes: &[Change],
+        backup: bool,
+        report: &mut SaveReport,
+        applied: &mut Vec<Applied>,
+    ) -> Result<(), error::Application> {
+        for change in changes {
+            let backed_up = change.backed_up(backup);
+            let deleted_file = (change.existing == Some(EntryKind::File) && !backed_up)
+                .then(|| change.managed_entry(file_system));
+            let previous = previous_path(staging, &self.id, change);
+            if backed_up {
+                let backup_path = backup_path(file_system, &change.dest_path);
+                file_system
+                    .rename(&change.dest_path, &backup_path)
+                    .map_err(|e| self.save_error(change, e, error::SaveStep::RolledBack))?;
+                applied.push(Applied::Moved {
+                    from: change.dest_path.clone(),
+                    to: backup_path,
+                });
+            }
+            if change.script.is_some() {
+                if let Some(created) = missing_ancestor(file_system, &change.dest_dir) {
+                    file_system
+                        .create_dir_all(&change.dest_dir)
+                        .map_err(|e| self.save_error(change, e, error::SaveStep::RolledBack))?;
+                    applied.push(Applied::CreatedDir(created));
+                }
+                // Renaming replaces whatever is still at the destination in one step
+                file_system
+                    .rename(&staged_path(staging, &self.id, change), &change.dest_path)
+                    .map_err(|e| self.save_error(change, e, error::SaveStep::RolledBack))?;
+                applied.push(if change.existing.is_some() && !backed_up {
+                    Applied::Moved {
+                        from: change.dest_path.clone(),
+                        to: previous,
+                    }
+                } else {
+                    Applied::Created(change.dest_path.clone())
+                });
+            } else if change.existing.is_some() && !backed_up {
+                file_system
+                    .create_dir_all(previous.parent().unwrap_or(staging))
+                    .and_then(|()| file_system.rename(&change.dest_path, &previous))
+                    .map_err(|e| self.save_error(change, e, error::SaveStep::RolledBack))?;
+                applied.push(Applied::Moved {
+                    from: change.dest_path.clone(),
+                    to: previous,
+                });
+            }
+            let dest_path = change.dest_path.clone();
+            match change.kind() {
+                ChangeKind::Create => report.created.push(dest_path),
+                ChangeKind::Replace => report.replaced.push(dest_path),
+                ChangeKind::Remove => report.removed.push(dest_path),
+            }
+            match deleted_file {
+                Some(ManagedEntry::OwnedCopy) => {
+                    report.deleted_copies.push(change.dest_path.clone())
+                }
+                Some(ManagedEntry::Foreign | ManagedEntry::Link) => {
+                    report.deleted_files.push(change.dest_path.clone());
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+    fn save_error(
+        &self,
+        change: &Change,
+        source: io::Error,
+        step: error::SaveStep,
+    ) -> error::Application {
+        error::SaveDataError {
+            activity: self.name.clone(),
+            event: change.event.into(),
+            script_path: change.dest_path.to_string_lossy().into(),
+            step,
+            source,
+        }
+    }
 }
 
-fn get_script_dest_path(
-    activity_id: &String,
-    root: &Path,
-    script_filename: &ShellScriptFilename,
-    event: &ActivityEvent,
-) -> PathBuf {
-    root.join(activity_id)
-        .join(event.to_string())
-        .join(script_filename.as_str())
-}
-fn get_script_path(
-    activity_id: &String,
-    root: &Path,
-    script_filename: &ShellScriptFilename,
-    event: &ActivityEvent,
-) -> Option<PathBuf> {
-    read_link(get_script_dest_path(
-        activity_id,
-        root,
-        script_filename,
-        event,
-    ))
-    .ok()
+#[derive(Debug)]
+struct Change {
+    event: ActivityEvent,
+    dest_dir: PathBuf,
+    dest_path: PathBuf,
+    existing: Option<EntryKind>,
+    script: Option<PathBuf>,
 }
 
-// Allowed in tests
-#[allow(clippy::unwrap_used)]
-#[cfg(test)]
-mod tests {
-    use std::fs::symlink_metadata;
+impl Change {
+    fn managed_entry(&self, file_system: &impl FileSystem) -> ManagedEntry {
+        classify_entry(file_system, &self.dest_path, self.script.as_ref())
+            .unwrap_or(ManagedEntry::Foreign)
+    }
+    fn backed_up(&self, backup: bool) -> bool {
+        backup && self.existing == Some(EntryKind::File)
+    }
+    const fn kind(&self) -> ChangeKind {
+        match (self.existing.is_some(), self.script.is_some()) {
+            (false, _) => ChangeKind::Create,
+            (true, true) => ChangeKind::Replace,
+            (true, false) => ChangeKind::Remove,
+        }
+    }
+}
 
-    use super::*;
-    use asserting::prelude::*;
-    use tempfile::tempdir;
+/// A step of a save's commit, recorded so it can be undone if a later step fails.
+#[derive(Debug)]
+enum Applied {
+    /// The entry at `from` now lives at `to`.
+    Moved {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    Created(PathBuf),
+    CreatedDir(PathBuf),
+}
 
-    #[test]
-    fn debug_regex_capture() {
-        let line = "[RUNNING] abc-12d-a Activity A (icon-a)";
-        let cap = ACTIVITY_DATA_RX.captures(line);
-        assert!(cap.is_some(), "Regex failed to match: {line}");
+/// Returns whether every applied step could be undone.
+fn roll_back(file_system: &impl FileSystem, applied: &[Applied]) -> bool {
+    let mut undone = true;
+    for step in applied.iter().rev() {
+        let result = match step {
+            Applied::Moved { from, to } => file_system.rename(to, from),
+            Applied::Created(path) => file_system.remove_file(path),
+            Applied::CreatedDir(path) => file_system.remove_dir_all(path),
+        };
+        if let Err(e) = result {
+            eprintln!("Undoing part of a failed save failed due to: {e}");
+            undone = false;
+        }
     }
-    #[test]
-    fn from_activity_data_contains_exactly() {
-        let sample_data = r#"
-            [RUNNING] abc-12d-a Activity A (icon-a)
-            [STOPPED] abc-12d-b activity B (icon-b)
-            [CURRENT] abc-12d-d Long Named Activity (icon-d)
-            [RUNNING] abc-12d-e Filing Taxes & Accounting (icon-e)
-        "#
-        .trim();
+    undone
+}
 
-        let activities = Activity::from_activity_data(sample_data, ScriptMap::new()).unwrap();
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ChangeKind {
+    Create,
+    Replace,
+    Remove,
+}
 
-        let actual: Vec<_> = activities
-            .iter()
-            .map(|a| (a.name().clone(), a.id().clone()))
-            .collect();
+#[derive(Debug, Clone, PartialEq, Eq, Getters)]
+pub struct PlannedChange {
+    activity: String,
+    #[getter(copy)]
+    event: ActivityEvent,
+    #[getter(copy)]
+    kind: ChangeKind,
+    dest_path: PathBuf,
+    script: Option<PathBuf>,
+}
 
-        assert_that!(actual).contains_exactly([
-            ("Activity A".to_string(), "abc-12d-a".to_string()),
-            ("activity B".to_string(), "abc-12d-b".to_string()),
-            (
-                "Filing Taxes & Accounting".to_string(),
-                "abc-12d-e".to_string(),
-            ),
-            ("Long Named Activity".to_string(), "abc-12d-d".to_string()),
-        ]);
+impl std::fmt::Display for PlannedChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {}: {}",
+            self.kind,
+            self.activity,
+            self.event,
+            self.dest_path.display()
+        )?;
+        if let Some(script) = &self.script {
+            write!(f, " -> {}", script.display())?;
+        }
+        Ok(())
     }
-    #[test]
-    fn from_activity_data_populates_event_scripts() {
-        let sample_data = r#"
-            [RUNNING] abc-12d-a Activity A (icon-a)
-            [RUNNING] abc-12d-b Activity B (icon-b)
-        "#
-        .trim();
-
-        let mut map = ScriptMap::new();
+}
 
-        let mut events_a = EventMap::new();
-        events_a.insert(
-            ActivityEvent::Activated,
-            PathBuf::from("/scripts/a/activated/kas-script.sh"),
-        );
-        events_a.insert(
-            ActivityEvent::Started,
-            PathBuf::from("/scripts/a/started/kas-script.sh"),
-        );
+#[derive(Debug, Default, Getters)]
+pub struct SaveReport {
+    created: Vec<PathBuf>,
+    replaced: Vec<PathBuf>,
+    removed: Vec<PathBuf>,
+    /// Regular files replaced or removed without a backup that were copies of their script.
+    deleted_copies: Vec<PathBuf>,
+    /// Regular files replaced or removed without a backup whose content is now gone.
+    deleted_files: Vec<PathBuf>,
+    #[getter(copy)]
+    unchanged: usize,
+}
 
-        let mut events_b = EventMap::new();
-        events_b.insert(
-            ActivityEvent::Deactivated,
-            PathBuf::from("/scripts/b/deactivated/kas-script.sh"),
-        );
+pub fn script_metadata(path: &Path) -> Result<ScriptMetadata, error::Application> {
+    let link_meta = fs::symlink_metadata(path).map_err(|e| error::Io {
+        context: "reading script metadata",
+        source: e,
+    })?;
+    let target = if link_meta.file_type().is_symlink() {
+        let target = read_link(path).map_err(|e| error::Io {
+            context: "reading script link",
+            source: e,
+        })?;
+        match path.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target,
+        }
+    } else {
+        path.to_path_buf()
+    };
+    Ok(match fs::metadata(path) {
+        Ok(meta) => ScriptMetadata {
+            target,
+            target_exists: true,
+            size: Some(meta.len()),
+            modified: meta.modified().ok(),
+            executable: Some(meta.permissions().mode() & 0o111 != 0),
+        },
+        Err(_) => ScriptMetadata {
+            target,
+            target_exists: false,
+            size: None,
+            modified: None,
+            executable: None,
+        },
+    })
+}
 
-        map.insert("abc-12d-a".into(), events_a.clone());
-        map.insert("abc-12d-b".into(), events_b.clone());
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutableStatus {
+    Executable,
+    NotExecutable,
+    Missing,
+}
 
-        let activities = Activity::from_activity_data(sample_data, map).unwrap();
+/// For files owned by someone else, any group or other execute bit counts, since group
+/// membership isn't checked.
+pub fn executable_status(path: &Path) -> ExecutableStatus {
+    let Ok(meta) = fs::metadata(path) else {
+        return ExecutableStatus::Missing;
+    };
+    let bits = if current_uid() == Some(meta.uid()) {
+        0o100
+    } else {
+        0o011
+    };
+    if meta.permissions().mode() & bits == 0 {
+        ExecutableStatus::NotExecutable
+    } else {
+        ExecutableStatus::Executable
+    }
+}
 
-        assert_that!(activities.len()).is_equal_to(2);
+fn current_uid() -> Option<u32> {
+    fs::metadata("/proc/self").ok().map(|meta| meta.uid())
+}
 
-        let a = activities.iter().find(|a| a.id() == "abc-12d-a").unwrap();
-        assert_that!(a.event_scripts.clone()).is_equal_to(events_a);
+pub fn make_executable(path: &Path) -> Result<(), error::Application> {
+    let mode = fs::metadata(path)
+        .map_err(|e| error::Io {
+            context: "reading script permissions",
+            source: e,
+        })?
+        .permissions()
+        .mode();
+    fs::set_permissions(path, fs::Permissions::from_mode(mode | (mode & 0o444) >> 2)).map_err(|e| {
+        error::Io {
+            context: "making script executable",
+            source: e,
+        }
+    })
+}
 
-        let b = activities.iter().find(|a| a.id() == "abc-12d-b").unwrap();
+pub fn fix_permissions(paths: &[PathBuf]) -> Result<usize, error::Application> {
+    for path in paths {
+        make_executable(path)?;
+        if executable_status(path) != ExecutableStatus::Executable {
+            return Err(error::NotExecutable {
+                path: path.display().to_string(),
+            });
+        }
+    }
+    Ok(paths.len())
+}
+
+/// A regular file only counts as an owned copy when its content matches `script`.
+pub fn classify_entry(
+    file_system: &impl FileSystem,
+    dest_path: &Path,
+    script: Option<&PathBuf>,
+) -> Option<ManagedEntry> {
+    if file_system.symlink_metadata(dest_path).ok()? == EntryKind::Symlink {
+        return Some(ManagedEntry::Link);
+    }
+    let is_copy = script.is_some_and(|script| {
+        matches!(
+            (file_system.read(dest_path), file_system.read(script)),
+            (Ok(dest), Ok(source)) if dest == source
+        )
+    });
+    Some(if is_copy {
+        ManagedEntry::OwnedCopy
+    } else {
+        ManagedEntry::Foreign
+    })
+}
+
+pub fn looks_like_kas_root(root: &Path, activities: &[Activity]) -> bool {
+    activities
+        .iter()
+        .any(|activity| root.join(activity.id()).is_dir())
+}
+
+pub fn links_into_other_activity(root: &Path, script: &Path, dest_path: &Path) -> bool {
+    let root = normalize(root);
+    let activity_dir = |path: &Path| {
+        let path = normalize(path);
+        let mut parts = path.strip_prefix(&root).ok()?.iter();
+        let (activity, event) = (parts.next()?, parts.next()?);
+        parts.next()?;
+        ActivityEvent::iter()
+            .any(|known| known.to_string().as_str() == event)
+            .then(|| activity.to_owned())
+    };
+    match (activity_dir(script), activity_dir(dest_path)) {
+        (Some(script_dir), Some(dest_dir)) => script_dir != dest_dir,
+        _ => false,
+    }
+}
+
+fn same_content(file_system: &impl FileSystem, a: &Path, b: &Path) -> bool {
+    matches!((file_system.read(a), file_system.read(b)), (Ok(a), Ok(b)) if a == b)
+}
+
+fn staged_path(staging: &Path, activity_id: &str, change: &Change) -> PathBuf {
+    staging
+        .join(activity_id)
+        .join(change.event.to_string())
+        .join(change.dest_path.file_name().unwrap_or_default())
+}
+
+fn previous_path(staging: &Path, activity_id: &str, change: &Change) -> PathBuf {
+    staged_path(&staging.join(PREVIOUS_DIR), activity_id, change)
+}
+
+/// The outermost directory `create_dir_all(dir)` would create, if any.
+fn missing_ancestor(file_system: &impl FileSystem, dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .take_while(|dir| file_system.symlink_metadata(dir).is_err())
+        .last()
+        .map(Path::to_path_buf)
+}
+
+fn remove_staging(file_system: &impl FileSystem, staging: &Path) {
+    file_system.remove_dir_all(staging).ok();
+}
+
+/// `<dest>.bak-<unix seconds>` next to the original file, with `-1`, `-2`, … added when a
+/// backup from the same second is already there.
+fn backup_path(file_system: &impl FileSystem, dest_path: &Path) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let mut base = dest_path.as_os_str().to_owned();
+    base.push(format!(".bak-{timestamp}"));
+    let base = PathBuf::from(base);
+    let mut path = base.clone();
+    for n in 1.. {
+        if file_system.symlink_metadata(&path).is_err() {
+            break;
+        }
+        let mut name = base.as_os_str().to_owned();
+        name.push(format!("-{n}"));
+        path = PathBuf::from(name);
+    }
+    path
+}
+
+/// Scripts already in `event_dir` stay where they are. A lone script is placed as
+/// `script_filename`, several as `01-<script_filename>`, `02-…` in the order they are listed,
+/// since the activity manager runs them in file name order.
+fn dest_paths(
+    event_dir: &Path,
+    script_filename: &ShellScriptFilename,
+    scripts: &[PathBuf],
+) -> Vec<PathBuf> {
+    let in_place: Vec<&Path> = scripts
+        .iter()
+        .map(PathBuf::as_path)
+        .filter(|script| script.parent() == Some(event_dir))
+        .collect();
+    let single = event_dir.join(script_filename.as_str());
+    let numbered = scripts.len() - in_place.len() > 1 || in_place.contains(&single.as_path());
+    let mut number = 0;
+    scripts
+        .iter()
+        .map(|script| {
+            if in_place.contains(&script.as_path()) {
+                return script.clone();
+            }
+            if !numbered {
+                return single.clone();
+            }
+            loop {
+                number += 1;
+                let dest_path = event_dir.join(format!("{number:02}-{script_filename}"));
+                if !in_place.contains(&dest_path.as_path()) {
+                    return dest_path;
+                }
+            }
+        })
+        .collect()
+}
+
+fn get_script_paths(
+    file_system: &impl FileSystem,
+    event_dir: &Path,
+    script_filename: &ShellScriptFilename,
+) -> Vec<PathBuf> {
+    let mut entries = file_system.read_dir(event_dir).unwrap_or_default();
+    entries.sort();
+    let mut scripts = Vec::new();
+    for entry in entries {
+        if !is_script_entry(&entry, script_filename) {
+            continue;
+        }
+        let script = match file_system.symlink_metadata(&entry) {
+            Ok(EntryKind::Symlink) => resolve_link(file_system, &entry),
+            Ok(EntryKind::File) => Some(entry),
+            Ok(EntryKind::Dir) | Err(_) => None,
+        };
+        if let Some(script) = script
+            && !scripts.contains(&script)
+        {
+            scripts.push(script);
+        }
+    }
+    scripts
+}
+
+/// Whether an event directory entry is named like a script: with the same extension as
+/// `script_filename`, which leaves out backups, and not hidden unless it is that name.
+fn is_script_entry(path: &Path, script_filename: &ShellScriptFilename) -> bool {
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+    name == script_filename.as_str()
+        || (!name.to_string_lossy().starts_with('.')
+            && path.extension() == Path::new(script_filename.as_str()).extension())
+}
+
+fn resolve_link(file_system: &impl FileSystem, link: &Path) -> Option<PathBuf> {
+    let target = file_system.read_link(link).ok()?;
+    if target.is_absolute() {
+        return Some(target);
+    }
+    link.parent().map(|dir| normalize(&dir.join(target)))
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn relative_path(from_dir: &Path, target: &Path) -> PathBuf {
+    if !target.is_absolute() || !from_dir.is_absolute() {
+        return target.to_path_buf();
+    }
+    let (from_dir, target) = (normalize(from_dir), normalize(target));
+    let from: Vec<_> = from_dir.components().collect();
+    let to: Vec<_> = target.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    from[common..]
+        .iter()
+        .map(|_| Component::ParentDir)
+        .chain(to[common..].iter().copied())
+        .collect()
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::symlink_metadata,
+        os::unix::fs::{MetadataExt, symlink},
+    };
+
+    use super::*;
+    use crate::file_system::fake::{Entry, FakeFileSystem};
+    use asserting::prelude::*;
+    use tempfile::tempdir;
+
+    struct FakeSource(&'static str);
+
+    impl ActivitySource for FakeSource {
+        fn list_activities(&self) -> Result<Vec<ListedActivity>, error::Application> {
+            CliSource::parse(self.0)
+        }
+    }
+
+    #[test]
+    fn from_activity_data_contains_exactly() {
+        let sample_data = r#"
+            [RUNNING] abc-12d-a Activity A (icon-a)
+            [STOPPED] abc-12d-b activity B (icon-b)
+            [CURRENT] abc-12d-d Long Named Activity (icon-d)
+            [RUNNING] abc-12d-e Filing Taxes & Accounting (icon-e)
+        "#
+        .trim();
+
+        let activities = Activity::from_activity_data(sample_data, ScriptMap::new()).unwrap();
+
+        let actual: Vec<_> = activities
+            .iter()
+            .map(|a| (a.name().clone(), a.id().clone(), a.state()))
+            .collect();
+
+        assert_that!(actual).contains_exactly([
+            (
+                "Activity A".to_string(),
+                "abc-12d-a".to_string(),
+                ActivityState::Running,
+            ),
+            (
+                "activity B".to_string(),
+                "abc-12d-b".to_string(),
+                ActivityState::Stopped,
+            ),
+            (
+                "Long Named Activity".to_string(),
+                "abc-12d-d".to_string(),
+                ActivityState::Current,
+            ),
+            (
+                "Filing Taxes & Accounting".to_string(),
+                "abc-12d-e".to_string(),
+                ActivityState::Running,
+            ),
+        ]);
+    }
+    #[test]
+    fn unknown_state_marker_is_kept_as_unknown() {
+        let activities =
+            Activity::from_activity_data("[INVALID] abc-12d-a Work (icon-a)", ScriptMap::new())
+                .unwrap();
+        assert_that!(activities[0].state()).is_equal_to(ActivityState::Unknown);
+        assert_that!(Activity::new("Work".into(), "abc-12d-a".into(), EventMap::new()).state())
+            .is_equal_to(ActivityState::Unknown);
+    }
+    #[test]
+    fn compare_by_name_ignores_case_then_uses_id() {
+        let activity =
+            |name: &str, id: &str| Activity::new(name.into(), id.into(), EventMap::new());
+        let mut activities = [
+            activity("work", "abc-12d-c"),
+            activity("Zebra", "abc-12d-a"),
+            activity("apple", "abc-12d-d"),
+            activity("Work", "abc-12d-b"),
+            activity("Émile", "abc-12d-e"),
+        ];
+        activities.sort_by(Activity::compare_by_name);
+
+        let ids: Vec<&str> = activities.iter().map(|a| a.id().as_str()).collect();
+        assert_that!(ids).contains_exactly([
+            "abc-12d-d",
+            "abc-12d-e",
+            "abc-12d-b",
+            "abc-12d-c",
+            "abc-12d-a",
+        ]);
+        assert_that!(activity("B", "x").compare_by_name(&activity("a", "y")))
+            .is_equal_to(Ordering::Greater);
+    }
+    #[test]
+    fn sort_orders_break_ties_as_documented() {
+        let activity = |name: &str, id: &str, state| Activity {
+            name: name.into(),
+            id: id.into(),
+            event_scripts: EventMap::new(),
+            state,
+            icon: String::new(),
+        };
+        let listed = [
+            activity("work", "abc-12d-c", ActivityState::Stopped),
+            activity("Zebra", "abc-12d-a", ActivityState::Running),
+            activity("apple", "abc-12d-d", ActivityState::Stopped),
+            activity("Mail", "abc-12d-e", ActivityState::Current),
+            activity("Work", "abc-12d-b", ActivityState::Starting),
+        ];
+        let sorted = |order| {
+            let mut activities = listed.clone();
+            sort_activities(&mut activities, order);
+            activities
+                .iter()
+                .map(|a| a.id().clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_that!(sorted(SortOrder::ByName)).contains_exactly([
+            "abc-12d-d",
+            "abc-12d-e",
+            "abc-12d-b",
+            "abc-12d-c",
+            "abc-12d-a",
+        ]);
+        assert_that!(sorted(SortOrder::ByStatus)).contains_exactly([
+            "abc-12d-e",
+            "abc-12d-a",
+            "abc-12d-b",
+            "abc-12d-c",
+            "abc-12d-d",
+        ]);
+        assert_that!(sorted(SortOrder::ByStatusThenName)).contains_exactly([
+            "abc-12d-e",
+            "abc-12d-b",
+            "abc-12d-a",
+            "abc-12d-d",
+            "abc-12d-c",
+        ]);
+    }
+    #[test]
+    fn from_activity_data_populates_event_scripts() {
+        let sample_data = r#"
+            [RUNNING] abc-12d-a Activity A (icon-a)
+            [RUNNING] abc-12d-b Activity B (icon-b)
+        "#
+        .trim();
+
+        let mut map = ScriptMap::new();
+
+        let mut events_a = EventMap::new();
+        events_a.insert(
+            ActivityEvent::Activated,
+            PathBuf::from("/scripts/a/activated/kas-script.sh").into(),
+        );
+        events_a.insert(
+            ActivityEvent::Started,
+            PathBuf::from("/scripts/a/started/kas-script.sh").into(),
+        );
+
+        let mut events_b = EventMap::new();
+        events_b.insert(
+            ActivityEvent::Deactivated,
+            PathBuf::from("/scripts/b/deactivated/kas-script.sh").into(),
+        );
+
+        map.insert("abc-12d-a".into(), events_a.clone());
+        map.insert("abc-12d-b".into(), events_b.clone());
+
+        let activities = Activity::from_activity_data(sample_data, map).unwrap();
+
+        assert_that!(activities.len()).is_equal_to(2);
+
+        let a = activities.iter().find(|a| a.id() == "abc-12d-a").unwrap();
+        assert_that!(a.event_scripts.clone()).is_equal_to(events_a);
+        assert_that!(a.icon().as_str()).is_equal_to("icon-a");
+
+        let b = activities.iter().find(|a| a.id() == "abc-12d-b").unwrap();
         assert_that!(b.event_scripts.clone()).is_equal_to(events_b);
     }
     #[test]
+    fn copy_scripts_from_overwrites_only_the_source_events() {
+        let mut source = Activity::new("Work".into(), "a-1".into(), EventMap::new());
+        source.set_script(ActivityEvent::Started, "/scripts/on.sh".into());
+        source.set_script(ActivityEvent::Stopped, "/scripts/off.sh".into());
+        let mut target = Activity::new("Home".into(), "a-2".into(), EventMap::new());
+        target.set_script(ActivityEvent::Started, "/scripts/home.sh".into());
+        target.set_script(ActivityEvent::Activated, "/scripts/home.sh".into());
+
+        target.copy_scripts_from(&source);
+
+        assert_that!(target.get_script(&ActivityEvent::Started).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/on.sh")));
+        assert_that!(target.get_script(&ActivityEvent::Stopped).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/off.sh")));
+        assert_that!(target.get_script(&ActivityEvent::Activated).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/home.sh")));
+        assert_that!(source.script_count()).is_equal_to(2);
+    }
+    #[test]
+    fn merge_scripts_from_keeps_existing_scripts_unless_overwriting() {
+        let mut source = Activity::new("Work".into(), "a-1".into(), EventMap::new());
+        source.set_script(ActivityEvent::Started, "/scripts/on.sh".into());
+        source.set_script(ActivityEvent::Stopped, "/scripts/off.sh".into());
+        let mut target = Activity::new("Home".into(), "a-2".into(), EventMap::new());
+        target.set_script(ActivityEvent::Started, "/scripts/home.sh".into());
+        let mut overwritten = target.clone();
+
+        target.merge_scripts_from(&source, false);
+        overwritten.merge_scripts_from(&source, true);
+
+        assert_that!(target.get_script(&ActivityEvent::Started).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/home.sh")));
+        assert_that!(target.get_script(&ActivityEvent::Stopped).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/off.sh")));
+        assert_that!(overwritten.get_script(&ActivityEvent::Started).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/on.sh")));
+        assert_that!(overwritten.script_count()).is_equal_to(2);
+    }
+    #[test]
+    fn script_count_tracks_in_memory_changes() {
+        let mut activity = Activity::new("Work".into(), "abc-12d-a".into(), EventMap::new());
+        assert_that!(activity.script_count()).is_equal_to(0);
+
+        activity.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        activity.set_script(ActivityEvent::Stopped, "/scripts/b.sh".into());
+        activity.set_script(ActivityEvent::Stopped, "/scripts/c.sh".into());
+        assert_that!(activity.script_count()).is_equal_to(2);
+
+        activity.delete_script(ActivityEvent::Activated);
+        assert_that!(activity.script_count()).is_equal_to(1);
+    }
+    #[test]
+    fn looks_like_kas_root_requires_an_activity_folder() {
+        let activities = [Activity::new(
+            "Work".into(),
+            "abc-12d-a".into(),
+            EventMap::new(),
+        )];
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+
+        assert!(!looks_like_kas_root(&root.join("missing"), &activities));
+        assert!(!looks_like_kas_root(root, &activities), "empty dir");
+
+        fs::create_dir(root.join("Documents")).unwrap();
+        fs::write(root.join("abc-12d-a"), "not a folder").unwrap();
+        assert!(!looks_like_kas_root(root, &activities), "unrelated content");
+
+        fs::remove_file(root.join("abc-12d-a")).unwrap();
+        fs::create_dir(root.join("abc-12d-a")).unwrap();
+        assert!(looks_like_kas_root(root, &activities), "matching uuid dir");
+    }
+    #[test]
+    fn script_status_checks_the_target_exists() {
+        let tmp = tempdir().unwrap();
+        let script = tmp.path().join("hello.sh");
+        fs::write(&script, "#!/bin/sh\necho hello").unwrap();
+        let mut activity = Activity::new("Work".into(), "a-1".into(), EventMap::new());
+        activity.set_script(ActivityEvent::Activated, script);
+        activity.set_script(ActivityEvent::Stopped, tmp.path().join("gone.sh"));
+
+        assert_that!(activity.script_status(&ActivityEvent::Activated))
+            .is_equal_to(ScriptStatus::Ok);
+        assert_that!(activity.script_status(&ActivityEvent::Stopped))
+            .is_equal_to(ScriptStatus::Missing);
+        assert_that!(activity.script_status(&ActivityEvent::Started))
+            .is_equal_to(ScriptStatus::Unassigned);
+    }
+    #[test]
+    fn script_metadata_follows_links() {
+        let tmp = tempdir().unwrap();
+        let script = tmp.path().join("hello.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let link = tmp.path().join("link.sh");
+        symlink("hello.sh", &link).unwrap();
+
+        let meta = script_metadata(&link).unwrap();
+
+        assert_that!(meta.target().clone()).is_equal_to(script);
+        assert_that!(*meta.target_exists()).is_true();
+        assert_that!(*meta.size()).is_equal_to(Some(10));
+        assert_that!(*meta.executable()).is_equal_to(Some(true));
+        assert_that!(meta.modified().is_some()).is_true();
+    }
+    #[test]
+    fn script_metadata_of_dangling_link_is_partial() {
+        let tmp = tempdir().unwrap();
+        let link = tmp.path().join("link.sh");
+        symlink(tmp.path().join("gone.sh"), &link).unwrap();
+
+        let meta = script_metadata(&link).unwrap();
+
+        assert_that!(meta.target().clone()).is_equal_to(tmp.path().join("gone.sh"));
+        assert_that!(*meta.target_exists()).is_false();
+        assert_that!(*meta.size()).is_none();
+        assert_that!(*meta.executable()).is_none();
+        assert_that!(script_metadata(&tmp.path().join("missing.sh"))).is_err();
+    }
+    #[test]
+    fn make_executable_follows_read_permissions() {
+        let tmp = tempdir().unwrap();
+        let script = tmp.path().join("hello.sh");
+        fs::write(&script, "#!/bin/sh\necho hello").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o640)).unwrap();
+        assert_that!(executable_status(&script)).is_equal_to(ExecutableStatus::NotExecutable);
+
+        make_executable(&script).unwrap();
+
+        assert_that!(executable_status(&script)).is_equal_to(ExecutableStatus::Executable);
+        let mode = fs::metadata(&script).unwrap().permissions().mode();
+        assert_that!(mode & 0o777).is_equal_to(0o750);
+        assert_that!(executable_status(&tmp.path().join("gone.sh")))
+            .is_equal_to(ExecutableStatus::Missing);
+        assert_that!(make_executable(&tmp.path().join("gone.sh"))).is_err();
+    }
+    #[test]
+    fn non_executable_scripts_are_found_after_loading_and_fixed() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("activities");
+        let runnable = tmp.path().join("runnable.sh");
+        let plain = tmp.path().join("plain.sh");
+        for (script, mode) in [(&runnable, 0o755), (&plain, 0o644)] {
+            fs::write(script, "#!/bin/sh\n").unwrap();
+            fs::set_permissions(script, fs::Permissions::from_mode(mode)).unwrap();
+        }
+        for (event, script) in [
+            ("activated", &runnable),
+            ("started", &plain),
+            ("stopped", &plain),
+        ] {
+            let dir = root.join("abc-12d-a").join(event);
+            fs::create_dir_all(&dir).unwrap();
+            symlink(script, dir.join("kas-script.sh")).unwrap();
+        }
+        let scripts =
+            Activity::load_scripts(&RealFileSystem, &root, &"kas-script.sh".parse().unwrap())
+                .unwrap();
+        let activities =
+            Activity::from_activity_data("[RUNNING] abc-12d-a Work (icon-a)", scripts).unwrap();
+
+        let found = Activity::non_executable_scripts(&activities);
+        assert_that!(&found).contains_exactly([plain.clone()]);
+
+        assert_that!(fix_permissions(&found).unwrap()).is_equal_to(1);
+        assert_that!(executable_status(&plain)).is_equal_to(ExecutableStatus::Executable);
+        assert_that!(Activity::non_executable_scripts(&activities)).is_empty();
+    }
+    #[test]
+    fn fix_permissions_reports_scripts_that_stay_unrunnable() {
+        let tmp = tempdir().unwrap();
+        // Nobody may read it, so making it executable adds nothing
+        let script = tmp.path().join("write-only.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o200)).unwrap();
+
+        let error = fix_permissions(std::slice::from_ref(&script)).unwrap_err();
+
+        assert!(matches!(error, error::NotExecutable { .. }));
+        assert_that!(error.kind()).is_equal_to(error::ErrorKind::PermissionDenied);
+        assert_that!(fix_permissions(&[tmp.path().join("gone.sh")])).is_err();
+    }
+    #[test]
+    fn executable_status_uses_the_owner_bits_for_own_files() {
+        let tmp = tempdir().unwrap();
+        let script = tmp.path().join("hello.sh");
+        fs::write(&script, "#!/bin/sh\necho hello").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o655)).unwrap();
+
+        assert_that!(executable_status(&script)).is_equal_to(ExecutableStatus::NotExecutable);
+    }
+    #[test]
+    fn classify_entry_distinguishes_links_copies_and_foreign_files() {
+        let script = PathBuf::from("/scripts/hello.sh");
+        let file_system = FakeFileSystem::default()
+            .with_file(&script, "#!/bin/sh\necho hello")
+            .with_entry("/kas/link.sh", Entry::Symlink(script.clone()))
+            .with_file("/kas/copy.sh", "#!/bin/sh\necho hello")
+            .with_file("/kas/foreign.sh", "#!/bin/sh\necho mine");
+        let classify = |path: &str, script: Option<&PathBuf>| {
+            classify_entry(&file_system, Path::new(path), script)
+        };
+
+        assert_that!(classify("/kas/missing.sh", Some(&script))).is_none();
+        assert_that!(classify("/kas/link.sh", Some(&script))).is_equal_to(Some(ManagedEntry::Link));
+        assert_that!(classify("/kas/copy.sh", Some(&script)))
+            .is_equal_to(Some(ManagedEntry::OwnedCopy));
+        assert_that!(classify("/kas/copy.sh", None)).is_equal_to(Some(ManagedEntry::Foreign));
+        assert_that!(classify("/kas/foreign.sh", Some(&script)))
+            .is_equal_to(Some(ManagedEntry::Foreign));
+    }
+    #[test]
+    fn planned_overwrites_flags_only_links_changed_by_another_tool() {
+        let root = Path::new("/kas");
+        let filename = "kas-script.sh".parse().unwrap();
+        let file_system = FakeFileSystem::default()
+            .with_entry("/scripts/on.sh", Entry::File)
+            .with_entry("/scripts/old.sh", Entry::File)
+            .with_entry(
+                "/kas/a-1/started/kas-script.sh",
+                Entry::Symlink("/opt/other-tool/hook.sh".into()),
+            )
+            .with_entry(
+                "/kas/a-1/stopped/kas-script.sh",
+                Entry::Symlink("/scripts/old.sh".into()),
+            )
+            .with_entry(
+                "/kas/a-1/activated/kas-script.sh",
+                Entry::Symlink("/scripts/on.sh".into()),
+            );
+        let mut activity = Activity::new("Work".into(), "a-1".into(), EventMap::new());
+        for event in [
+            ActivityEvent::Started,
+            ActivityEvent::Stopped,
+            ActivityEvent::Activated,
+        ] {
+            activity.set_script(event, "/scripts/on.sh".into());
+        }
+        let mut loaded = Activity::new("Work".into(), "a-1".into(), EventMap::new());
+        loaded.set_script(ActivityEvent::Stopped, "/scripts/old.sh".into());
+
+        assert_that!(Activity::planned_overwrites(
+            &file_system,
+            root,
+            &filename,
+            &[activity],
+            &[loaded]
+        ))
+        .contains_exactly([(
+            PathBuf::from("/kas/a-1/started/kas-script.sh"),
+            PathBuf::from("/opt/other-tool/hook.sh"),
+        )]);
+    }
+    #[test]
+    fn planned_file_removals_lists_only_real_files() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let script = root.join("hello.sh");
+        fs::write(&script, "#!/bin/sh\necho hello").unwrap();
+        for event in ["activated", "started", "stopped"] {
+            fs::create_dir_all(root.join("a-1").join(event)).unwrap();
+        }
+        fs::write(
+            root.join("a-1/activated/kas-script.sh"),
+            "#!/bin/sh\necho hello",
+        )
+        .unwrap();
+        symlink(&script, root.join("a-1/started/kas-script.sh")).unwrap();
+        fs::write(
+            root.join("a-1/stopped/kas-script.sh"),
+            "#!/bin/sh\necho mine",
+        )
+        .unwrap();
+        let mut activity = Activity::new("Work".into(), "a-1".into(), EventMap::new());
+        activity.set_script(ActivityEvent::Activated, script);
+        let activities = [activity];
+        let filename = "kas-script.sh".parse().unwrap();
+        let removals = |backup| {
+            Activity::planned_file_removals(
+                &RealFileSystem,
+                root,
+                &filename,
+                &activities,
+                backup,
+                LinkMode::Symlink,
+            )
+        };
+
+        assert_that!(removals(false)).contains_exactly([
+            (
+                root.join("a-1/activated/kas-script.sh"),
+                ManagedEntry::OwnedCopy,
+            ),
+            (
+                root.join("a-1/stopped/kas-script.sh"),
+                ManagedEntry::Foreign,
+            ),
+        ]);
+        assert_that!(removals(true)).is_empty();
+
+        let report = Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+        assert_that!(report.deleted_copies().clone())
+            .contains_exactly([root.join("a-1/activated/kas-script.sh")]);
+        assert_that!(report.deleted_files().clone())
+            .contains_exactly([root.join("a-1/stopped/kas-script.sh")]);
+    }
+    #[test]
+    fn planned_file_removals_skips_files_the_save_leaves_alone() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let script = root.join("hello.sh");
+        fs::write(&script, "#!/bin/sh\necho hello").unwrap();
+        for event in ["started", "stopped"] {
+            fs::create_dir_all(root.join("a-1").join(event)).unwrap();
+            fs::write(
+                root.join("a-1").join(event).join("kas-script.sh"),
+                "#!/bin/sh\necho mine",
+            )
+            .unwrap();
+        }
+        let filename = "kas-script.sh".parse().unwrap();
+        let scripts = Activity::load_scripts(&RealFileSystem, root, &filename).unwrap();
+        let mut activity = Activity::new("Work".into(), "a-1".into(), scripts["a-1"].clone());
+        activity.set_script(ActivityEvent::Stopped, script);
+
+        for link_mode in [LinkMode::Symlink, LinkMode::Copy] {
+            assert_that!(Activity::planned_file_removals(
+                &RealFileSystem,
+                root,
+                &filename,
+                std::slice::from_ref(&activity),
+                false,
+                link_mode,
+            ))
+            .contains_exactly([(
+                root.join("a-1/stopped/kas-script.sh"),
+                ManagedEntry::Foreign,
+            )]);
+        }
+    }
+    #[test]
+    fn folders_of_unknown_activities_are_reported_as_orphans() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let script = root.join("script.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        for id in ["abc-12d-a", "abc-12d-gone", "abc-12d-empty"] {
+            fs::create_dir_all(root.join(id).join("started")).unwrap();
+        }
+        symlink(&script, root.join("abc-12d-a/started/kas-script.sh")).unwrap();
+        symlink(&script, root.join("abc-12d-gone/started/kas-script.sh")).unwrap();
+        let sample_data = r#"
+            [RUNNING] abc-12d-a Activity A (icon-a)
+            [STOPPED] abc-12d-b activity B (icon-b)
+        "#;
+        let filename = "kas-script.sh".parse().unwrap();
+
+        let source = FakeSource(sample_data);
+
+        let (activities, orphans) =
+            Activity::from_source(&source, &RealFileSystem, root, &filename).unwrap();
+        assert_that!(activities.len()).is_equal_to(2);
+        assert_that!(&orphans).contains_exactly(["abc-12d-gone".to_string()]);
+
+        Activity::remove_orphans(&RealFileSystem, root, &orphans).unwrap();
+        assert!(!root.join("abc-12d-gone").exists());
+        assert!(root.join("abc-12d-a/started/kas-script.sh").exists());
+        assert!(script.exists());
+        let (_, orphans) =
+            Activity::from_source(&source, &RealFileSystem, root, &filename).unwrap();
+        assert_that!(orphans).is_empty();
+
+        for outside in ["../abc-12d-a", ".", ""] {
+            let outside = [outside.to_string()];
+            assert!(Activity::remove_orphans(&RealFileSystem, root, &outside).is_err());
+        }
+        assert!(root.join("abc-12d-a").exists());
+    }
+    #[test]
+    fn load_scripts_treats_missing_root_as_empty() {
+        let tmp = tempdir().unwrap();
+        let filename = "kas-script.sh".parse().unwrap();
+
+        let result =
+            Activity::load_scripts(&RealFileSystem, &tmp.path().join("missing"), &filename)
+                .unwrap();
+        assert_that!(result.is_empty()).is_true();
+
+        let file_root = tmp.path().join("file");
+        fs::write(&file_root, "").unwrap();
+        let error = Activity::load_scripts(&RealFileSystem, &file_root, &filename).unwrap_err();
+        assert!(matches!(error, error::Io { .. }));
+        assert_that!(error.kind()).is_not_equal_to(error::ErrorKind::NotFound);
+    }
+    #[test]
     fn load_scripts_reads_symlink_structure() {
         let dir = tempdir().unwrap();
         let root = dir.path();
@@ -353,13 +1862,148 @@ mod tests {
         let symlink_path = activity_dir.join("kas-script.sh");
         symlink(&actual_script, &symlink_path).unwrap();
 
-        let result = Activity::load_scripts(root, &"kas-script.sh".parse().unwrap()).unwrap();
+        let result =
+            Activity::load_scripts(&RealFileSystem, root, &"kas-script.sh".parse().unwrap())
+                .unwrap();
 
         assert_that!(result.len()).is_equal_to(1);
         let event_map = result.get(activity_id).unwrap();
 
         assert_that!(event_map.len()).is_equal_to(1);
-        assert_that!(event_map.get(&ActivityEvent::Activated).unwrap()).is_equal_to(&actual_script);
+        assert_that!(event_map[&ActivityEvent::Activated].to_vec())
+            .is_equal_to(vec![actual_script]);
+    }
+    #[test]
+    fn from_activity_data_without_matches_is_empty() {
+        for data in ["", "No activities\n", "abc-12d-a Work (icon-a)"] {
+            let activities = Activity::from_activity_data(data, ScriptMap::new()).unwrap();
+            assert_that!(activities).is_empty();
+        }
+    }
+    #[test]
+    fn every_event_round_trips_through_its_folder_name() {
+        let names: Vec<_> = ActivityEvent::iter().map(|e| e.to_string()).collect();
+        assert_that!(names).contains_exactly([
+            "activated".to_string(),
+            "deactivated".to_string(),
+            "started".to_string(),
+            "stopped".to_string(),
+            "suspended".to_string(),
+            "resumed".to_string(),
+        ]);
+        for event in ActivityEvent::iter() {
+            assert_that!(event.to_string().parse::<ActivityEvent>().unwrap()).is_equal_to(event);
+        }
+    }
+    #[test]
+    fn save_activities_links_and_loads_every_event() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("activities");
+        let filename = "kas-script.sh".parse().unwrap();
+        let mut events = EventMap::new();
+        for event in ActivityEvent::iter() {
+            let script = tmp.path().join(format!("{event}.sh"));
+            fs::write(&script, "#!/bin/sh\n").unwrap();
+            events.insert(event, script.into());
+        }
+        let activity = Activity {
+            name: "Work".into(),
+            id: "a-1".into(),
+            event_scripts: events.clone(),
+            state: ActivityState::default(),
+            icon: String::new(),
+        };
+
+        Activity::save_activities(
+            &RealFileSystem,
+            &root,
+            &filename,
+            &[activity],
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        for event in ActivityEvent::iter() {
+            let link = root
+                .join("a-1")
+                .join(event.to_string())
+                .join("kas-script.sh");
+            assert_that!(fs::read_link(link).unwrap()).is_equal_to(events[&event][0].clone());
+        }
+        let scripts = Activity::load_scripts(&RealFileSystem, &root, &filename).unwrap();
+        assert_that!(scripts["a-1"].clone()).is_equal_to(events);
+    }
+    #[test]
+    fn plan_save_lists_what_saving_then_does() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("activities");
+        let filename = "kas-script.sh".parse().unwrap();
+        let on = tmp.path().join("on.sh");
+        let off = tmp.path().join("off.sh");
+        fs::write(&on, "#!/bin/sh\n").unwrap();
+        fs::write(&off, "#!/bin/sh\n").unwrap();
+        for (event, script) in [("activated", &on), ("stopped", &on)] {
+            let dir = root.join("a-1").join(event);
+            fs::create_dir_all(&dir).unwrap();
+            symlink(script, dir.join("kas-script.sh")).unwrap();
+        }
+        let mut activity = Activity::new("Work".into(), "a-1".into(), EventMap::new());
+        activity.set_script(ActivityEvent::Activated, on.clone());
+        activity.set_script(ActivityEvent::Started, on.clone());
+        activity.set_script(ActivityEvent::Stopped, off.clone());
+        let activities = [activity];
+
+        let plan = Activity::plan_save(
+            &RealFileSystem,
+            &root,
+            &filename,
+            &activities,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+        let planned: Vec<_> = plan.iter().map(|c| (c.kind(), c.event())).collect();
+        assert_that!(planned).contains_exactly_in_any_order([
+            (ChangeKind::Create, ActivityEvent::Started),
+            (ChangeKind::Replace, ActivityEvent::Stopped),
+        ]);
+        assert_that!(fs::read_link(root.join("a-1/stopped/kas-script.sh")).unwrap())
+            .is_equal_to(on);
+        assert_that!(root.join("a-1/started").exists()).is_false();
+        let stopped = plan
+            .iter()
+            .find(|c| c.event() == ActivityEvent::Stopped)
+            .unwrap();
+        assert_that!(stopped.to_string()).is_equal_to(format!(
+            "replace Work stopped: {} -> {}",
+            root.join("a-1/stopped/kas-script.sh").display(),
+            off.display()
+        ));
+
+        let report = Activity::save_activities(
+            &RealFileSystem,
+            &root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+        assert_that!(report.created().clone())
+            .contains_exactly([root.join("a-1/started/kas-script.sh")]);
+        assert_that!(report.replaced().clone())
+            .contains_exactly([root.join("a-1/stopped/kas-script.sh")]);
+        assert_that!(
+            Activity::plan_save(
+                &RealFileSystem,
+                &root,
+                &filename,
+                &activities,
+                LinkMode::Symlink
+            )
+            .unwrap()
+        )
+        .is_empty();
     }
     #[test]
     fn save_activities_writes_symlink_structure() {
@@ -370,15 +2014,25 @@ mod tests {
         fs::write(&source_script, "#!/bin/sh\necho hello").unwrap();
 
         let mut events = EventMap::new();
-        events.insert(ActivityEvent::Started, source_script.clone());
+        events.insert(ActivityEvent::Started, source_script.clone().into());
 
         let activity = Activity {
             name: "TestActivity".into(),
             id: "a-1".into(),
             event_scripts: events,
+            state: ActivityState::default(),
+            icon: String::new(),
         };
 
-        Activity::save_activities(root, &"kas-script.sh".parse().unwrap(), &[activity]).unwrap();
+        Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &"kas-script.sh".parse().unwrap(),
+            &[activity],
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
 
         let link_path = root.join("a-1/started/kas-script.sh");
         let meta = symlink_metadata(&link_path).unwrap();
@@ -407,13 +2061,967 @@ mod tests {
             name: "TestActivity".into(),
             id: "a-1".into(),
             event_scripts: EventMap::new(),
+            state: ActivityState::default(),
+            icon: String::new(),
         };
 
-        Activity::save_activities(root, &"kas-script.sh".parse().unwrap(), &[activity]).unwrap();
+        Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &"kas-script.sh".parse().unwrap(),
+            &[activity],
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
 
         assert!(
             !link_path.exists(),
             "Expected symlink to be removed when event is unset"
         );
     }
+    #[test]
+    fn save_activities_skips_unchanged_entries() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let filename = "kas-script.sh".parse().unwrap();
+        let source_script = root.join("hello.sh");
+        fs::write(&source_script, "#!/bin/sh\necho hello").unwrap();
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Started, source_script.into());
+        let activities = [
+            Activity::new("TestActivity".into(), "a-1".into(), events),
+            Activity::new("Empty".into(), "a-2".into(), EventMap::new()),
+        ];
+
+        let first = Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+        let link_path = root.join("a-1/started/kas-script.sh");
+        let before = symlink_metadata(&link_path).unwrap();
+        let second = Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+        let after = symlink_metadata(&link_path).unwrap();
+
+        assert_that!(first.created().len()).is_equal_to(1);
+        assert_that!(first.unchanged()).is_equal_to(0);
+        assert_that!(second.created()).is_empty();
+        assert_that!(second.replaced()).is_empty();
+        assert_that!(second.removed()).is_empty();
+        assert_that!(second.unchanged()).is_equal_to(1);
+        assert_that!(after.ino()).is_equal_to(before.ino());
+        assert_that!(after.modified().unwrap()).is_equal_to(before.modified().unwrap());
+        assert_that!(root.join("a-2").exists()).is_false();
+    }
+    #[test]
+    fn save_activities_replaces_dangling_links() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let link_dir = root.join("a-1/started");
+        fs::create_dir_all(&link_dir).unwrap();
+        let link_path = link_dir.join("kas-script.sh");
+        symlink(root.join("gone.sh"), &link_path).unwrap();
+        let source_script = root.join("hello.sh");
+        fs::write(&source_script, "#!/bin/sh\necho hello").unwrap();
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Started, source_script.clone().into());
+        let activity = Activity::new("TestActivity".into(), "a-1".into(), events);
+
+        Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &"kas-script.sh".parse().unwrap(),
+            &[activity],
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        assert_eq!(fs::read_link(&link_path).unwrap(), source_script);
+    }
+    #[test]
+    fn dangling_links_load_and_survive_saving() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let filename = "kas-script.sh".parse().unwrap();
+        fs::create_dir_all(root.join("a-1/started")).unwrap();
+        let link_path = root.join("a-1/started/kas-script.sh");
+        symlink(root.join("gone.sh"), &link_path).unwrap();
+
+        let scripts = Activity::load_scripts(&RealFileSystem, root, &filename).unwrap();
+        let activities = [Activity::new(
+            "Work".into(),
+            "a-1".into(),
+            scripts["a-1"].clone(),
+        )];
+        assert_that!(activities[0].script_status(&ActivityEvent::Started))
+            .is_equal_to(ScriptStatus::Missing);
+
+        let report = Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        assert_that!(report.removed().is_empty()).is_true();
+        assert_eq!(fs::read_link(&link_path).unwrap(), root.join("gone.sh"));
+    }
+    #[test]
+    fn regular_file_scripts_load_as_themselves_and_survive_saving() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let filename = "kas-script.sh".parse().unwrap();
+        fs::create_dir_all(root.join("a-1/activated")).unwrap();
+        let copied = root.join("a-1/activated/kas-script.sh");
+        fs::write(&copied, "#!/bin/sh\necho copied").unwrap();
+
+        let scripts = Activity::load_scripts(&RealFileSystem, root, &filename).unwrap();
+        assert_that!(scripts["a-1"][&ActivityEvent::Activated].to_vec())
+            .is_equal_to(vec![copied.clone()]);
+
+        let activities = [Activity::new(
+            "Work".into(),
+            "a-1".into(),
+            scripts["a-1"].clone(),
+        )];
+        let report = Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        assert_that!(report.created()).is_empty();
+        assert_that!(report.replaced()).is_empty();
+        assert_that!(fs::symlink_metadata(&copied).unwrap().is_file()).is_true();
+        assert_that!(fs::read_to_string(&copied).unwrap())
+            .is_equal_to("#!/bin/sh\necho copied".to_string());
+    }
+    #[test]
+    fn save_report_separates_replaced_from_created_links() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let filename = "kas-script.sh".parse().unwrap();
+        let source_script = root.join("hello.sh");
+        fs::write(&source_script, "#!/bin/sh\necho hello").unwrap();
+        // Another tool's link, pointing at something we never assigned
+        fs::create_dir_all(root.join("a-1/started")).unwrap();
+        let other_link = root.join("a-1/started/kas-script.sh");
+        symlink("/opt/other-tool/hook.sh", &other_link).unwrap();
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Started, source_script.clone().into());
+        events.insert(ActivityEvent::Stopped, source_script.clone().into());
+        let activities = [Activity::new("Work".into(), "a-1".into(), events)];
+        let loaded = [Activity::new("Work".into(), "a-1".into(), EventMap::new())];
+
+        assert_that!(Activity::planned_overwrites(
+            &RealFileSystem,
+            root,
+            &filename,
+            &activities,
+            &loaded
+        ))
+        .contains_exactly([(other_link.clone(), PathBuf::from("/opt/other-tool/hook.sh"))]);
+        let report = Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        assert_that!(report.replaced().clone()).contains_exactly([other_link.clone()]);
+        assert_that!(report.created().clone())
+            .contains_exactly([root.join("a-1/stopped/kas-script.sh")]);
+        assert_that!(report.removed()).is_empty();
+        assert_eq!(fs::read_link(&other_link).unwrap(), source_script);
+        assert_that!(Activity::planned_overwrites(
+            &RealFileSystem,
+            root,
+            &filename,
+            &activities,
+            &loaded
+        ))
+        .is_empty();
+    }
+    #[test]
+    fn links_into_other_activity_only_flags_sibling_activities() {
+        let root = Path::new("/home/me/activities");
+        let dest = Path::new("/home/me/activities/b-2/started/kas-script.sh");
+
+        assert_that!(links_into_other_activity(
+            root,
+            Path::new("/home/me/activities/a-1/started/kas-script.sh"),
+            dest
+        ))
+        .is_true();
+        assert_that!(links_into_other_activity(
+            root,
+            Path::new("/home/me/activities/b-2/../a-1/stopped/kas-script.sh"),
+            dest
+        ))
+        .is_true();
+        assert_that!(links_into_other_activity(
+            root,
+            Path::new("/home/me/activities/b-2/helpers/on.sh"),
+            dest
+        ))
+        .is_false();
+        assert_that!(links_into_other_activity(
+            root,
+            Path::new("/home/me/activities/a-1/helpers/on.sh"),
+            dest
+        ))
+        .is_false();
+        assert_that!(links_into_other_activity(
+            root,
+            Path::new("/home/me/scripts/work.sh"),
+            dest
+        ))
+        .is_false();
+    }
+    #[test]
+    fn save_rejects_script_from_another_activity() {
+        let root = Path::new("/activities");
+        let file_system = FakeFileSystem::default().with_entry(root, Entry::Dir);
+        let mut events = EventMap::new();
+        events.insert(
+            ActivityEvent::Started,
+            PathBuf::from("/activities/a-1/started/kas-script.sh").into(),
+        );
+        let activities = [Activity::new("Work".into(), "b-2".into(), events)];
+
+        let result = Activity::save_activities(
+            &file_system,
+            root,
+            &"kas-script.sh".parse().unwrap(),
+            &activities,
+            false,
+            LinkMode::Symlink,
+        );
+
+        assert_that!(result).is_err();
+        assert_that!(file_system.entry(Path::new("/activities/b-2"))).is_none();
+    }
+    #[test]
+    fn relative_path_climbs_to_common_ancestor() {
+        assert_that!(relative_path(
+            Path::new("/home/me/activities/a-1/started"),
+            Path::new("/home/me/scripts/work.sh")
+        ))
+        .is_equal_to(PathBuf::from("../../../scripts/work.sh"));
+        assert_that!(relative_path(Path::new("/a/b"), Path::new("/a/b/c.sh")))
+            .is_equal_to(PathBuf::from("c.sh"));
+        assert_that!(relative_path(Path::new("/a/b"), Path::new("scripts/c.sh")))
+            .is_equal_to(PathBuf::from("scripts/c.sh"));
+    }
+    #[test]
+    fn copy_mode_copies_scripts_with_permissions() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("activities");
+        let filename = "kas-script.sh".parse().unwrap();
+        let source_script = tmp.path().join("hello.sh");
+        fs::write(&source_script, "#!/bin/sh\necho hello").unwrap();
+        fs::set_permissions(&source_script, fs::Permissions::from_mode(0o750)).unwrap();
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Started, source_script.into());
+        let activities = [Activity::new("Work".into(), "a-1".into(), events)];
+
+        Activity::save_activities(
+            &RealFileSystem,
+            &root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::Copy,
+        )
+        .unwrap();
+
+        let copy = root.join("a-1/started/kas-script.sh");
+        let meta = symlink_metadata(&copy).unwrap();
+        assert_that!(meta.file_type().is_file()).is_true();
+        assert_that!(meta.permissions().mode() & 0o777).is_equal_to(0o750);
+        assert_that!(fs::read_to_string(&copy).unwrap())
+            .is_equal_to("#!/bin/sh\necho hello".to_string());
+
+        // Loaded back, the copy stands in for the script and saving again changes nothing
+        let scripts = Activity::load_scripts(&RealFileSystem, &root, &filename).unwrap();
+        let loaded = [Activity::new(
+            "Work".into(),
+            "a-1".into(),
+            scripts["a-1"].clone(),
+        )];
+        assert_that!(loaded[0].get_script(&ActivityEvent::Started).cloned())
+            .is_equal_to(Some(copy));
+        let report = Activity::save_activities(
+            &RealFileSystem,
+            &root,
+            &filename,
+            &loaded,
+            false,
+            LinkMode::Copy,
+        )
+        .unwrap();
+        assert_that!(report.created().is_empty() && report.replaced().is_empty()).is_true();
+    }
+    #[test]
+    fn copy_mode_saving_again_without_reloading_changes_nothing() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("activities");
+        let filename = "kas-script.sh".parse().unwrap();
+        let source_script = tmp.path().join("hello.sh");
+        fs::write(&source_script, "#!/bin/sh\necho hello").unwrap();
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Started, source_script.clone().into());
+        let activities = [Activity::new("Work".into(), "a-1".into(), events)];
+        let save = |activities: &[Activity]| {
+            Activity::save_activities(
+                &RealFileSystem,
+                &root,
+                &filename,
+                activities,
+                false,
+                LinkMode::Copy,
+            )
+            .unwrap()
+        };
+
+        assert_that!(save(&activities).created().len()).is_equal_to(1);
+        let report = save(&activities);
+        assert_that!(report.replaced()).is_empty();
+        assert_that!(report.created()).is_empty();
+        assert_that!(report.unchanged()).is_equal_to(1);
+
+        // An edited original is copied again
+        fs::write(&source_script, "#!/bin/sh\necho changed").unwrap();
+        assert_that!(save(&activities).replaced().len()).is_equal_to(1);
+    }
+    #[test]
+    fn copy_mode_allows_copying_another_activity_s_copy() {
+        let root = Path::new("/kas");
+        let filename = "kas-script.sh".parse().unwrap();
+        let fake = FakeFileSystem::default()
+            .with_file("/kas/a-1/started/kas-script.sh", "#!/bin/sh\necho on");
+        let mut events = EventMap::new();
+        events.insert(
+            ActivityEvent::Started,
+            PathBuf::from("/kas/a-1/started/kas-script.sh").into(),
+        );
+        let activities = [Activity::new("Home".into(), "a-2".into(), events)];
+
+        let report =
+            Activity::save_activities(&fake, root, &filename, &activities, false, LinkMode::Copy)
+                .unwrap();
+
+        assert_that!(report.created().clone())
+            .contains_exactly([PathBuf::from("/kas/a-2/started/kas-script.sh")]);
+        assert_that!(
+            fake.read(Path::new("/kas/a-2/started/kas-script.sh"))
+                .unwrap()
+        )
+        .is_equal_to(b"#!/bin/sh\necho on".to_vec());
+        assert!(
+            Activity::save_activities(
+                &fake,
+                root,
+                &filename,
+                &activities,
+                false,
+                LinkMode::Symlink
+            )
+            .is_err()
+        );
+    }
+    #[test]
+    fn save_activities_writes_relative_links_when_enabled() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("activities");
+        let filename = "kas-script.sh".parse().unwrap();
+        let source_script = tmp.path().join("scripts/hello.sh");
+        fs::create_dir_all(source_script.parent().unwrap()).unwrap();
+        fs::write(&source_script, "#!/bin/sh\necho hello").unwrap();
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Started, source_script.clone().into());
+        let activities = [Activity::new("Work".into(), "a-1".into(), events)];
+
+        Activity::save_activities(
+            &RealFileSystem,
+            &root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::RelativeSymlink,
+        )
+        .unwrap();
+
+        let link_path = root.join("a-1/started/kas-script.sh");
+        assert_eq!(
+            fs::read_link(&link_path).unwrap(),
+            PathBuf::from("../../../scripts/hello.sh")
+        );
+        assert_that!(fs::read_to_string(&link_path).unwrap())
+            .is_equal_to("#!/bin/sh\necho hello".to_string());
+        let loaded = Activity::load_scripts(&RealFileSystem, &root, &filename).unwrap();
+        assert_that!(loaded["a-1"][&ActivityEvent::Started].to_vec())
+            .is_equal_to(vec![source_script]);
+        let again = Activity::save_activities(
+            &RealFileSystem,
+            &root,
+            &filename,
+            &activities,
+            false,
+            LinkMode::RelativeSymlink,
+        )
+        .unwrap();
+        assert_that!(again.created()).is_empty();
+        assert_that!(again.replaced()).is_empty();
+    }
+    #[test]
+    fn non_utf8_script_links_load_and_can_be_removed() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+        let filename = "kas-script.sh".parse().unwrap();
+        let script = root.join(OsStr::from_bytes(b"caf\xe9.sh"));
+        fs::write(&script, "#!/bin/sh\necho hello").unwrap();
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Stopped, script.clone().into());
+        let mut activity = Activity::new("Work".into(), "a-1".into(), events);
+        Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &filename,
+            std::slice::from_ref(&activity),
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        let loaded = Activity::load_scripts(&RealFileSystem, root, &filename).unwrap();
+        assert_that!(loaded["a-1"][&ActivityEvent::Stopped].to_vec()).is_equal_to(vec![script]);
+
+        activity.delete_script(ActivityEvent::Stopped);
+        let report = Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &filename,
+            &[activity],
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+        assert_that!(report.removed().len()).is_equal_to(1);
+        assert_that!(symlink_metadata(root.join("a-1/stopped/kas-script.sh"))).is_err();
+    }
+    #[test]
+    fn save_reports_overwrites_without_touching_disk() {
+        let root = Path::new("/kas");
+        let file_system = FakeFileSystem::default()
+            .with_entry(
+                "/kas/a-1/started/kas-script.sh",
+                Entry::Symlink("/opt/other-tool/hook.sh".into()),
+            )
+            .with_entry("/kas/a-1/stopped/kas-script.sh", Entry::File);
+        let mut events = EventMap::new();
+        events.insert(
+            ActivityEvent::Started,
+            PathBuf::from("/scripts/work.sh").into(),
+        );
+        events.insert(
+            ActivityEvent::Activated,
+            PathBuf::from("/scripts/work.sh").into(),
+        );
+        let activity = Activity::new("Work".into(), "a-1".into(), events);
+
+        let report = Activity::save_activities(
+            &file_system,
+            root,
+            &"kas-script.sh".parse().unwrap(),
+            &[activity],
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        assert_that!(report.created().clone())
+            .contains_exactly([root.join("a-1/activated/kas-script.sh")]);
+        assert_that!(report.replaced().clone())
+            .contains_exactly([root.join("a-1/started/kas-script.sh")]);
+        assert_that!(report.removed().clone())
+            .contains_exactly([root.join("a-1/stopped/kas-script.sh")]);
+        assert_that!(file_system.entry(&root.join("a-1/started/kas-script.sh")))
+            .is_equal_to(Some(Entry::Symlink("/scripts/work.sh".into())));
+    }
+    #[test]
+    fn failed_commit_puts_every_entry_back() {
+        let root = Path::new("/kas");
+        let file_system = FakeFileSystem::default()
+            .with_entry(
+                "/kas/a-1/activated/kas-script.sh",
+                Entry::Symlink("/scripts/old.sh".into()),
+            )
+            .with_entry(
+                "/kas/a-1/stopped/kas-script.sh",
+                Entry::Symlink("/scripts/stopped.sh".into()),
+            )
+            .with_file("/kas/a-1/started/kas-script.sh", "echo mine")
+            .failing_at("/kas/b-2/deactivated/kas-script.sh");
+        let activities = ["a-1", "b-2"].map(|id| {
+            let mut events = EventMap::new();
+            for event in [
+                ActivityEvent::Activated,
+                ActivityEvent::Deactivated,
+                ActivityEvent::Started,
+            ] {
+                events.insert(event, PathBuf::from("/scripts/new.sh").into());
+            }
+            Activity::new(id.into(), id.into(), events)
+        });
+
+        for backup in [false, true] {
+            let error = Activity::save_activities(
+                &file_system,
+                root,
+                &"kas-script.sh".parse().unwrap(),
+                &activities,
+                backup,
+                LinkMode::Symlink,
+            )
+            .unwrap_err();
+
+            assert!(matches!(
+                &error,
+                error::SaveDataError {
+                    activity,
+                    event: "deactivated",
+                    step: error::SaveStep::RolledBack,
+                    ..
+                } if activity == "b-2"
+            ));
+            assert_that!(error.kind()).is_equal_to(error::ErrorKind::PermissionDenied);
+            assert_that!(file_system.entry(&root.join("a-1/activated/kas-script.sh")))
+                .is_equal_to(Some(Entry::Symlink("/scripts/old.sh".into())));
+            assert_that!(file_system.entry(&root.join("a-1/stopped/kas-script.sh")))
+                .is_equal_to(Some(Entry::Symlink("/scripts/stopped.sh".into())));
+            assert_that!(file_system.entry(&root.join("a-1/started/kas-script.sh")))
+                .is_equal_to(Some(Entry::File));
+            assert_that!(
+                file_system
+                    .read(&root.join("a-1/started/kas-script.sh"))
+                    .unwrap()
+            )
+            .is_equal_to(b"echo mine".to_vec());
+            assert_that!(file_system.read_dir(&root.join("a-1/started")).unwrap()).has_length(1);
+            assert_that!(file_system.entry(&root.join("a-1/deactivated"))).is_none();
+            assert_that!(file_system.entry(&root.join("b-2"))).is_none();
+            assert_that!(file_system.entry(&root.join(STAGING_DIR))).is_none();
+        }
+    }
+    #[test]
+    fn failed_staging_leaves_existing_links_intact() {
+        let root = Path::new("/kas");
+        let file_system = FakeFileSystem::default()
+            .with_entry(
+                "/kas/a-1/started/kas-script.sh",
+                Entry::Symlink("/scripts/old.sh".into()),
+            )
+            .failing_at("/kas/.kas-staging/b-2/started");
+        let activities = ["a-1", "b-2"].map(|id| {
+            let mut events = EventMap::new();
+            events.insert(
+                ActivityEvent::Started,
+                PathBuf::from("/scripts/new.sh").into(),
+            );
+            Activity::new(id.into(), id.into(), events)
+        });
+
+        let error = Activity::save_activities(
+            &file_system,
+            root,
+            &"kas-script.sh".parse().unwrap(),
+            &activities,
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            &error,
+            error::SaveDataError {
+                activity,
+                step: error::SaveStep::Staging,
+                ..
+            } if activity == "b-2"
+        ));
+        assert_that!(file_system.entry(&root.join("a-1/started/kas-script.sh")))
+            .is_equal_to(Some(Entry::Symlink("/scripts/old.sh".into())));
+        assert_that!(file_system.entry(&root.join("b-2"))).is_none();
+        assert_that!(file_system.entry(&root.join(STAGING_DIR))).is_none();
+    }
+    #[test]
+    fn save_clears_a_stale_staging_area() {
+        let root = Path::new("/kas");
+        let file_system = FakeFileSystem::default().with_entry(
+            "/kas/.kas-staging/a-1/started",
+            Entry::Symlink("/scripts/stale.sh".into()),
+        );
+        let mut events = EventMap::new();
+        events.insert(
+            ActivityEvent::Started,
+            PathBuf::from("/scripts/new.sh").into(),
+        );
+        let activity = Activity::new("Work".into(), "a-1".into(), events);
+
+        Activity::save_activities(
+            &file_system,
+            root,
+            &"kas-script.sh".parse().unwrap(),
+            &[activity],
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        assert_that!(file_system.entry(&root.join("a-1/started/kas-script.sh")))
+            .is_equal_to(Some(Entry::Symlink("/scripts/new.sh".into())));
+        assert_that!(file_system.entry(&root.join(STAGING_DIR))).is_none();
+    }
+    #[test]
+    fn broken_links_lists_dangling_event_links() {
+        let root = Path::new("/kas");
+        let filename = "kas-script.sh".parse().unwrap();
+        let file_system = FakeFileSystem::default()
+            .with_entry("/scripts/on.sh", Entry::File)
+            .with_entry(
+                "/kas/a-1/started/kas-script.sh",
+                Entry::Symlink("/scripts/on.sh".into()),
+            )
+            .with_entry(
+                "/kas/a-1/stopped/kas-script.sh",
+                Entry::Symlink("/scripts/gone.sh".into()),
+            );
+        let scripts = Activity::load_scripts(&file_system, root, &filename).unwrap();
+        let activities = [Activity::new(
+            "Work".into(),
+            "a-1".into(),
+            scripts["a-1"].clone(),
+        )];
+
+        let broken = Activity::broken_links(&file_system, root, &filename, &activities);
+
+        assert_that!(broken.len()).is_equal_to(1);
+        assert!(matches!(
+            &broken[0],
+            error::BrokenSymlink { activity_id, event: "stopped", path }
+                if activity_id == "a-1" && path == "/kas/a-1/stopped/kas-script.sh"
+        ));
+    }
+    #[test]
+    fn load_scripts_picks_up_every_script_in_an_event_folder() {
+        let root = Path::new("/kas");
+        let filename = "kas-script.sh".parse().unwrap();
+        let file_system = FakeFileSystem::default()
+            .with_entry("/scripts/on.sh", Entry::File)
+            .with_entry(
+                "/kas/a-1/started/kas-script.sh",
+                Entry::Symlink("/scripts/on.sh".into()),
+            )
+            .with_entry("/kas/a-1/started/10-mount.sh", Entry::File)
+            .with_entry("/kas/a-1/started/.hidden.sh", Entry::File)
+            .with_entry("/kas/a-1/started/kas-script.sh.bak-1", Entry::File)
+            .with_entry("/kas/a-1/started/notes.txt", Entry::File)
+            .with_entry("/kas/a-1/stopped/notes.sh", Entry::Dir)
+            .with_entry("/kas/a-1/custom/hook.sh", Entry::File)
+            .with_entry("/kas/a-2/activated/vpn.sh", Entry::File);
+
+        let scripts = Activity::load_scripts(&file_system, root, &filename).unwrap();
+
+        assert_that!(scripts["a-1"].len()).is_equal_to(1);
+        assert_that!(scripts["a-1"][&ActivityEvent::Started].to_vec()).contains_exactly([
+            PathBuf::from("/kas/a-1/started/10-mount.sh"),
+            PathBuf::from("/scripts/on.sh"),
+        ]);
+        assert_that!(scripts["a-2"][&ActivityEvent::Activated].to_vec())
+            .contains_exactly([PathBuf::from("/kas/a-2/activated/vpn.sh")]);
+    }
+    #[test]
+    fn backups_made_within_one_second_keep_each_file() {
+        let root = Path::new("/kas");
+        let filename = "kas-script.sh".parse().unwrap();
+        let dest = Path::new("/kas/a-1/started/kas-script.sh");
+        let file_system = FakeFileSystem::default()
+            .with_file("/scripts/on.sh", "on")
+            .with_file("/hand/first.sh", "first")
+            .with_file("/hand/second.sh", "second")
+            .with_file(dest, "first");
+        let mut activity = Activity::new("Work".into(), "a-1".into(), EventMap::new());
+        activity.set_script(ActivityEvent::Started, "/scripts/on.sh".into());
+        let save = || {
+            Activity::save_activities(
+                &file_system,
+                root,
+                &filename,
+                std::slice::from_ref(&activity),
+                true,
+                LinkMode::Symlink,
+            )
+            .unwrap();
+        };
+
+        save();
+        file_system.remove_file(dest).unwrap();
+        file_system
+            .copy(Path::new("/hand/second.sh"), dest)
+            .unwrap();
+        save();
+
+        let mut backups: Vec<String> = file_system
+            .read_dir(Path::new("/kas/a-1/started"))
+            .unwrap()
+            .into_iter()
+            .filter(|path| path.to_string_lossy().contains(".bak-"))
+            .map(|path| String::from_utf8(file_system.read(&path).unwrap()).unwrap())
+            .collect();
+        backups.sort();
+        assert_that!(backups).contains_exactly(["first".to_string(), "second".to_string()]);
+    }
+    #[test]
+    fn several_scripts_are_numbered_in_order_and_load_back_the_same() {
+        let root = Path::new("/kas");
+        let filename = "kas-script.sh".parse().unwrap();
+        let file_system = FakeFileSystem::default()
+            .with_entry("/scripts/mount.sh", Entry::File)
+            .with_entry("/scripts/vpn.sh", Entry::File);
+        let mut activity = Activity::new("Work".into(), "a-1".into(), EventMap::new());
+        activity.add_script(ActivityEvent::Started, "/scripts/vpn.sh".into());
+        activity.add_script(ActivityEvent::Started, "/scripts/mount.sh".into());
+        activity.add_script(ActivityEvent::Started, "/scripts/vpn.sh".into());
+
+        let report = Activity::save_activities(
+            &file_system,
+            root,
+            &filename,
+            std::slice::from_ref(&activity),
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        assert_that!(report.created().clone()).contains_exactly([
+            PathBuf::from("/kas/a-1/started/01-kas-script.sh"),
+            PathBuf::from("/kas/a-1/started/02-kas-script.sh"),
+        ]);
+        let loaded = Activity::load_scripts(&file_system, root, &filename).unwrap();
+        assert_that!(loaded["a-1"][&ActivityEvent::Started].to_vec()).contains_exactly([
+            PathBuf::from("/scripts/vpn.sh"),
+            PathBuf::from("/scripts/mount.sh"),
+        ]);
+        let planned = Activity::plan_save(
+            &file_system,
+            root,
+            &filename,
+            &[activity.clone()],
+            LinkMode::Symlink,
+        )
+        .unwrap();
+        assert_that!(planned).is_empty();
+
+        activity.remove_script(ActivityEvent::Started, Path::new("/scripts/vpn.sh"));
+        let report = Activity::save_activities(
+            &file_system,
+            root,
+            &filename,
+            &[activity],
+            false,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        assert_that!(report.created().clone())
+            .contains_exactly([PathBuf::from("/kas/a-1/started/kas-script.sh")]);
+        assert_that!(report.removed().clone()).contains_exactly([
+            PathBuf::from("/kas/a-1/started/01-kas-script.sh"),
+            PathBuf::from("/kas/a-1/started/02-kas-script.sh"),
+        ]);
+    }
+    #[test]
+    fn scripts_placed_in_the_event_folder_stay_where_they_are() {
+        let root = Path::new("/kas");
+        let filename = "kas-script.sh".parse().unwrap();
+        let file_system = FakeFileSystem::default()
+            .with_entry("/scripts/on.sh", Entry::File)
+            .with_entry("/kas/a-1/started/01-kas-script.sh", Entry::File)
+            .with_entry(
+                "/kas/a-1/started/kas-script.sh",
+                Entry::Symlink("/scripts/on.sh".into()),
+            );
+        let (mut activities, _) = Activity::from_source(
+            &FakeSource("[RUNNING] a-1 Work (icon)"),
+            &file_system,
+            root,
+            &filename,
+        )
+        .unwrap();
+
+        let planned = Activity::plan_save(
+            &file_system,
+            root,
+            &filename,
+            &activities,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+        assert_that!(planned).is_empty();
+
+        activities[0].add_script(ActivityEvent::Started, "/scripts/off.sh".into());
+        let planned = Activity::plan_save(
+            &file_system,
+            root,
+            &filename,
+            &activities,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+        let summary: Vec<String> = planned.iter().map(ToString::to_string).collect();
+        assert_that!(summary).contains_exactly([
+            "create Work started: /kas/a-1/started/02-kas-script.sh -> /scripts/on.sh",
+            "create Work started: /kas/a-1/started/03-kas-script.sh -> /scripts/off.sh",
+            "remove Work started: /kas/a-1/started/kas-script.sh",
+        ]);
+    }
+    #[test]
+    fn scripts_serialize_a_lone_script_as_a_plain_path() {
+        let one = Scripts::from(PathBuf::from("/scripts/on.sh"));
+        let two = Scripts::from(vec![
+            PathBuf::from("/scripts/on.sh"),
+            PathBuf::from("/scripts/vpn.sh"),
+        ]);
+
+        assert_that!(serde_json::to_string(&one).unwrap())
+            .is_equal_to(r#""/scripts/on.sh""#.to_string());
+        assert_that!(serde_json::to_string(&two).unwrap())
+            .is_equal_to(r#"["/scripts/on.sh","/scripts/vpn.sh"]"#.to_string());
+        for scripts in [one, two] {
+            let json = serde_json::to_string(&scripts).unwrap();
+            assert_that!(serde_json::from_str::<Scripts>(&json).unwrap()).is_equal_to(scripts);
+        }
+    }
+    #[test]
+    fn load_scripts_reads_fake_links() {
+        let file_system = FakeFileSystem::default()
+            .with_entry(
+                "/kas/a-1/stopped/kas-script.sh",
+                Entry::Symlink("../../scripts/off.sh".into()),
+            )
+            .with_entry(
+                "/kas/a-1/unknown/kas-script.sh",
+                Entry::Symlink("/x.sh".into()),
+            )
+            .with_entry("/kas/stray.txt", Entry::File);
+
+        let scripts = Activity::load_scripts(
+            &file_system,
+            Path::new("/kas"),
+            &"kas-script.sh".parse().unwrap(),
+        )
+        .unwrap();
+
+        assert_that!(scripts.len()).is_equal_to(1);
+        assert_that!(scripts["a-1"].clone().into_iter().collect::<Vec<_>>()).contains_exactly([(
+            ActivityEvent::Stopped,
+            PathBuf::from("/kas/scripts/off.sh").into(),
+        )]);
+    }
+    #[test]
+    fn save_activities_reports_progress_per_activity() {
+        let tmp = tempdir().unwrap();
+        let activities = ["a-1", "a-2", "a-3"].map(|id| Activity {
+            name: id.into(),
+            id: id.into(),
+            event_scripts: EventMap::new(),
+            state: ActivityState::default(),
+            icon: String::new(),
+        });
+        let mut progress = Vec::new();
+
+        Activity::save_activities_with_progress(
+            &RealFileSystem,
+            tmp.path(),
+            &"kas-script.sh".parse().unwrap(),
+            &activities,
+            false,
+            LinkMode::Symlink,
+            |done, total| progress.push((done, total)),
+        )
+        .unwrap();
+
+        assert_that!(progress).contains_exactly([(1, 3), (2, 3), (3, 3)]);
+    }
+    #[test]
+    fn save_activities_backs_up_regular_files() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path();
+
+        let source_script = root.join("hello.sh");
+        fs::write(&source_script, "#!/bin/sh\necho hello").unwrap();
+
+        // A hand-written script where our link should go
+        let link_dir = root.join("a-1/started");
+        fs::create_dir_all(&link_dir).unwrap();
+        let link_path = link_dir.join("kas-script.sh");
+        fs::write(&link_path, "#!/bin/sh\necho mine").unwrap();
+
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Started, source_script.clone().into());
+        let activity = Activity {
+            name: "TestActivity".into(),
+            id: "a-1".into(),
+            event_scripts: events,
+            state: ActivityState::default(),
+            icon: String::new(),
+        };
+
+        Activity::save_activities(
+            &RealFileSystem,
+            root,
+            &"kas-script.sh".parse().unwrap(),
+            &[activity],
+            true,
+            LinkMode::Symlink,
+        )
+        .unwrap();
+
+        assert_eq!(fs::read_link(&link_path).unwrap(), source_script);
+        let backups: Vec<_> = fs::read_dir(&link_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.to_string_lossy().contains("kas-script.sh.bak-"))
+            .collect();
+        assert_that!(backups.len()).is_equal_to(1);
+        assert_that!(fs::read_to_string(&backups[0]).unwrap())
+            .is_equal_to("#!/bin/sh\necho mine".to_string());
+    }
 }
@@ -0,0 +1,288 @@
+use std::{io, process::Command, sync::LazyLock};
+
+use derive_getters::{Dissolve, Getters};
+use derive_new::new;
+use regex::Regex;
+use strum::{Display, EnumString};
+
+use crate::{activity::ActivityState, error};
+
+const ACTIVITY_MANAGER_NAME: &str = "org.kde.ActivityManager";
+const ACTIVITIES_PATH: &str = "/ActivityManager/Activities";
+const ACTIVITIES_INTERFACE: &str = "org.kde.ActivityManager.Activities";
+
+#[allow(clippy::expect_used)]
+static ACTIVITY_DATA_RX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*\[(?P<state>\w+)\]\s+(?P<id>[a-f0-9\-]+)\s+(?P<name>.+?)\s+\((?P<icon>[^\n]+)\)\s*$",
+    )
+    .expect("ValidRx")
+});
+
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Dissolve, new)]
+pub struct ListedActivity {
+    id: String,
+    name: String,
+    icon: String,
+    #[getter(copy)]
+    state: ActivityState,
+}
+
+pub trait ActivitySource {
+    fn list_activities(&self) -> Result<Vec<ListedActivity>, error::Application>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CliSource;
+
+impl CliSource {
+    pub fn parse(data: &str) -> Result<Vec<ListedActivity>, error::Application> {
+        data.lines()
+            .filter_map(|line| ACTIVITY_DATA_RX.captures(line))
+            .map(|cap| {
+                let group = |name: &str, category: &'static str| {
+                    cap.name(name)
+                        .map(|m| m.as_str().to_string())
+                        .ok_or_else(|| error::InvalidValue {
+                            category,
+                            value: data.to_string(),
+                        })
+                };
+                let state = cap
+                    .name("state")
+                    .and_then(|state| state.as_str().parse().ok())
+                    .unwrap_or_default();
+                Ok(ListedActivity::new(
+                    group("id", "Activity Data id")?,
+                    group("name", "Activity Data name")?,
+                    group("icon", "Activity Data icon")?,
+                    state,
+                ))
+            })
+            .collect()
+    }
+}
+
+impl ActivitySource for CliSource {
+    fn list_activities(&self) -> Result<Vec<ListedActivity>, error::Application> {
+        let output = Command::new("kactivities-cli")
+            .arg("--list-activities")
+            .output()
+            .map_err(|e| error::Application::CommandFailed {
+                command: "kactivities-cli",
+                error_text: e.to_string(),
+                source: Some(e),
+            })?;
+
+        if !output.status.success() {
+            let error_text = String::from_utf8_lossy(&output.stderr).to_string();
+            return Err(error::Application::CommandFailed {
+                command: "kactivities-cli",
+                error_text,
+                source: None,
+            });
+        }
+
+        Self::parse(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DbusSource;
+
+impl DbusSource {
+    fn query() -> zbus::Result<Vec<ListedActivity>> {
+        let connection = zbus::blocking::Connection::session()?;
+        let proxy = zbus::blocking::Proxy::new(
+            &connection,
+            ACTIVITY_MANAGER_NAME,
+            ACTIVITIES_PATH,
+            ACTIVITIES_INTERFACE,
+        )?;
+        let current: String = proxy.call("CurrentActivity", &())?;
+        let ids: Vec<String> = proxy.call("ListActivities", &())?;
+        ids.into_iter()
+            .map(|id| {
+                let name: String = proxy.call("ActivityName", &(id.as_str(),))?;
+                let icon: String = proxy.call("ActivityIcon", &(id.as_str(),))?;
+                let state: i32 = proxy.call("ActivityState", &(id.as_str(),))?;
+                let state = if id == current {
+                    ActivityState::Current
+                } else {
+                    state_from_dbus(state)
+                };
+                Ok(ListedActivity::new(id, name, icon, state))
+            })
+            .collect()
+    }
+}
+
+impl ActivitySource for DbusSource {
+    fn list_activities(&self) -> Result<Vec<ListedActivity>, error::Application> {
+        Self::query().map_err(|e| error::CommandFailed {
+            command: ACTIVITY_MANAGER_NAME,
+            error_text: e.to_string(),
+            source: Some(io::Error::other(e)),
+        })
+    }
+}
+
+/// Tries `primary` first and only asks `fallback` when it fails.
+///
+/// When both fail, the error names both reasons, since either may be the one to fix.
+#[derive(Debug, Clone, Copy, new)]
+pub struct WithFallback<P, F> {
+    primary: P,
+    fallback: F,
+}
+
+impl<P: ActivitySource, F: ActivitySource> ActivitySource for WithFallback<P, F> {
+    fn list_activities(&self) -> Result<Vec<ListedActivity>, error::Application> {
+        self.primary.list_activities().or_else(|primary_error| {
+            eprintln!("Listing activities failed due to: {primary_error}, trying the fallback");
+            self.fallback
+                .list_activities()
+                .map_err(|fallback_error| error::CommandFailed {
+                    command: "listing activities",
+                    error_text: format!("{primary_error} Then: {fallback_error}"),
+                    source: None,
+                })
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, EnumString, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum SourceKind {
+    #[default]
+    Auto,
+    Dbus,
+    Cli,
+}
+
+impl SourceKind {
+    pub fn from_env() -> Result<Self, error::Application> {
+        std::env::var("KAS_ACTIVITY_SOURCE").map_or_else(
+            |_| Ok(Self::default()),
+            |value| {
+                value.parse().map_err(|_| error::InvalidValue {
+                    category: "activity source",
+                    value,
+                })
+            },
+        )
+    }
+}
+
+impl ActivitySource for SourceKind {
+    fn list_activities(&self) -> Result<Vec<ListedActivity>, error::Application> {
+        match self {
+            Self::Auto => WithFallback::new(DbusSource, CliSource).list_activities(),
+            Self::Dbus => DbusSource.list_activities(),
+            Self::Cli => CliSource.list_activities(),
+        }
+    }
+}
+
+const fn state_from_dbus(state: i32) -> ActivityState {
+    match state {
+        2 => ActivityState::Running,
+        3 => ActivityState::Starting,
+        4 => ActivityState::Stopped,
+        5 => ActivityState::Stopping,
+        _ => ActivityState::Unknown,
+    }
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+
+    use super::*;
+
+    struct Fixed(Vec<ListedActivity>);
+
+    impl ActivitySource for Fixed {
+        fn list_activities(&self) -> Result<Vec<ListedActivity>, error::Application> {
+            if self.0.is_empty() {
+                Err(error::CommandFailed {
+                    command: "fixed",
+                    error_text: "unreachable".into(),
+                    source: None,
+                })
+            } else {
+                Ok(self.0.clone())
+            }
+        }
+    }
+
+    fn listed(id: &str) -> ListedActivity {
+        ListedActivity::new(
+            id.into(),
+            "Work".into(),
+            "icon".into(),
+            ActivityState::Running,
+        )
+    }
+
+    #[test]
+    fn debug_regex_capture() {
+        let line = "[RUNNING] abc-12d-a Activity A (icon-a)";
+        let cap = ACTIVITY_DATA_RX.captures(line);
+        assert!(cap.is_some(), "Regex failed to match: {line}");
+    }
+    #[test]
+    fn cli_output_keeps_icons() {
+        let listed = CliSource::parse(
+            r#"
+            [CURRENT] abc-12d-a Filing Taxes (office-chart-pie)
+            not an activity
+            "#,
+        )
+        .unwrap();
+
+        assert_that!(listed).contains_exactly([ListedActivity::new(
+            "abc-12d-a".into(),
+            "Filing Taxes".into(),
+            "office-chart-pie".into(),
+            ActivityState::Current,
+        )]);
+    }
+    #[test]
+    fn fallback_is_only_asked_when_the_primary_fails() {
+        let source = WithFallback::new(Fixed(vec![listed("a")]), Fixed(vec![listed("b")]));
+        assert_that!(source.list_activities().unwrap()).contains_exactly([listed("a")]);
+
+        let source = WithFallback::new(Fixed(Vec::new()), Fixed(vec![listed("b")]));
+        assert_that!(source.list_activities().unwrap()).contains_exactly([listed("b")]);
+
+        let source = WithFallback::new(Fixed(Vec::new()), Fixed(Vec::new()));
+        let error = source.list_activities().unwrap_err();
+        assert!(matches!(error, error::CommandFailed { .. }));
+        assert_that!(error.to_string().matches("unreachable").count()).is_equal_to(2);
+    }
+    #[test]
+    fn source_is_chosen_from_the_environment() {
+        temp_env::with_var("KAS_ACTIVITY_SOURCE", Some("cli"), || {
+            assert_that!(SourceKind::from_env().unwrap()).is_equal_to(SourceKind::Cli);
+        });
+        temp_env::with_var("KAS_ACTIVITY_SOURCE", Some("carrier-pigeon"), || {
+            let error = SourceKind::from_env().unwrap_err();
+            assert!(matches!(
+                error,
+                error::InvalidValue { value, .. } if value == "carrier-pigeon"
+            ));
+        });
+        temp_env::with_var("KAS_ACTIVITY_SOURCE", None::<&str>, || {
+            assert_that!(SourceKind::from_env().unwrap()).is_equal_to(SourceKind::Auto);
+        });
+    }
+    #[test]
+    fn dbus_states_map_to_activity_states() {
+        assert_that!(state_from_dbus(2)).is_equal_to(ActivityState::Running);
+        assert_that!(state_from_dbus(4)).is_equal_to(ActivityState::Stopped);
+        assert_that!(state_from_dbus(0)).is_equal_to(ActivityState::Unknown);
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error;
+
+const APP_DIR_NAME: &str = "kas-selector";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppDir {
+    Cache,
+    Config,
+    State,
+}
+
+impl AppDir {
+    const fn xdg_var(self) -> &'static str {
+        match self {
+            Self::Cache => "XDG_CACHE_HOME",
+            Self::Config => "XDG_CONFIG_HOME",
+            Self::State => "XDG_STATE_HOME",
+        }
+    }
+    const fn home_fallback(self) -> &'static str {
+        match self {
+            Self::Cache => ".cache",
+            Self::Config => ".config",
+            Self::State => ".local/state",
+        }
+    }
+    pub fn file(self, filename: &str) -> Option<PathBuf> {
+        env::var(self.xdg_var())
+            .ok()
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var("HOME")
+                    .ok()
+                    .filter(|dir| !dir.is_empty())
+                    .map(|home| PathBuf::from(home).join(self.home_fallback()))
+            })
+            .map(|dir| dir.join(APP_DIR_NAME).join(filename))
+    }
+}
+
+pub fn write_file(
+    path: &Path,
+    contents: &str,
+    context: &'static str,
+) -> Result<(), error::Application> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|source| error::Io { context, source })?;
+    }
+    fs::write(path, contents).map_err(|source| error::Io { context, source })
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn files_prefer_the_xdg_variable_over_home() {
+        temp_env::with_vars(
+            [
+                ("XDG_STATE_HOME", Some("/state")),
+                ("XDG_CACHE_HOME", Some("")),
+                ("XDG_CONFIG_HOME", None),
+                ("HOME", Some("/home/me")),
+            ],
+            || {
+                assert_that!(AppDir::State.file("state.toml"))
+                    .is_equal_to(Some(PathBuf::from("/state/kas-selector/state.toml")));
+                assert_that!(AppDir::Cache.file("window.toml")).is_equal_to(Some(PathBuf::from(
+                    "/home/me/.cache/kas-selector/window.toml",
+                )));
+                assert_that!(AppDir::Config.file("config.toml")).is_equal_to(Some(PathBuf::from(
+                    "/home/me/.config/kas-selector/config.toml",
+                )));
+            },
+        );
+        temp_env::with_vars([("XDG_STATE_HOME", None::<&str>), ("HOME", None)], || {
+            assert_that!(AppDir::State.file("state.toml")).is_none();
+        });
+    }
+    #[test]
+    fn write_file_creates_missing_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a/b/file.toml");
+
+        write_file(&path, "x = 1", "writing test file").unwrap();
+
+        assert_that!(fs::read_to_string(&path).unwrap()).is_equal_to("x = 1".to_string());
+    }
+}
@@ -0,0 +1,1344 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use strum::IntoEnumIterator;
+
+use crate::{
+    activity::{self, Activity, ActivityEvent, SortOrder},
+    recovery::Snapshot,
+    transfer::{ConflictStrategy, ImportSummary, ScriptExport},
+};
+
+/// How many changes can be undone; older ones are forgotten.
+const UNDO_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitChoice {
+    Cancel,
+    Discard,
+    Save,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitAction {
+    Quit,
+    Stay,
+    DiscardAndQuit,
+    SaveAndQuit,
+}
+
+#[derive(Debug)]
+struct UndoEntry {
+    previous: Vec<(String, ActivityEvent, Vec<PathBuf>)>,
+}
+
+#[derive(Debug)]
+pub struct AppState {
+    activities: Vec<Activity>,
+    /// The activities as last loaded or saved, i.e. as they should be on disk.
+    saved: Vec<Activity>,
+    selected_activity_index: usize,
+    multi_select: bool,
+    batch_selection: BTreeSet<usize>,
+    sort_by_name: bool,
+    sort_by_status: bool,
+    listed_order: Vec<String>,
+    filter: String,
+    filtered_indices: Vec<usize>,
+    undo_stack: Vec<UndoEntry>,
+    redo_stack: Vec<UndoEntry>,
+    is_dirty: bool,
+    is_loading: bool,
+    is_stale: bool,
+    /// Bumped when a save ends, so the end of its quiet period can be matched to it.
+    save_generation: usize,
+    save_settling: bool,
+    /// Set when files change before a save has settled, as they may not all be the save's own.
+    changed_while_settling: bool,
+    /// The event whose scripts are waiting for the delete to be confirmed.
+    pending_delete_event: Option<ActivityEvent>,
+    read_only: bool,
+}
+
+impl AppState {
+    pub const fn loading() -> Self {
+        Self {
+            activities: Vec::new(),
+            saved: Vec::new(),
+            selected_activity_index: 0,
+            multi_select: false,
+            batch_selection: BTreeSet::new(),
+            sort_by_name: false,
+            sort_by_status: false,
+            listed_order: Vec::new(),
+            filter: String::new(),
+            filtered_indices: Vec::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            is_dirty: false,
+            is_loading: true,
+            is_stale: false,
+            save_generation: 0,
+            save_settling: false,
+            changed_while_settling: false,
+            pending_delete_event: None,
+            read_only: false,
+        }
+    }
+    pub fn activities(&self) -> &[Activity] {
+        &self.activities
+    }
+    pub const fn has_no_activities(&self) -> bool {
+        !self.is_loading && self.activities.is_empty()
+    }
+    pub fn configured_count(&self) -> usize {
+        self.activities
+            .iter()
+            .filter(|activity| activity.script_count() > 0)
+            .count()
+    }
+    pub fn saved_activities(&self) -> &[Activity] {
+        &self.saved
+    }
+    pub const fn selected_activity_index(&self) -> usize {
+        self.selected_activity_index
+    }
+    pub fn selected_activity(&self) -> Option<&Activity> {
+        self.activities.get(self.selected_activity_index)
+    }
+    pub fn selected_script(&self, event: ActivityEvent) -> Option<&PathBuf> {
+        self.selected_activity()?.get_script(&event)
+    }
+    pub fn selected_scripts(&self, event: ActivityEvent) -> &[PathBuf] {
+        self.selected_activity()
+            .map(|activity| activity.scripts(&event))
+            .unwrap_or_default()
+    }
+    pub const fn is_sorted_by_name(&self) -> bool {
+        self.sort_by_name
+    }
+    pub const fn is_sorted_by_status(&self) -> bool {
+        self.sort_by_status
+    }
+    pub fn filtered_indices(&self) -> &[usize] {
+        &self.filtered_indices
+    }
+    pub fn filtered_position(&self) -> Option<usize> {
+        self.filtered_indices
+            .iter()
+            .position(|&index| index == self.selected_activity_index)
+    }
+    pub const fn is_multi_select(&self) -> bool {
+        self.multi_select
+    }
+    /// Indices of the activities that edits apply to. The selected activity is always included.
+    pub fn target_indices(&self) -> BTreeSet<usize> {
+        let mut targets = if self.multi_select {
+            self.batch_selection.clone()
+        } else {
+            BTreeSet::new()
+        };
+        if self.selected_activity().is_some() {
+            targets.insert(self.selected_activity_index);
+        }
+        targets
+    }
+    pub const fn is_loading(&self) -> bool {
+        self.is_loading
+    }
+    pub const fn is_stale(&self) -> bool {
+        self.is_stale
+    }
+    pub const fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty() && !self.is_loading && !self.read_only
+    }
+    pub const fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty() && !self.is_loading && !self.read_only
+    }
+    pub const fn is_dirty(&self) -> bool {
+        self.is_dirty
+    }
+    pub const fn can_save(&self) -> bool {
+        self.is_dirty && !self.is_loading && !self.read_only
+    }
+    pub fn can_edit(&self) -> bool {
+        !self.is_loading && self.selected_activity().is_some()
+    }
+    pub fn can_modify(&self) -> bool {
+        self.can_edit() && !self.read_only
+    }
+    pub const fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+    pub const fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+    pub const fn select(&mut self, index: usize) {
+        if index < self.activities.len() {
+            self.selected_activity_index = index;
+        }
+    }
+    pub fn select_id(&mut self, id: &str) {
+        if let Some(index) = index_of_id(&self.activities, id) {
+            self.selected_activity_index = index;
+        }
+    }
+    pub fn select_filtered(&mut self, position: usize) {
+        if let Some(&index) = self.filtered_indices.get(position) {
+            self.select(index);
+        }
+    }
+    /// When the selected activity is filtered out, the first match is selected instead.
+    pub fn set_filter(&mut self, query: &str) {
+        query.clone_into(&mut self.filter);
+        self.apply_filter();
+    }
+    fn apply_filter(&mut self) {
+        let query = self.filter.to_lowercase();
+        self.filtered_indices = self
+            .activities
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| a.name().to_lowercase().contains(&query))
+            .map(|(index, _)| index)
+            .collect();
+        if self.filtered_position().is_none()
+            && let Some(&index) = self.filtered_indices.first()
+        {
+            self.selected_activity_index = index;
+        }
+    }
+    pub fn set_multi_select(&mut self, enabled: bool) {
+        self.multi_select = enabled;
+        if !enabled {
+            self.batch_selection.clear();
+        }
+    }
+    pub fn set_batch_selected(&mut self, index: usize, selected: bool) {
+        if index >= self.activities.len() || index == self.selected_activity_index {
+            return;
+        }
+        if selected {
+            self.batch_selection.insert(index);
+        } else {
+            self.batch_selection.remove(&index);
+        }
+    }
+    pub fn set_sort_by_name(&mut self, enabled: bool) {
+        self.sort_by_name = enabled;
+        self.resort();
+    }
+    pub fn set_sort_by_status(&mut self, enabled: bool) {
+        self.sort_by_status = enabled;
+        self.resort();
+    }
+    fn resort(&mut self) {
+        let selected_id = self.selected_activity().map(|a| a.id().clone());
+        let batch_ids: Vec<String> = self
+            .batch_selection
+            .iter()
+            .filter_map(|&index| self.activities.get(index).map(|a| a.id().clone()))
+            .collect();
+        self.sort_activities();
+        self.selected_activity_index = selected_id
+            .and_then(|id| index_of_id(&self.activities, &id))
+            .unwrap_or(0);
+        self.batch_selection = batch_ids
+            .iter()
+            .filter_map(|id| index_of_id(&self.activities, id))
+            .collect();
+        self.apply_filter();
+    }
+    pub const fn sort_order(&self) -> Option<SortOrder> {
+        match (self.sort_by_name, self.sort_by_status) {
+            (true, true) => Some(SortOrder::ByStatusThenName),
+            (true, false) => Some(SortOrder::ByName),
+            (false, true) => Some(SortOrder::ByStatus),
+            (false, false) => None,
+        }
+    }
+    fn sort_activities(&mut self) {
+        let listed_order = &self.listed_order;
+        self.activities
+            .sort_by_key(|a| listed_order.iter().position(|id| id == a.id()));
+        if let Some(order) = self.sort_order() {
+            activity::sort_activities(&mut self.activities, order);
+        }
+    }
+    pub fn load_finished(&mut self, activities: Vec<Activity>) {
+        let previous_id = self.selected_activity().map(|a| a.id().clone());
+        self.listed_order = activities.iter().map(|a| a.id().clone()).collect();
+        self.saved.clone_from(&activities);
+        self.activities = activities;
+        self.sort_activities();
+        self.selected_activity_index = previous_id
+            .and_then(|id| index_of_id(&self.activities, &id))
+            .unwrap_or(0);
+        self.batch_selection.clear();
+        self.apply_filter();
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.is_dirty = false;
+        self.is_loading = false;
+        self.is_stale = false;
+    }
+    pub const fn load_failed(&mut self) {
+        self.is_loading = false;
+    }
+    pub const fn reload_started(&mut self) {
+        self.is_loading = true;
+    }
+    /// Changes seen while loading or saving are our own and are ignored. Changes seen before
+    /// the last save has settled are held until it does.
+    pub const fn files_changed(&mut self) -> bool {
+        if self.is_loading {
+            return false;
+        }
+        if self.save_settling {
+            self.changed_while_settling = true;
+            return false;
+        }
+        if self.is_dirty {
+            self.is_stale = true;
+            return false;
+        }
+        true
+    }
+    pub fn set_script(&mut self, event: ActivityEvent, script: PathBuf) {
+        self.edit(event, |_| vec![script.clone()]);
+    }
+    pub fn add_script(&mut self, event: ActivityEvent, script: PathBuf) {
+        self.edit(event, |scripts| {
+            let mut scripts = scripts.to_vec();
+            if !scripts.contains(&script) {
+                scripts.push(script.clone());
+            }
+            scripts
+        });
+    }
+    pub fn remove_script(&mut self, event: ActivityEvent, script: &Path) {
+        self.edit(event, |scripts| {
+            scripts.iter().filter(|s| *s != script).cloned().collect()
+        });
+    }
+    pub fn delete_script(&mut self, event: ActivityEvent) {
+        self.edit(event, |_| Vec::new());
+    }
+    /// Returns whether there is anything to delete, i.e. whether to ask for confirmation.
+    pub fn request_delete_script(&mut self, event: ActivityEvent) -> bool {
+        self.pending_delete_event =
+            (self.can_modify() && !self.selected_scripts(event).is_empty()).then_some(event);
+        self.pending_delete_event.is_some()
+    }
+    pub fn confirm_delete_script(&mut self) {
+        if let Some(event) = self.pending_delete_event.take() {
+            self.delete_script(event);
+        }
+    }
+    pub const fn cancel_delete_script(&mut self) {
+        self.pending_delete_event = None;
+    }
+    pub fn assign_all_count(&self, event: ActivityEvent, script: &Path) -> usize {
+        self.activities
+            .iter()
+            .filter(|activity| activity.scripts(&event) != [script])
+            .count()
+    }
+    pub fn set_script_for_all(&mut self, event: ActivityEvent, script: &Path) {
+        let changes = self
+            .activities
+            .iter()
+            .enumerate()
+            .filter(|(_, activity)| activity.scripts(&event) != [script])
+            .map(|(index, _)| (index, event, vec![script.to_path_buf()]))
+            .collect();
+        self.apply_changes(changes);
+    }
+    fn edit(&mut self, event: ActivityEvent, change: impl Fn(&[PathBuf]) -> Vec<PathBuf>) {
+        let changes = self
+            .target_indices()
+            .into_iter()
+            .filter_map(|index| {
+                let scripts = self.activities.get(index)?.scripts(&event);
+                let changed = change(scripts);
+                (changed != scripts).then_some((index, event, changed))
+            })
+            .collect();
+        self.apply_changes(changes);
+    }
+    pub fn copy_scripts_from(&mut self, source: usize) {
+        let targets = self.target_indices();
+        self.copy_scripts(source, targets, true);
+    }
+    pub fn copy_scripts_to(&mut self, targets: &[usize], overwrite: bool) {
+        self.copy_scripts(
+            self.selected_activity_index,
+            targets.iter().copied(),
+            overwrite,
+        );
+    }
+    fn copy_scripts(
+        &mut self,
+        source: usize,
+        targets: impl IntoIterator<Item = usize>,
+        overwrite: bool,
+    ) {
+        if self.read_only {
+            return;
+        }
+        let Some(source_activity) = self.activities.get(source).cloned() else {
+            return;
+        };
+        let mut previous = Vec::new();
+        for index in targets {
+            if index == source {
+                continue;
+            }
+            if let Some(activity) = self.activities.get_mut(index) {
+                for event in ActivityEvent::iter() {
+                    let scripts = source_activity.scripts(&event);
+                    let current = activity.scripts(&event);
+                    if scripts.is_empty()
+                        || current == scripts
+                        || (!current.is_empty() && !overwrite)
+                    {
+                        continue;
+                    }
+                    previous.push((activity.id().clone(), event, current.to_vec()));
+                }
+                activity.merge_scripts_from(&source_activity, overwrite);
+            }
+        }
+        self.push_undo(previous);
+    }
+    fn apply_changes(&mut self, changes: Vec<(usize, ActivityEvent, Vec<PathBuf>)>) {
+        if self.read_only {
+            return;
+        }
+        let mut previous = Vec::new();
+        for (index, event, scripts) in changes {
+            if let Some(activity) = self.activities.get_mut(index) {
+                previous.push((
+                    activity.id().clone(),
+                    event,
+                    activity.scripts(&event).to_vec(),
+                ));
+                activity.set_scripts(event, scripts);
+            }
+        }
+        self.push_undo(previous);
+    }
+    fn push_undo(&mut self, mut previous: Vec<(String, ActivityEvent, Vec<PathBuf>)>) {
+        if !previous.is_empty() {
+            let selected = self.selected_activity_index;
+            previous.sort_by_key(|(id, _, _)| index_of_id(&self.activities, id) != Some(selected));
+            self.undo_stack.push(UndoEntry { previous });
+            if self.undo_stack.len() > UNDO_LIMIT {
+                self.undo_stack.remove(0);
+            }
+            self.redo_stack.clear();
+        }
+        self.refresh_dirty();
+    }
+    pub fn undo(&mut self) {
+        if let Some(entry) = self.undo_stack.pop() {
+            let inverse = self.revert(entry);
+            self.redo_stack.push(inverse);
+        }
+    }
+    pub fn redo(&mut self) {
+        if let Some(entry) = self.redo_stack.pop() {
+            let inverse = self.revert(entry);
+            self.undo_stack.push(inverse);
+        }
+    }
+    fn revert(&mut self, entry: UndoEntry) -> UndoEntry {
+        let mut current = Vec::with_capacity(entry.previous.len());
+        for (id, event, scripts) in entry.previous {
+            if let Some(index) = index_of_id(&self.activities, &id) {
+                let scripts_now = self.activities[index].scripts(&event).to_vec();
+                self.activities[index].set_scripts(event, scripts);
+                current.push((id, event, scripts_now));
+            }
+        }
+        if let Some(index) = current
+            .first()
+            .and_then(|(id, _, _)| index_of_id(&self.activities, id))
+        {
+            self.selected_activity_index = index;
+            self.apply_filter();
+        }
+        self.refresh_dirty();
+        UndoEntry { previous: current }
+    }
+    fn refresh_dirty(&mut self) {
+        self.is_dirty = self.activities.iter().any(|activity| {
+            let saved = self.saved.iter().find(|saved| saved.id() == activity.id());
+            ActivityEvent::iter().any(|event| {
+                activity.scripts(&event)
+                    != saved.map(|saved| saved.scripts(&event)).unwrap_or_default()
+            })
+        });
+    }
+    pub fn import(&mut self, export: &ScriptExport, strategy: ConflictStrategy) -> ImportSummary {
+        if self.read_only {
+            return ImportSummary::default();
+        }
+        let mut imported = self.activities.clone();
+        let summary = export.merge_into(&mut imported, strategy);
+        self.apply_changes(changes_between(&self.activities, &imported));
+        summary
+    }
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        let mut restored = self.activities.clone();
+        snapshot.restore_into(&mut restored);
+        self.apply_changes(changes_between(&self.activities, &restored));
+    }
+    pub const fn save_started(&mut self) {
+        self.is_loading = true;
+    }
+    /// Records the activities as saved. Earlier edits can no longer be undone.
+    pub fn save_finished(&mut self) {
+        self.saved.clone_from(&self.activities);
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.is_dirty = false;
+        self.save_ended();
+    }
+    pub const fn save_failed(&mut self) {
+        self.save_ended();
+    }
+    const fn save_ended(&mut self) {
+        self.is_loading = false;
+        self.save_generation += 1;
+        self.save_settling = true;
+    }
+    pub const fn save_generation(&self) -> usize {
+        self.save_generation
+    }
+    /// Returns whether to reload for changes held while the save was settling.
+    pub const fn save_settled(&mut self, generation: usize) -> bool {
+        if generation != self.save_generation {
+            return false;
+        }
+        self.save_settling = false;
+        let changed = self.changed_while_settling;
+        self.changed_while_settling = false;
+        changed && self.files_changed()
+    }
+    /// `None` is a dismissed unsaved-changes dialog, which keeps the edits like cancelling.
+    pub const fn exit_action(&self, choice: Option<ExitChoice>) -> ExitAction {
+        if !self.can_save() {
+            return ExitAction::Quit;
+        }
+        match choice {
+            Some(ExitChoice::Save) => ExitAction::SaveAndQuit,
+            Some(ExitChoice::Discard) => ExitAction::DiscardAndQuit,
+            Some(ExitChoice::Cancel) | None => ExitAction::Stay,
+        }
+    }
+}
+
+fn changes_between(
+    current: &[Activity],
+    updated: &[Activity],
+) -> Vec<(usize, ActivityEvent, Vec<PathBuf>)> {
+    current
+        .iter()
+        .zip(updated)
+        .enumerate()
+        .flat_map(|(index, (current, updated))| {
+            ActivityEvent::iter()
+                .filter(|event| current.scripts(event) != updated.scripts(event))
+                .map(move |event| (index, event, updated.scripts(&event).to_vec()))
+        })
+        .collect()
+}
+
+fn index_of_id(activities: &[Activity], id: &str) -> Option<usize> {
+    activities.iter().position(|a| a.id() == id)
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+
+    use super::*;
+    use crate::{
+        activity::{EventMap, ScriptMap},
+        fixture::known_activities,
+    };
+
+    #[test]
+    fn loading_state_blocks_editing_and_saving() {
+        let mut state = AppState::loading();
+        assert_that!(state.is_loading()).is_true();
+        assert_that!(state.can_edit()).is_false();
+        assert_that!(state.can_save()).is_false();
+
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        state.load_failed();
+        assert_that!(state.can_save()).is_false();
+    }
+    #[test]
+    fn load_finished_enables_editing() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+
+        assert_that!(state.is_loading()).is_false();
+        assert_that!(state.can_edit()).is_true();
+        assert_that!(state.can_save()).is_false();
+        assert_that!(state.selected_activity().unwrap().id().as_str()).is_equal_to("abc-12d-a");
+    }
+    #[test]
+    fn loading_no_activities_leaves_nothing_to_edit_or_save() {
+        let mut state = AppState::loading();
+        assert_that!(state.has_no_activities()).is_false();
+        state.load_finished(Vec::new());
+
+        assert_that!(state.has_no_activities()).is_true();
+        assert_that!(state.selected_activity()).is_none();
+        assert_that!(state.can_edit()).is_false();
+
+        state.select(0);
+        state.select_filtered(0);
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        state.delete_script(ActivityEvent::Stopped);
+        state.set_filter("work");
+        assert_that!(state.selected_activity()).is_none();
+        assert_that!(state.can_save()).is_false();
+        assert_that!(state.filtered_position()).is_none();
+    }
+    #[test]
+    fn selected_script_follows_the_selection() {
+        let mut activities = known_activities();
+        activities[1].set_script(ActivityEvent::Stopped, "/scripts/off.sh".into());
+        let mut state = AppState::loading();
+        assert_that!(state.selected_script(ActivityEvent::Stopped)).is_none();
+        state.load_finished(activities);
+
+        assert_that!(state.selected_script(ActivityEvent::Stopped)).is_none();
+        state.select(1);
+        assert_that!(state.selected_script(ActivityEvent::Stopped).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/off.sh")));
+        assert_that!(state.selected_script(ActivityEvent::Started)).is_none();
+    }
+    #[test]
+    fn configured_count_follows_assignments() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        assert_that!(state.configured_count()).is_equal_to(0);
+
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        state.set_script(ActivityEvent::Stopped, "/scripts/b.sh".into());
+        assert_that!(state.configured_count()).is_equal_to(1);
+
+        state.delete_script(ActivityEvent::Activated);
+        state.delete_script(ActivityEvent::Stopped);
+        assert_that!(state.configured_count()).is_equal_to(0);
+    }
+    #[test]
+    fn load_failed_leaves_nothing_to_edit() {
+        let mut state = AppState::loading();
+        state.load_failed();
+
+        assert_that!(state.is_loading()).is_false();
+        assert_that!(state.can_edit()).is_false();
+        assert_that!(state.selected_activity()).is_none();
+    }
+    #[test]
+    fn failed_reload_keeps_unsaved_edits() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_script(ActivityEvent::Started, "/scripts/a.sh".into());
+
+        state.reload_started();
+        assert_that!(state.can_edit()).is_false();
+        state.load_failed();
+
+        assert_that!(state.can_edit()).is_true();
+        assert_that!(state.is_dirty()).is_true();
+        assert_that!(state.can_undo()).is_true();
+    }
+    #[test]
+    fn edits_mark_dirty_until_saved() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.select(1);
+        state.set_script(ActivityEvent::Started, "/scripts/a.sh".into());
+
+        assert_that!(state.can_save()).is_true();
+        assert_that!(
+            state.activities()[1]
+                .get_script(&ActivityEvent::Started)
+                .is_some()
+        )
+        .is_true();
+
+        state.save_started();
+        assert_that!(state.can_save()).is_false();
+        assert_that!(state.can_edit()).is_false();
+        assert_that!(state.saved_activities()[1].get_script(&ActivityEvent::Started)).is_none();
+        state.save_finished();
+        assert_that!(state.can_save()).is_false();
+        assert_that!(state.can_edit()).is_true();
+        assert_that!(state.saved_activities()[1].get_script(&ActivityEvent::Started)).is_some();
+    }
+    #[test]
+    fn failed_save_keeps_edits_to_retry() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_script(ActivityEvent::Started, "/scripts/a.sh".into());
+
+        state.save_started();
+        state.save_failed();
+        assert_that!(state.is_dirty()).is_true();
+        assert_that!(state.can_save()).is_true();
+        assert_that!(state.can_undo()).is_true();
+        assert_that!(state.saved_activities()[0].get_script(&ActivityEvent::Started)).is_none();
+    }
+    #[test]
+    fn import_marks_dirty_only_when_something_matched() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        let unrelated = ScriptExport::from_activities(&[Activity::new(
+            "Gone".into(),
+            "gone-id".into(),
+            EventMap::new(),
+        )]);
+
+        assert_that!(
+            state
+                .import(&unrelated, ConflictStrategy::ImportedWins)
+                .unmatched_activities()
+                .clone()
+        )
+        .contains_exactly(["Gone".to_string()]);
+        assert_that!(state.can_save()).is_false();
+
+        let mut events = EventMap::new();
+        events.insert(
+            ActivityEvent::Activated,
+            PathBuf::from("/scripts/a.sh").into(),
+        );
+        let matching = ScriptExport::from_activities(&[Activity::new(
+            "Work".into(),
+            "abc-12d-a".into(),
+            events,
+        )]);
+        assert_that!(
+            *state
+                .import(&matching, ConflictStrategy::ImportedWins)
+                .matched()
+        )
+        .is_equal_to(1);
+        assert_that!(state.can_save()).is_true();
+
+        state.undo();
+        assert_that!(state.can_save()).is_false();
+        state.redo();
+        assert_that!(state.activities()[0].get_script(&ActivityEvent::Activated)).is_some();
+    }
+    #[test]
+    fn single_selection_edits_only_the_selected_activity() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_batch_selected(1, true);
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+
+        assert_that!(state.activities()[0].get_script(&ActivityEvent::Activated)).is_some();
+        assert_that!(state.activities()[1].get_script(&ActivityEvent::Activated)).is_none();
+    }
+    #[test]
+    fn multi_select_applies_edits_to_every_target() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_multi_select(true);
+        state.set_batch_selected(1, true);
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+
+        assert_that!(state.target_indices().into_iter().collect::<Vec<_>>())
+            .contains_exactly([0, 1]);
+        for activity in state.activities() {
+            assert_that!(activity.get_script(&ActivityEvent::Activated).cloned())
+                .is_equal_to(Some(PathBuf::from("/scripts/a.sh")));
+        }
+
+        state.delete_script(ActivityEvent::Activated);
+        for activity in state.activities() {
+            assert_that!(activity.get_script(&ActivityEvent::Activated)).is_none();
+        }
+    }
+    #[test]
+    fn disabling_multi_select_clears_batch() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_multi_select(true);
+        state.set_batch_selected(1, true);
+        state.set_batch_selected(7, true);
+        state.set_multi_select(false);
+
+        assert_that!(state.target_indices().into_iter().collect::<Vec<_>>()).contains_exactly([0]);
+    }
+    #[test]
+    fn files_changed_reloads_only_without_unsaved_edits() {
+        let mut state = AppState::loading();
+        assert_that!(state.files_changed()).is_false();
+        state.load_finished(known_activities());
+        assert_that!(state.files_changed()).is_true();
+        assert_that!(state.is_stale()).is_false();
+
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        assert_that!(state.files_changed()).is_false();
+        assert_that!(state.is_stale()).is_true();
+
+        state.reload_started();
+        state.load_finished(known_activities());
+        assert_that!(state.is_stale()).is_false();
+        assert_that!(state.can_save()).is_false();
+    }
+    #[test]
+    fn files_changed_waits_for_a_save_to_settle() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+
+        state.save_started();
+        assert_that!(state.files_changed()).is_false();
+        state.save_finished();
+        let first = state.save_generation();
+        assert_that!(state.save_settled(first)).is_false();
+
+        state.save_started();
+        state.save_finished();
+        let second = state.save_generation();
+        assert_that!(state.files_changed()).is_false();
+        assert_that!(state.save_settled(first)).is_false();
+        assert_that!(state.files_changed()).is_false();
+        assert_that!(state.save_settled(second)).is_true();
+        assert_that!(state.files_changed()).is_true();
+        assert_that!(state.is_stale()).is_false();
+
+        state.set_script(ActivityEvent::Activated, "/scripts/b.sh".into());
+        state.save_started();
+        state.save_failed();
+        assert_that!(state.files_changed()).is_false();
+        assert_that!(state.save_settled(state.save_generation())).is_false();
+        assert_that!(state.is_stale()).is_true();
+    }
+    #[test]
+    fn restore_marks_recovered_edits_dirty() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.restore(&Snapshot::from_activities(&known_activities()));
+        assert_that!(state.is_dirty()).is_false();
+
+        let mut edited = known_activities();
+        edited[1].set_script(ActivityEvent::Started, "/scripts/a.sh".into());
+        state.restore(&Snapshot::from_activities(&edited));
+
+        assert_that!(state.is_dirty()).is_true();
+        assert_that!(state.activities()[1].get_script(&ActivityEvent::Started)).is_some();
+
+        state.undo();
+        assert_that!(state.is_dirty()).is_false();
+        assert_that!(state.activities()[1].get_script(&ActivityEvent::Started)).is_none();
+    }
+    #[test]
+    fn index_of_id_finds_matching_activity() {
+        let activities = known_activities();
+        assert_that!(index_of_id(&activities, "abc-12d-b")).is_equal_to(Some(1));
+        assert_that!(index_of_id(&activities, "gone-id")).is_none();
+    }
+    #[test]
+    fn select_id_ignores_unknown_activities() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+
+        state.select_id("gone-id");
+        assert_that!(state.selected_activity_index()).is_equal_to(0);
+        state.select_id("abc-12d-b");
+        assert_that!(state.selected_activity().unwrap().id().as_str()).is_equal_to("abc-12d-b");
+    }
+    #[test]
+    fn reload_keeps_selected_activity() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.select(1);
+
+        let mut reordered = known_activities();
+        reordered.reverse();
+        reordered.insert(
+            0,
+            Activity::new("Admin".into(), "abc-12d-c".into(), EventMap::new()),
+        );
+        state.reload_started();
+        state.load_finished(reordered);
+        assert_that!(state.selected_activity().unwrap().id().as_str()).is_equal_to("abc-12d-b");
+
+        state.reload_started();
+        state.load_finished(vec![Activity::new(
+            "Admin".into(),
+            "abc-12d-c".into(),
+            EventMap::new(),
+        )]);
+        assert_that!(state.selected_activity_index()).is_equal_to(0);
+    }
+    #[test]
+    fn sorting_by_status_puts_running_activities_first() {
+        let listed = Activity::from_activity_data(
+            r#"
+            [STOPPED] abc-12d-a Work (icon-a)
+            [RUNNING] abc-12d-b Personal (icon-b)
+            [STOPPED] abc-12d-c Admin (icon-c)
+            [CURRENT] abc-12d-d Play (icon-d)
+            "#,
+            ScriptMap::new(),
+        )
+        .unwrap();
+        let mut state = AppState::loading();
+        state.load_finished(listed);
+        state.select(2);
+        let names = |state: &AppState| -> Vec<String> {
+            state
+                .activities()
+                .iter()
+                .map(|a| a.name().clone())
+                .collect()
+        };
+
+        state.set_sort_by_status(true);
+        assert_that!(names(&state))
+            .contains_exactly(["Play", "Personal", "Work", "Admin"].map(String::from));
+        assert_that!(state.selected_activity().unwrap().name().as_str()).is_equal_to("Admin");
+
+        state.set_sort_by_name(true);
+        assert_that!(state.sort_order()).is_equal_to(Some(SortOrder::ByStatusThenName));
+        assert_that!(names(&state))
+            .contains_exactly(["Play", "Personal", "Admin", "Work"].map(String::from));
+
+        state.set_sort_by_status(false);
+        state.set_sort_by_name(false);
+        assert_that!(names(&state))
+            .contains_exactly(["Work", "Personal", "Admin", "Play"].map(String::from));
+    }
+    #[test]
+    fn sorting_keeps_the_same_activities_selected() {
+        let mut state = AppState::loading();
+        state.set_sort_by_name(true);
+        let mut listed = known_activities();
+        listed.push(Activity::new(
+            "admin".into(),
+            "abc-12d-c".into(),
+            EventMap::new(),
+        ));
+        state.load_finished(listed);
+        let names = |state: &AppState| -> Vec<String> {
+            state
+                .activities()
+                .iter()
+                .map(|a| a.name().clone())
+                .collect()
+        };
+        assert_that!(names(&state))
+            .contains_exactly(["admin", "Personal", "Work"].map(String::from));
+
+        state.select(2);
+        state.set_multi_select(true);
+        state.set_batch_selected(0, true);
+        state.set_sort_by_name(false);
+
+        assert_that!(names(&state))
+            .contains_exactly(["Work", "Personal", "admin"].map(String::from));
+        assert_that!(state.selected_activity().unwrap().name().as_str()).is_equal_to("Work");
+        assert_that!(state.target_indices().into_iter().collect::<Vec<_>>())
+            .contains_exactly([0, 2]);
+    }
+    #[test]
+    fn read_only_state_never_changes_or_saves() {
+        let mut state = AppState::loading();
+        state.set_read_only(true);
+        state.load_finished(known_activities());
+        assert_that!(state.can_edit()).is_true();
+        assert_that!(state.can_modify()).is_false();
+
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        let mut edited = known_activities();
+        edited[1].set_script(ActivityEvent::Started, "/scripts/a.sh".into());
+        state.restore(&Snapshot::from_activities(&edited));
+
+        assert_that!(state.can_save()).is_false();
+        assert_that!(state.activities()[0].script_count()).is_equal_to(0);
+        assert_that!(state.activities()[1].script_count()).is_equal_to(0);
+    }
+    #[test]
+    fn select_ignores_out_of_range_index() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.select(1);
+        state.select(u32::MAX as usize);
+
+        assert_that!(state.selected_activity_index()).is_equal_to(1);
+    }
+    #[test]
+    fn undo_reverts_edits_across_activity_switches() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        state.select(1);
+        state.set_script(ActivityEvent::Started, "/scripts/b.sh".into());
+        state.select(0);
+
+        state.undo();
+        assert_that!(state.selected_activity_index()).is_equal_to(1);
+        assert_that!(state.activities()[1].get_script(&ActivityEvent::Started)).is_none();
+        assert_that!(state.can_save()).is_true();
+
+        state.undo();
+        assert_that!(state.selected_activity_index()).is_equal_to(0);
+        assert_that!(state.activities()[0].get_script(&ActivityEvent::Activated)).is_none();
+        assert_that!(state.can_save()).is_false();
+
+        state.redo();
+        state.redo();
+        assert_that!(
+            state.activities()[0]
+                .get_script(&ActivityEvent::Activated)
+                .cloned()
+        )
+        .is_equal_to(Some(PathBuf::from("/scripts/a.sh")));
+        assert_that!(
+            state.activities()[1]
+                .get_script(&ActivityEvent::Started)
+                .cloned()
+        )
+        .is_equal_to(Some(PathBuf::from("/scripts/b.sh")));
+    }
+    #[test]
+    fn undo_restores_a_deleted_script() {
+        let mut events = EventMap::new();
+        events.insert(
+            ActivityEvent::Stopped,
+            PathBuf::from("/scripts/a.sh").into(),
+        );
+        let mut state = AppState::loading();
+        state.load_finished(vec![Activity::new(
+            "Work".into(),
+            "abc-12d-a".into(),
+            events,
+        )]);
+        state.delete_script(ActivityEvent::Stopped);
+        assert_that!(state.can_save()).is_true();
+
+        state.undo();
+        assert_that!(
+            state.activities()[0]
+                .get_script(&ActivityEvent::Stopped)
+                .cloned()
+        )
+        .is_equal_to(Some(PathBuf::from("/scripts/a.sh")));
+        assert_that!(state.can_save()).is_false();
+    }
+    #[test]
+    fn undo_forgets_the_oldest_changes_past_the_limit() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        for n in 0..=UNDO_LIMIT {
+            state.set_script(ActivityEvent::Activated, format!("/scripts/{n}.sh").into());
+        }
+
+        for _ in 0..UNDO_LIMIT {
+            state.undo();
+        }
+        assert_that!(state.can_undo()).is_false();
+        assert_that!(
+            state
+                .selected_activity()
+                .and_then(|a| a.get_script(&ActivityEvent::Activated))
+                .cloned()
+        )
+        .is_equal_to(Some(PathBuf::from("/scripts/0.sh")));
+        assert_that!(state.is_dirty()).is_true();
+    }
+    #[test]
+    fn edits_cancelling_out_leave_nothing_to_save() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        state.delete_script(ActivityEvent::Activated);
+
+        assert_that!(state.is_dirty()).is_false();
+        assert_that!(state.can_save()).is_false();
+    }
+    #[test]
+    fn new_edit_clears_redo() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        state.undo();
+        state.set_script(ActivityEvent::Stopped, "/scripts/b.sh".into());
+        state.redo();
+
+        assert_that!(state.activities()[0].get_script(&ActivityEvent::Activated)).is_none();
+        assert_that!(state.activities()[0].script_count()).is_equal_to(1);
+    }
+    #[test]
+    fn undo_reverts_a_batch_edit_at_once() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.select(1);
+        state.set_multi_select(true);
+        state.set_batch_selected(0, true);
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        state.set_multi_select(false);
+        state.select(0);
+
+        state.undo();
+        assert_that!(state.selected_activity_index()).is_equal_to(1);
+        assert_that!(state.configured_count()).is_equal_to(0);
+    }
+    #[test]
+    fn undo_is_available_only_with_history() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        assert_that!(state.can_undo()).is_false();
+        assert_that!(state.can_redo()).is_false();
+
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        assert_that!(state.can_undo()).is_true();
+        state.undo();
+        assert_that!(state.can_undo()).is_false();
+        assert_that!(state.can_redo()).is_true();
+
+        state.redo();
+        state.save_started();
+        assert_that!(state.can_undo()).is_false();
+        state.save_finished();
+        assert_that!(state.can_undo()).is_false();
+        assert_that!(state.can_redo()).is_false();
+        assert_that!(state.is_dirty()).is_false();
+    }
+    #[test]
+    fn copy_scripts_from_marks_dirty_and_can_be_undone() {
+        let mut activities = known_activities();
+        activities[1].set_script(ActivityEvent::Started, "/scripts/on.sh".into());
+        activities[0].set_script(ActivityEvent::Stopped, "/scripts/off.sh".into());
+        let mut state = AppState::loading();
+        state.load_finished(activities);
+        state.copy_scripts_from(1);
+
+        assert_that!(state.can_save()).is_true();
+        assert_that!(state.activities()[0].script_count()).is_equal_to(2);
+
+        state.undo();
+        assert_that!(state.activities()[0].get_script(&ActivityEvent::Started)).is_none();
+        assert_that!(state.activities()[0].script_count()).is_equal_to(1);
+        assert_that!(state.can_save()).is_false();
+    }
+    #[test]
+    fn added_and_removed_scripts_keep_the_others_and_can_be_undone() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_script(ActivityEvent::Started, "/scripts/a.sh".into());
+        state.add_script(ActivityEvent::Started, "/scripts/b.sh".into());
+        state.add_script(ActivityEvent::Started, "/scripts/a.sh".into());
+
+        assert_that!(state.selected_scripts(ActivityEvent::Started).to_vec()).contains_exactly([
+            PathBuf::from("/scripts/a.sh"),
+            PathBuf::from("/scripts/b.sh"),
+        ]);
+        assert_that!(state.selected_script(ActivityEvent::Started).cloned())
+            .is_equal_to(Some("/scripts/a.sh".into()));
+
+        state.remove_script(ActivityEvent::Started, Path::new("/scripts/a.sh"));
+        assert_that!(state.selected_scripts(ActivityEvent::Started).to_vec())
+            .contains_exactly([PathBuf::from("/scripts/b.sh")]);
+
+        state.undo();
+        assert_that!(state.selected_scripts(ActivityEvent::Started).len()).is_equal_to(2);
+        state.undo();
+        assert_that!(state.selected_scripts(ActivityEvent::Started).to_vec())
+            .contains_exactly([PathBuf::from("/scripts/a.sh")]);
+        state.undo();
+        assert_that!(state.selected_scripts(ActivityEvent::Started)).is_empty();
+        assert_that!(state.is_dirty()).is_false();
+    }
+    #[test]
+    fn set_script_for_all_updates_every_activity_and_can_be_undone() {
+        let mut activities = known_activities();
+        activities.push(Activity::new(
+            "Play".into(),
+            "abc-12d-c".into(),
+            EventMap::new(),
+        ));
+        activities[1].set_script(ActivityEvent::Activated, "/scripts/on.sh".into());
+        activities[2].set_script(ActivityEvent::Activated, "/scripts/play.sh".into());
+        activities[2].set_script(ActivityEvent::Stopped, "/scripts/off.sh".into());
+        let mut state = AppState::loading();
+        state.load_finished(activities);
+        let script = Path::new("/scripts/on.sh");
+
+        assert_that!(state.assign_all_count(ActivityEvent::Activated, script)).is_equal_to(2);
+        state.set_script_for_all(ActivityEvent::Activated, script);
+
+        assert_that!(state.can_save()).is_true();
+        for activity in state.activities() {
+            assert_that!(activity.get_script(&ActivityEvent::Activated).cloned())
+                .is_equal_to(Some(script.to_path_buf()));
+        }
+        assert_that!(
+            state.activities()[2]
+                .get_script(&ActivityEvent::Stopped)
+                .cloned()
+        )
+        .is_equal_to(Some(PathBuf::from("/scripts/off.sh")));
+        assert_that!(state.assign_all_count(ActivityEvent::Activated, script)).is_equal_to(0);
+
+        state.undo();
+        assert_that!(state.activities()[0].get_script(&ActivityEvent::Activated)).is_none();
+        assert_that!(
+            state.activities()[2]
+                .get_script(&ActivityEvent::Activated)
+                .cloned()
+        )
+        .is_equal_to(Some(PathBuf::from("/scripts/play.sh")));
+        assert_that!(state.can_save()).is_false();
+    }
+    #[test]
+    fn copy_scripts_from_the_selected_activity_does_nothing() {
+        let mut activities = known_activities();
+        activities[0].set_script(ActivityEvent::Started, "/scripts/on.sh".into());
+        let mut state = AppState::loading();
+        state.load_finished(activities);
+        state.copy_scripts_from(0);
+        state.copy_scripts_from(9);
+
+        assert_that!(state.can_save()).is_false();
+        assert_that!(state.can_undo()).is_false();
+    }
+    #[test]
+    fn copy_scripts_to_keeps_existing_scripts_unless_overwriting() {
+        let mut activities = known_activities();
+        activities.push(Activity::new(
+            "Play".into(),
+            "abc-12d-c".into(),
+            EventMap::new(),
+        ));
+        activities[0].set_script(ActivityEvent::Started, "/scripts/on.sh".into());
+        activities[0].set_script(ActivityEvent::Stopped, "/scripts/off.sh".into());
+        activities[1].set_script(ActivityEvent::Started, "/scripts/home.sh".into());
+        let mut state = AppState::loading();
+        state.load_finished(activities);
+        let script = |state: &AppState, index: usize, event| {
+            state.activities()[index].get_script(&event).cloned()
+        };
+
+        state.copy_scripts_to(&[1, 2], false);
+        assert_that!(script(&state, 1, ActivityEvent::Started))
+            .is_equal_to(Some(PathBuf::from("/scripts/home.sh")));
+        assert_that!(script(&state, 1, ActivityEvent::Stopped))
+            .is_equal_to(Some(PathBuf::from("/scripts/off.sh")));
+        assert_that!(state.activities()[2].script_count()).is_equal_to(2);
+        assert_that!(state.can_save()).is_true();
+
+        state.copy_scripts_to(&[1], true);
+        assert_that!(script(&state, 1, ActivityEvent::Started))
+            .is_equal_to(Some(PathBuf::from("/scripts/on.sh")));
+
+        state.undo();
+        state.undo();
+        assert_that!(state.activities()[1].script_count()).is_equal_to(1);
+        assert_that!(state.activities()[2].script_count()).is_equal_to(0);
+        assert_that!(state.can_save()).is_false();
+    }
+    #[test]
+    fn reload_clears_undo_history() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_script(ActivityEvent::Activated, "/scripts/a.sh".into());
+        state.reload_started();
+        state.load_finished(known_activities());
+        state.undo();
+
+        assert_that!(state.configured_count()).is_equal_to(0);
+        assert_that!(state.can_save()).is_false();
+    }
+    #[test]
+    fn deleting_a_script_waits_for_confirmation() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_script(ActivityEvent::Started, "/scripts/a.sh".into());
+
+        assert_that!(state.request_delete_script(ActivityEvent::Stopped)).is_false();
+        assert_that!(state.request_delete_script(ActivityEvent::Started)).is_true();
+        assert_that!(state.selected_scripts(ActivityEvent::Started)).has_length(1);
+        state.cancel_delete_script();
+        state.confirm_delete_script();
+        assert_that!(state.selected_scripts(ActivityEvent::Started)).has_length(1);
+
+        assert_that!(state.request_delete_script(ActivityEvent::Started)).is_true();
+        state.confirm_delete_script();
+        assert_that!(state.selected_scripts(ActivityEvent::Started)).is_empty();
+    }
+    #[test]
+    fn exit_action_follows_the_choice_only_with_unsaved_edits() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        assert_that!(state.exit_action(None)).is_equal_to(ExitAction::Quit);
+
+        state.set_script(ActivityEvent::Started, "/scripts/a.sh".into());
+        assert_that!(state.exit_action(Some(ExitChoice::Save)))
+            .is_equal_to(ExitAction::SaveAndQuit);
+        assert_that!(state.exit_action(Some(ExitChoice::Discard)))
+            .is_equal_to(ExitAction::DiscardAndQuit);
+        assert_that!(state.exit_action(Some(ExitChoice::Cancel))).is_equal_to(ExitAction::Stay);
+        assert_that!(state.exit_action(None)).is_equal_to(ExitAction::Stay);
+    }
+    #[test]
+    fn filter_maps_positions_back_to_activities() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        assert_that!(state.filtered_indices().to_vec()).contains_exactly([0, 1]);
+
+        state.set_filter("SON");
+        assert_that!(state.filtered_indices().to_vec()).contains_exactly([1]);
+        assert_that!(state.selected_activity().unwrap().name().as_str()).is_equal_to("Personal");
+        assert_that!(state.filtered_position()).is_equal_to(Some(0));
+
+        state.set_filter("");
+        state.select_filtered(0);
+        assert_that!(state.filtered_indices().to_vec()).contains_exactly([0, 1]);
+        assert_that!(state.selected_activity().unwrap().name().as_str()).is_equal_to("Work");
+    }
+    #[test]
+    fn filter_without_matches_keeps_the_selection() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.select(1);
+        state.set_filter("nothing");
+        state.select_filtered(0);
+
+        assert_that!(state.filtered_indices()).is_empty();
+        assert_that!(state.filtered_position()).is_none();
+        assert_that!(state.selected_activity_index()).is_equal_to(1);
+    }
+    #[test]
+    fn filter_follows_sorting_and_reloads() {
+        let mut state = AppState::loading();
+        state.load_finished(known_activities());
+        state.set_filter("o");
+        state.set_sort_by_name(true);
+        assert_that!(state.filtered_indices().to_vec()).contains_exactly([0, 1]);
+        assert_that!(state.selected_activity().unwrap().name().as_str()).is_equal_to("Work");
+
+        state.set_filter("work");
+        state.reload_started();
+        state.load_finished(known_activities());
+        assert_that!(state.filtered_indices().to_vec()).contains_exactly([1]);
+        assert_that!(state.filtered_position()).is_equal_to(Some(0));
+    }
+}
@@ -0,0 +1,381 @@
+use std::{
+    collections::BTreeMap,
+    ffi::{OsStr, OsString},
+    fs,
+    path::Path,
+    process::Command,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    activity::{Activity, EventMap, PlannedChange, SaveReport},
+    cli,
+    config::Config,
+    error,
+    file_format::Format,
+    file_system::RealFileSystem,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Mapping {
+    activities: BTreeMap<String, EventMap>,
+}
+
+impl Mapping {
+    /// Reads JSON from `.json` files and TOML from everything else.
+    pub fn from_file(path: &Path) -> Result<Self, error::Application> {
+        let source = fs::read_to_string(path).map_err(|e| error::Io {
+            context: "reading mapping file",
+            source: e,
+        })?;
+        match Format::from_path(path).unwrap_or(Format::Toml) {
+            Format::Json => Self::from_json(&source),
+            Format::Toml => Self::from_toml(&source),
+        }
+    }
+    pub fn from_activities(activities: &[Activity]) -> Self {
+        Self {
+            activities: activities
+                .iter()
+                .map(|activity| {
+                    let event_scripts = activity.scripts_by_event().into_iter().collect();
+                    (activity.id().clone(), event_scripts)
+                })
+                .collect(),
+        }
+    }
+    pub fn write_json(&self, path: &Path) -> Result<(), error::Application> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| error::InvalidValue {
+            category: "JSON mapping file",
+            value: e.to_string(),
+        })?;
+        fs::write(path, json).map_err(|e| error::Io {
+            context: "writing mapping file",
+            source: e,
+        })
+    }
+    pub fn from_toml(source: &str) -> Result<Self, error::Application> {
+        toml::from_str(source).map_err(|e| error::InvalidValue {
+            category: "TOML mapping file",
+            value: e.to_string(),
+        })
+    }
+    pub fn from_json(source: &str) -> Result<Self, error::Application> {
+        serde_json::from_str(source).map_err(|e| error::InvalidValue {
+            category: "JSON mapping file",
+            value: e.to_string(),
+        })
+    }
+    pub fn into_activities(self, known: &[Activity]) -> Result<Vec<Activity>, error::Application> {
+        self.activities
+            .into_iter()
+            .map(|(key, event_scripts)| {
+                let activity = cli::find_activity(known, &key)?;
+                // `activated = []` leaves the event without scripts
+                let event_scripts = event_scripts
+                    .into_iter()
+                    .filter(|(_, scripts)| !scripts.is_empty())
+                    .collect();
+                Ok(Activity::new(
+                    activity.name().clone(),
+                    activity.id().clone(),
+                    event_scripts,
+                ))
+            })
+            .collect()
+    }
+}
+
+pub fn run(
+    config: &Config,
+    mapping_path: &Path,
+) -> Result<(Vec<Activity>, SaveReport), error::Application> {
+    if *config.read_only() {
+        return Err(error::UnsupportedValue {
+            category: "apply in read-only mode",
+            value: mapping_path.to_string_lossy().into(),
+        });
+    }
+    let activities = mapped_activities(config, mapping_path)?;
+    let report = Activity::save_activities(
+        &RealFileSystem,
+        config.root_path(),
+        config.script_filename(),
+        &activities,
+        *config.backup(),
+        *config.link_mode(),
+    )?;
+    Ok((activities, report))
+}
+
+pub fn plan(
+    config: &Config,
+    mapping_path: &Path,
+) -> Result<Vec<PlannedChange>, error::Application> {
+    Activity::plan_save(
+        &RealFileSystem,
+        config.root_path(),
+        config.script_filename(),
+        &mapped_activities(config, mapping_path)?,
+        *config.link_mode(),
+    )
+}
+
+fn mapped_activities(
+    config: &Config,
+    mapping_path: &Path,
+) -> Result<Vec<Activity>, error::Application> {
+    let mapping = Mapping::from_file(mapping_path)?;
+    let (known, _) = Activity::from_env(config.root_path(), config.script_filename())?;
+    mapping.into_activities(&known)
+}
+
+/// pkexec clears the environment, so the settings and the session bus address (needed to list
+/// activities) are passed through `env`.
+pub fn elevated_command(
+    config: &Config,
+    program: &Path,
+    mapping_path: &Path,
+    bus_address: Option<&OsStr>,
+) -> Vec<OsString> {
+    let mut command: Vec<OsString> = vec!["pkexec".into(), "env".into()];
+    let mut set = |name: &str, value: &OsStr| {
+        let mut arg = OsString::from(format!("{name}="));
+        arg.push(value);
+        command.push(arg);
+    };
+    set("KAS_ROOT", config.root_path().as_os_str());
+    set(
+        "KAS_SCRIPT_NAME",
+        config.script_filename().as_str().as_ref(),
+    );
+    set(
+        "KAS_SCRIPT_EXTENSIONS",
+        config.script_extensions().join(",").as_ref(),
+    );
+    set("KAS_BACKUP", bool_text(*config.backup()));
+    set("KAS_LINK_MODE", config.link_mode().to_string().as_ref());
+    if let Some(address) = bus_address {
+        set("DBUS_SESSION_BUS_ADDRESS", address);
+    }
+    command.extend([program.into(), "--apply".into(), mapping_path.into()]);
+    command
+}
+
+pub fn run_elevated(command: &[OsString]) -> Result<(), error::Application> {
+    let (program, args) = command.split_first().ok_or_else(|| error::InvalidValue {
+        category: "elevated command",
+        value: String::new(),
+    })?;
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| error::CommandFailed {
+            command: "pkexec",
+            error_text: e.to_string(),
+            source: Some(e),
+        })?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(error::CommandFailed {
+            command: "pkexec",
+            error_text: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            source: None,
+        })
+    }
+}
+
+fn bool_text(value: bool) -> &'static OsStr {
+    OsStr::new(if value { "true" } else { "false" })
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use asserting::prelude::*;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        activity::{ActivityEvent, LinkMode},
+        error::ErrorKind,
+        fixture::known_activities,
+    };
+
+    #[test]
+    fn toml_mapping_resolves_ids_and_names() {
+        let mapping = Mapping::from_toml(
+            r#"
+            [activities.abc-12d-a]
+            activated = "/scripts/work-on.sh"
+            deactivated = "/scripts/work-off.sh"
+
+            [activities.Personal]
+            started = "/scripts/personal.sh"
+            "#,
+        )
+        .unwrap();
+
+        let activities = mapping.into_activities(&known_activities()).unwrap();
+
+        assert_that!(activities.len()).is_equal_to(2);
+        let work = activities.iter().find(|a| a.id() == "abc-12d-a").unwrap();
+        assert_that!(work.name().as_str()).is_equal_to("Work");
+        assert_that!(work.get_script(&ActivityEvent::Activated).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/work-on.sh")));
+        assert_that!(work.get_script(&ActivityEvent::Deactivated).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/work-off.sh")));
+        assert_that!(work.get_script(&ActivityEvent::Started)).is_none();
+        let personal = activities.iter().find(|a| a.id() == "abc-12d-b").unwrap();
+        assert_that!(personal.get_script(&ActivityEvent::Started).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/personal.sh")));
+    }
+    #[test]
+    fn mapping_lists_several_scripts_per_event() {
+        let mapping = Mapping::from_toml(
+            r#"
+            [activities.Work]
+            started = ["/scripts/vpn.sh", "/scripts/mount.sh"]
+            stopped = []
+            "#,
+        )
+        .unwrap();
+
+        let activities = mapping.into_activities(&known_activities()).unwrap();
+
+        assert_that!(activities[0].scripts(&ActivityEvent::Started).to_vec()).contains_exactly([
+            PathBuf::from("/scripts/vpn.sh"),
+            PathBuf::from("/scripts/mount.sh"),
+        ]);
+        assert_that!(activities[0].script_count()).is_equal_to(1);
+    }
+    #[test]
+    fn json_mapping_from_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("mapping.json");
+        fs::write(
+            &path,
+            r#"{"activities": {"Work": {"stopped": "/scripts/work-stop.sh"}}}"#,
+        )
+        .unwrap();
+
+        let activities = Mapping::from_file(&path)
+            .unwrap()
+            .into_activities(&known_activities())
+            .unwrap();
+
+        assert_that!(activities.len()).is_equal_to(1);
+        assert_that!(activities[0].id().as_str()).is_equal_to("abc-12d-a");
+        assert_that!(activities[0].get_script(&ActivityEvent::Stopped).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/work-stop.sh")));
+    }
+    #[test]
+    fn mapping_keys_match_names_ignoring_case_but_not_ambiguously() {
+        let mapping = Mapping::from_toml(
+            r#"
+            [activities.work]
+            activated = "/scripts/work-on.sh"
+            "#,
+        )
+        .unwrap();
+        let activities = mapping.into_activities(&known_activities()).unwrap();
+        assert_that!(activities[0].id().as_str()).is_equal_to("abc-12d-a");
+
+        let mut known = known_activities();
+        known.push(Activity::new(
+            "WORK".into(),
+            "abc-12d-c".into(),
+            EventMap::new(),
+        ));
+        let mapping = Mapping::from_toml(
+            r#"
+            [activities.Work]
+            activated = "/scripts/work-on.sh"
+            "#,
+        )
+        .unwrap();
+        assert_that!(mapping.into_activities(&known).unwrap_err().kind())
+            .is_equal_to(ErrorKind::InvalidInput);
+    }
+    #[test]
+    fn unknown_activity_is_rejected() {
+        let mapping = Mapping::from_toml(
+            r#"
+            [activities.Missing]
+            activated = "/scripts/a.sh"
+            "#,
+        )
+        .unwrap();
+        assert_that!(mapping.into_activities(&known_activities())).is_err();
+    }
+    #[test]
+    fn unknown_event_is_rejected() {
+        let result = Mapping::from_toml(
+            r#"
+            [activities.Work]
+            exploded = "/scripts/a.sh"
+            "#,
+        );
+        assert_that!(result.unwrap_err().kind()).is_equal_to(ErrorKind::InvalidInput);
+    }
+    #[test]
+    fn missing_mapping_file_is_not_found() {
+        let dir = tempdir().unwrap();
+        let result = Mapping::from_file(&dir.path().join("missing.toml"));
+        assert_that!(result.unwrap_err().kind()).is_equal_to(ErrorKind::NotFound);
+    }
+    #[test]
+    fn written_mapping_round_trips_by_id() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("mapping.json");
+        let mut known = known_activities();
+        known[1].set_script(ActivityEvent::Started, "/scripts/personal.sh".into());
+
+        Mapping::from_activities(&known).write_json(&path).unwrap();
+        let activities = Mapping::from_file(&path)
+            .unwrap()
+            .into_activities(&known)
+            .unwrap();
+
+        assert_that!(activities.len()).is_equal_to(2);
+        assert_that!(activities[1].id().as_str()).is_equal_to("abc-12d-b");
+        assert_that!(activities[1].get_script(&ActivityEvent::Started).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/personal.sh")));
+    }
+    #[test]
+    fn elevated_command_passes_settings_through_env() {
+        let config = Config::try_new(
+            "/home/me/activities".into(),
+            "kas-script.sh".parse().unwrap(),
+        )
+        .unwrap()
+        .with_backup(true)
+        .with_link_mode(LinkMode::RelativeSymlink);
+
+        let command = elevated_command(
+            &config,
+            Path::new("/usr/bin/kas-selector"),
+            Path::new("/tmp/mapping.json"),
+            Some(OsStr::new("unix:path=/run/user/1000/bus")),
+        );
+
+        assert_that!(command).contains_exactly([
+            OsString::from("pkexec"),
+            "env".into(),
+            "KAS_ROOT=/home/me/activities".into(),
+            "KAS_SCRIPT_NAME=kas-script.sh".into(),
+            "KAS_SCRIPT_EXTENSIONS=sh".into(),
+            "KAS_BACKUP=true".into(),
+            "KAS_LINK_MODE=relative-symlink".into(),
+            "DBUS_SESSION_BUS_ADDRESS=unix:path=/run/user/1000/bus".into(),
+            "/usr/bin/kas-selector".into(),
+            "--apply".into(),
+            "/tmp/mapping.json".into(),
+        ]);
+    }
+}
@@ -0,0 +1,500 @@
+//! Command-line arguments, and the commands that read and change scripts without a window.
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::{
+    activity::{
+        self, Activity, ActivityEvent, ActivityState, PlannedChange, SaveReport, Scripts, SortOrder,
+    },
+    apply,
+    config::Config,
+    error,
+    file_system::RealFileSystem,
+};
+
+/// Assign scripts to KDE activity events.
+#[derive(Debug, Parser)]
+#[command(name = "kas-selector", version)]
+pub struct Args {
+    /// Run without opening the window, even when a display is available.
+    #[arg(long, global = true)]
+    pub no_gui: bool,
+    /// Show assignments without allowing them to be changed.
+    #[arg(long, global = true)]
+    pub read_only: bool,
+    /// Print the links that would be created, replaced or removed instead of saving.
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    /// Save the scripts described by a TOML or JSON mapping file.
+    #[arg(long, value_name = "MAPPING", conflicts_with = "list")]
+    pub apply: Option<PathBuf>,
+    /// Print every activity and its scripts.
+    #[arg(long)]
+    pub list: bool,
+    /// Print the `--list` output as JSON.
+    #[arg(long, requires = "list")]
+    pub json: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Args {
+    pub fn opens_window(&self, display_available: bool) -> bool {
+        !self.dry_run
+            && !self.list
+            && self.apply.is_none()
+            && self.headless_command(display_available).is_none()
+    }
+    pub fn headless_command(&self, display_available: bool) -> Option<Command> {
+        self.command
+            .clone()
+            .or_else(|| (self.no_gui || !display_available).then_some(Command::List))
+    }
+}
+
+/// A headless command, which prints its result as JSON.
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// List every activity with its scripts.
+    List,
+    /// Show the scripts of one activity, found by id or name.
+    Show { activity: String },
+    /// Link a script to one event, leaving the other events as they are.
+    Set {
+        activity: String,
+        event: String,
+        script: PathBuf,
+    },
+    /// Remove the script of one event.
+    #[command(alias = "unset")]
+    Delete { activity: String, event: String },
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActivityReport {
+    id: String,
+    name: String,
+    state: ActivityState,
+    scripts: BTreeMap<ActivityEvent, Scripts>,
+}
+
+impl From<&Activity> for ActivityReport {
+    fn from(activity: &Activity) -> Self {
+        Self {
+            id: activity.id().clone(),
+            name: activity.name().clone(),
+            state: activity.state(),
+            scripts: activity.scripts_by_event(),
+        }
+    }
+}
+
+pub fn display_available() -> bool {
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+/// Runs whatever `args` ask for without the window, returning the exit code, or `None` when
+/// the window should open instead.
+pub fn run_headless(config: &Config, args: &Args, display_available: bool) -> Option<i32> {
+    if args.dry_run {
+        return Some(run_dry_run(config, args));
+    }
+    if let Some(mapping_path) = &args.apply {
+        return Some(run_apply(config, mapping_path));
+    }
+    if args.list {
+        return Some(run_list(config, args.json));
+    }
+    let command = args.headless_command(display_available)?;
+    Some(run_command(config, &command))
+}
+
+fn run_apply(config: &Config, mapping_path: &Path) -> i32 {
+    match apply::run(config, mapping_path) {
+        Ok((activities, report)) => {
+            print_activities(&activities);
+            println!(
+                "{} created, {} replaced, {} removed, {} unchanged",
+                report.created().len(),
+                report.replaced().len(),
+                report.removed().len(),
+                report.unchanged()
+            );
+            for link in report.replaced() {
+                eprintln!("Replaced existing entry: {}", link.display());
+            }
+            for copy in report.deleted_copies() {
+                eprintln!("Deleted copy of its script: {}", copy.display());
+            }
+            for file in report.deleted_files() {
+                eprintln!("Deleted file with its own content: {}", file.display());
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Apply failed due to: {e}");
+            if let error::SaveDataError { step, .. } = &e {
+                eprintln!("{step}");
+            }
+            1
+        }
+    }
+}
+
+fn run_dry_run(config: &Config, args: &Args) -> i32 {
+    let planned = args.apply.as_ref().map_or_else(
+        || plan(config, args.command.as_ref()),
+        |mapping_path| apply::plan(config, mapping_path),
+    );
+    match planned {
+        Ok(changes) => {
+            for change in &changes {
+                println!("{change}");
+            }
+            println!("{} changes planned", changes.len());
+            0
+        }
+        Err(e) => {
+            eprintln!("Dry run failed due to: {e}");
+            1
+        }
+    }
+}
+
+fn run_command(config: &Config, command: &Command) -> i32 {
+    match run(config, command) {
+        Ok(json) => {
+            println!("{json}");
+            0
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            1
+        }
+    }
+}
+
+fn run_list(config: &Config, json: bool) -> i32 {
+    if json {
+        return run_command(config, &Command::List);
+    }
+    match listed_activities(config) {
+        Ok(activities) => {
+            print_activities(&activities);
+            0
+        }
+        Err(e) => {
+            eprintln!("Listing activities failed due to: {e}");
+            1
+        }
+    }
+}
+
+fn print_activities(activities: &[Activity]) {
+    for activity in activities {
+        println!("{} ({})", activity.name(), activity.id());
+        for event in ActivityEvent::iter() {
+            let scripts: Vec<String> = activity
+                .scripts(&event)
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect();
+            let scripts = if scripts.is_empty() {
+                "-".into()
+            } else {
+                scripts.join(", ")
+            };
+            println!("  {event}: {scripts}");
+        }
+    }
+}
+
+fn listed_activities(config: &Config) -> Result<Vec<Activity>, error::Application> {
+    let (mut activities, _) = Activity::from_env(config.root_path(), config.script_filename())?;
+    if *config.sort_by_name() {
+        activity::sort_activities(&mut activities, SortOrder::ByName);
+    }
+    Ok(activities)
+}
+
+pub fn run(config: &Config, command: &Command) -> Result<String, error::Application> {
+    let reports: Vec<ActivityReport> = match command {
+        Command::List => listed_activities(config)?
+            .iter()
+            .map(ActivityReport::from)
+            .collect(),
+        Command::Show { activity } => {
+            let (known, _) = Activity::from_env(config.root_path(), config.script_filename())?;
+            vec![find_activity(&known, activity)?.into()]
+        }
+        Command::Set {
+            activity,
+            event,
+            script,
+        } => vec![(&run_set(config, activity, event, Some(script))?.0).into()],
+        Command::Delete { activity, event } => {
+            vec![(&run_set(config, activity, event, None)?.0).into()]
+        }
+    };
+    to_json(&reports)
+}
+
+pub fn plan(
+    config: &Config,
+    command: Option<&Command>,
+) -> Result<Vec<PlannedChange>, error::Application> {
+    let activities = match command {
+        Some(Command::Set {
+            activity,
+            event,
+            script,
+        }) => vec![assigned(config, activity, event, Some(script))?],
+        Some(Command::Delete { activity, event }) => vec![assigned(config, activity, event, None)?],
+        Some(Command::Show { activity }) => {
+            let (known, _) = Activity::from_env(config.root_path(), config.script_filename())?;
+            vec![find_activity(&known, activity)?.clone()]
+        }
+        Some(Command::List) | None => {
+            Activity::from_env(config.root_path(), config.script_filename())?.0
+        }
+    };
+    Activity::plan_save(
+        &RealFileSystem,
+        config.root_path(),
+        config.script_filename(),
+        &activities,
+        *config.link_mode(),
+    )
+}
+
+fn to_json(reports: &[ActivityReport]) -> Result<String, error::Application> {
+    serde_json::to_string_pretty(reports).map_err(|e| error::InvalidValue {
+        category: "JSON output",
+        value: e.to_string(),
+    })
+}
+
+pub fn run_set(
+    config: &Config,
+    activity_key: &str,
+    event: &str,
+    script: Option<&Path>,
+) -> Result<(Activity, SaveReport), error::Application> {
+    if *config.read_only() {
+        return Err(error::UnsupportedValue {
+            category: "set in read-only mode",
+            value: activity_key.into(),
+        });
+    }
+    let activity = assigned(config, activity_key, event, script)?;
+    // Events left alone match the disk already, so saving only touches the one that changed
+    let report = Activity::save_activities(
+        &RealFileSystem,
+        config.root_path(),
+        config.script_filename(),
+        std::slice::from_ref(&activity),
+        *config.backup(),
+        *config.link_mode(),
+    )?;
+    Ok((activity, report))
+}
+
+fn assigned(
+    config: &Config,
+    activity_key: &str,
+    event: &str,
+    script: Option<&Path>,
+) -> Result<Activity, error::Application> {
+    let script = script
+        .map(|script| {
+            fs::metadata(script)
+                .and_then(|_| std::path::absolute(script))
+                .map_err(|e| error::Io {
+                    context: "reading script",
+                    source: e,
+                })
+        })
+        .transpose()?;
+    let (known, _) = Activity::from_env(config.root_path(), config.script_filename())?;
+    assign(&known, activity_key, event, script)
+}
+
+pub fn assign(
+    known: &[Activity],
+    activity_key: &str,
+    event: &str,
+    script: Option<PathBuf>,
+) -> Result<Activity, error::Application> {
+    let event: ActivityEvent = event.parse().map_err(|_| error::UnsupportedValue {
+        category: "activity event",
+        value: event.into(),
+    })?;
+    let mut activity = find_activity(known, activity_key)?.clone();
+    match script {
+        Some(script) => activity.set_script(event, script),
+        None => activity.delete_script(event),
+    }
+    Ok(activity)
+}
+
+pub fn find_activity<'a>(
+    known: &'a [Activity],
+    key: &str,
+) -> Result<&'a Activity, error::Application> {
+    if let Some(activity) = known.iter().find(|a| a.id() == key) {
+        return Ok(activity);
+    }
+    let mut named = known
+        .iter()
+        .filter(|a| a.name().to_lowercase() == key.to_lowercase());
+    match (named.next(), named.next()) {
+        (Some(activity), None) => Ok(activity),
+        (Some(_), Some(_)) => Err(error::InvalidValue {
+            category: "ambiguous activity name, use the id instead",
+            value: key.into(),
+        }),
+        (None, _) => Err(error::ActivityNotFound { key: key.into() }),
+    }
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+
+    use super::*;
+    use crate::{activity::ScriptMap, error::ErrorKind, fixture::known_activities};
+
+    #[test]
+    fn subcommands_parse() {
+        let args =
+            Args::try_parse_from(["kas-selector", "set", "Work", "started", "/s/on.sh"]).unwrap();
+        assert_that!(args.command).is_equal_to(Some(Command::Set {
+            activity: "Work".into(),
+            event: "started".into(),
+            script: "/s/on.sh".into(),
+        }));
+        let args = Args::try_parse_from(["kas-selector", "unset", "Work", "started"]).unwrap();
+        assert_that!(args.command).is_equal_to(Some(Command::Delete {
+            activity: "Work".into(),
+            event: "started".into(),
+        }));
+        let args = Args::try_parse_from(["kas-selector", "delete", "Work", "started", "--dry-run"])
+            .unwrap();
+        assert!(args.dry_run);
+        let args = Args::try_parse_from(["kas-selector", "--list", "--json"]).unwrap();
+        assert!(args.list && args.json);
+        let args =
+            Args::try_parse_from(["kas-selector", "--no-gui", "list", "--read-only"]).unwrap();
+        assert!(args.no_gui && args.read_only);
+        assert_that!(args.command).is_equal_to(Some(Command::List));
+
+        assert!(Args::try_parse_from(["kas-selector", "--json"]).is_err());
+        assert!(Args::try_parse_from(["kas-selector", "set", "Work"]).is_err());
+    }
+    #[test]
+    fn headless_without_display_or_when_asked() {
+        let args = Args::try_parse_from(["kas-selector"]).unwrap();
+        assert_that!(args.headless_command(true)).is_none();
+        assert!(args.opens_window(true));
+        assert!(!args.opens_window(false));
+        for flag in ["--list", "--dry-run"] {
+            assert!(
+                !Args::try_parse_from(["kas-selector", flag])
+                    .unwrap()
+                    .opens_window(true)
+            );
+        }
+        assert_that!(args.headless_command(false)).is_equal_to(Some(Command::List));
+
+        let args = Args::try_parse_from(["kas-selector", "--no-gui"]).unwrap();
+        assert_that!(args.headless_command(true)).is_equal_to(Some(Command::List));
+        assert!(!args.opens_window(true));
+
+        let args = Args::try_parse_from(["kas-selector", "show", "Work"]).unwrap();
+        assert_that!(args.headless_command(true)).is_equal_to(Some(Command::Show {
+            activity: "Work".into(),
+        }));
+    }
+    #[test]
+    fn display_is_found_through_either_variable() {
+        temp_env::with_vars(
+            [("DISPLAY", None), ("WAYLAND_DISPLAY", Some("wayland-0"))],
+            || {
+                assert!(display_available());
+            },
+        );
+        temp_env::with_vars(
+            [("DISPLAY", Some("")), ("WAYLAND_DISPLAY", None::<&str>)],
+            || {
+                assert!(!display_available());
+            },
+        );
+    }
+    #[test]
+    fn reports_list_assigned_scripts_in_event_order() {
+        let mut known = known_activities();
+        known[0].set_script(ActivityEvent::Stopped, "/s/off.sh".into());
+        known[0].set_script(ActivityEvent::Activated, "/s/on.sh".into());
+
+        let json = to_json(&[ActivityReport::from(&known[0])]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_that!(value[0]["id"].as_str()).is_equal_to(Some("abc-12d-a"));
+        assert_that!(value[0]["state"].as_str()).is_equal_to(Some("running"));
+        assert_that!(value[0]["scripts"]["activated"].as_str()).is_equal_to(Some("/s/on.sh"));
+        assert_that!(value[0]["scripts"]["started"].is_null()).is_true();
+        let keys: Vec<_> = value[0]["scripts"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        assert_that!(keys).contains_exactly(["activated".to_string(), "stopped".to_string()]);
+    }
+    #[test]
+    fn assign_sets_and_unsets_one_event() {
+        let mut known = known_activities();
+        known[0].set_script(ActivityEvent::Stopped, "/scripts/work-stop.sh".into());
+
+        let activity = assign(&known, "work", "started", Some("/scripts/on.sh".into())).unwrap();
+        assert_that!(activity.id().as_str()).is_equal_to("abc-12d-a");
+        assert_that!(activity.get_script(&ActivityEvent::Started).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/on.sh")));
+        assert_that!(activity.get_script(&ActivityEvent::Stopped).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/work-stop.sh")));
+
+        let activity = assign(&known, "abc-12d-a", "stopped", None).unwrap();
+        assert_that!(activity.get_script(&ActivityEvent::Stopped)).is_none();
+    }
+    #[test]
+    fn assign_rejects_unknown_event() {
+        let result = assign(&known_activities(), "Work", "exploded", None);
+        assert_that!(result.unwrap_err().kind()).is_equal_to(ErrorKind::InvalidInput);
+    }
+    #[test]
+    fn find_activity_reports_ambiguous_names() {
+        let known = Activity::from_activity_data(
+            r#"
+            [RUNNING] abc-12d-a Work (icon-a)
+            [STOPPED] abc-12d-b work (icon-b)
+            "#,
+            ScriptMap::new(),
+        )
+        .unwrap();
+
+        assert_that!(find_activity(&known, "WORK")).is_err();
+        assert_that!(find_activity(&known, "abc-12d-b").unwrap().name().as_str())
+            .is_equal_to("work");
+        assert_that!(find_activity(&known, "Play")).is_err();
+    }
+}
@@ -1,12 +1,346 @@
-use std::path::PathBuf;
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use derive_getters::Getters;
 use derive_new::new;
+use serde::Deserialize;
 
-use crate::shell_script_filename::ShellScriptFilename;
+use crate::{
+    activity::LinkMode,
+    app_dirs::AppDir,
+    error,
+    shell_script_filename::{AllowedExtensions, ShellScriptFilename},
+};
+
+const DEFAULT_ROOT_PATH: &str = ".local/share/kactivitymanagerd/activities";
+const DEFAULT_SCRIPT_FILENAME: &str = "activity_script.sh";
+const CONFIG_FILENAME: &str = "config.toml";
+const DEFAULT_RUN_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    root_path: Option<PathBuf>,
+    script_filename: Option<String>,
+}
+
+impl FileConfig {
+    fn read(path: &Path) -> Result<Self, error::Application> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(error::Io {
+                    context: "reading config file",
+                    source: e,
+                });
+            }
+        };
+        toml::from_str(&text).map_err(|e| error::InvalidValue {
+            category: "config file",
+            value: e.to_string(),
+        })
+    }
+}
 
 #[derive(Debug, Getters, new, Clone)]
 pub struct Config {
     root_path: PathBuf,
     script_filename: ShellScriptFilename,
+    #[new(default)]
+    backup: bool,
+    #[new(value = "true")]
+    watch: bool,
+    #[new(default)]
+    link_mode: LinkMode,
+    #[new(value = "true")]
+    sort_by_name: bool,
+    #[new(default)]
+    read_only: bool,
+    #[new(default)]
+    allow_any_script: bool,
+    #[new(default)]
+    script_extensions: AllowedExtensions,
+    #[new(value = "DEFAULT_RUN_TIMEOUT")]
+    #[getter(copy)]
+    run_timeout: Duration,
+}
+
+impl Config {
+    pub fn try_new(
+        root_path: PathBuf,
+        script_filename: ShellScriptFilename,
+    ) -> Result<Self, error::Application> {
+        if !root_path.is_absolute() {
+            return Err(error::InvalidValue {
+                category: "root path",
+                value: root_path.to_string_lossy().into(),
+            });
+        }
+        Ok(Self::new(root_path, script_filename))
+    }
+    pub const fn with_backup(mut self, backup: bool) -> Self {
+        self.backup = backup;
+        self
+    }
+    pub const fn with_watch(mut self, watch: bool) -> Self {
+        self.watch = watch;
+        self
+    }
+    pub const fn with_link_mode(mut self, link_mode: LinkMode) -> Self {
+        self.link_mode = link_mode;
+        self
+    }
+    pub const fn with_sort_by_name(mut self, sort_by_name: bool) -> Self {
+        self.sort_by_name = sort_by_name;
+        self
+    }
+    pub const fn with_allow_any_script(mut self, allow_any_script: bool) -> Self {
+        self.allow_any_script = allow_any_script;
+        self
+    }
+    pub fn with_script_extensions(mut self, script_extensions: AllowedExtensions) -> Self {
+        self.script_extensions = script_extensions;
+        self
+    }
+    pub const fn with_run_timeout(mut self, run_timeout: Duration) -> Self {
+        self.run_timeout = run_timeout;
+        self
+    }
+    pub const fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+    /// A `KAS_*` variable that is set wins over the config file, which wins over the defaults.
+    pub fn load() -> Result<Self, error::Application> {
+        let file = match config_path() {
+            Some(path) => FileConfig::read(&path)?,
+            None => FileConfig::default(),
+        };
+        Self::from_env(file)
+    }
+    fn from_env(file: FileConfig) -> Result<Self, error::Application> {
+        let root_path = env::var_os("KAS_ROOT")
+            .map(PathBuf::from)
+            .or(file.root_path)
+            .unwrap_or_else(default_root_path);
+        let script_extensions = match env::var("KAS_SCRIPT_EXTENSIONS") {
+            Ok(extensions) => extensions.parse()?,
+            Err(_) => AllowedExtensions::default(),
+        };
+        let script_filename = ShellScriptFilename::with_extensions(
+            &env::var("KAS_SCRIPT_NAME")
+                .ok()
+                .or(file.script_filename)
+                .unwrap_or_else(|| DEFAULT_SCRIPT_FILENAME.into()),
+            &script_extensions,
+            is_on("KAS_ALLOW_HIDDEN"),
+        )?;
+        let link_mode = match env::var("KAS_LINK_MODE") {
+            Ok(mode) => mode.parse().map_err(|_| error::InvalidValue {
+                category: "link mode",
+                value: mode,
+            })?,
+            Err(_) if is_on("KAS_RELATIVE_LINKS") => LinkMode::RelativeSymlink,
+            Err(_) => LinkMode::Symlink,
+        };
+        let run_timeout = match env::var("KAS_RUN_TIMEOUT") {
+            Ok(seconds) => seconds
+                .parse()
+                .ok()
+                .filter(|&seconds| seconds > 0)
+                .map(Duration::from_secs)
+                .ok_or(error::InvalidValue {
+                    category: "test run timeout in seconds",
+                    value: seconds,
+                })?,
+            Err(_) => DEFAULT_RUN_TIMEOUT,
+        };
+        Ok(Self::try_new(root_path, script_filename)?
+            .with_backup(is_on("KAS_BACKUP"))
+            .with_watch(!is_off("KAS_WATCH"))
+            .with_link_mode(link_mode)
+            .with_sort_by_name(!is_off("KAS_SORT"))
+            .with_read_only(is_on("KAS_READONLY"))
+            .with_allow_any_script(is_on("KAS_ALLOW_ANY_SCRIPT"))
+            .with_script_extensions(script_extensions)
+            .with_run_timeout(run_timeout))
+    }
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    AppDir::Config.file(CONFIG_FILENAME)
+}
+
+pub fn default_root_path() -> PathBuf {
+    PathBuf::from(env::var("HOME").unwrap_or_default()).join(DEFAULT_ROOT_PATH)
+}
+
+fn is_on(name: &str) -> bool {
+    env::var(name).is_ok_and(|v| v == "1" || v == "true")
+}
+
+fn is_off(name: &str) -> bool {
+    env::var(name).is_ok_and(|v| v == "0" || v == "false")
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn try_new_accepts_valid_config() {
+        let config = Config::try_new(
+            "/home/me/activities".into(),
+            "kas-script.sh".parse().unwrap(),
+        )
+        .unwrap();
+        assert_that!(config.script_filename().as_str()).is_equal_to("kas-script.sh");
+        assert_that!(*config.backup()).is_false();
+        assert_that!(*config.watch()).is_true();
+        assert_that!(*config.link_mode()).is_equal_to(LinkMode::Symlink);
+        assert_that!(*config.sort_by_name()).is_true();
+        assert_that!(*config.read_only()).is_false();
+    }
+    #[test]
+    fn try_new_rejects_relative_root() {
+        let result = Config::try_new("activities".into(), "kas-script.sh".parse().unwrap());
+        assert_that!(result).is_err();
+    }
+    const KAS_VARS: [(&str, Option<&str>); 12] = [
+        ("KAS_ALLOW_HIDDEN", None),
+        ("KAS_RUN_TIMEOUT", None),
+        ("KAS_SCRIPT_EXTENSIONS", None),
+        ("KAS_ROOT", None),
+        ("KAS_SCRIPT_NAME", None),
+        ("KAS_BACKUP", None),
+        ("KAS_WATCH", None),
+        ("KAS_RELATIVE_LINKS", None),
+        ("KAS_LINK_MODE", None),
+        ("KAS_SORT", None),
+        ("KAS_READONLY", None),
+        ("KAS_ALLOW_ANY_SCRIPT", None),
+    ];
+
+    fn with_config_file(text: &str, vars: &[(&str, Option<&str>)], test: impl FnOnce()) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kas-selector").join(CONFIG_FILENAME);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, text).unwrap();
+        let mut all: Vec<_> = KAS_VARS
+            .iter()
+            .filter(|(name, _)| vars.iter().all(|(set, _)| set != name))
+            .copied()
+            .collect();
+        all.extend_from_slice(vars);
+        all.push(("XDG_CONFIG_HOME", dir.path().to_str()));
+        temp_env::with_vars(all, test);
+    }
+
+    #[test]
+    fn load_uses_defaults_without_file_or_variables() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vars = KAS_VARS.to_vec();
+        vars.extend([
+            ("XDG_CONFIG_HOME", dir.path().to_str()),
+            ("HOME", Some("/home/me")),
+        ]);
+        temp_env::with_vars(vars, || {
+            let config = Config::load().unwrap();
+            assert_that!(config.root_path())
+                .is_equal_to(&PathBuf::from("/home/me").join(DEFAULT_ROOT_PATH));
+            assert_that!(config.script_filename().as_str()).is_equal_to(DEFAULT_SCRIPT_FILENAME);
+            assert_that!(config.sort_by_name()).is_equal_to(&true);
+            assert_that!(config.link_mode()).is_equal_to(&LinkMode::Symlink);
+        });
+    }
+    #[test]
+    fn load_reads_the_config_file() {
+        let file = "root_path = \"/data/activities\"\nscript_filename = \"hook.sh\"\n";
+        with_config_file(file, &[], || {
+            let config = Config::load().unwrap();
+            assert_that!(config.root_path()).is_equal_to(&PathBuf::from("/data/activities"));
+            assert_that!(config.script_filename().as_str()).is_equal_to("hook.sh");
+        });
+    }
+    #[test]
+    fn load_prefers_variables_over_the_config_file() {
+        let file = "root_path = \"/data/activities\"\nscript_filename = \"hook.sh\"\n";
+        let vars = [
+            ("KAS_ROOT", Some("/env/activities")),
+            ("KAS_SCRIPT_NAME", Some("env.sh")),
+        ];
+        with_config_file(file, &vars, || {
+            let config = Config::load().unwrap();
+            assert_that!(config.root_path()).is_equal_to(&PathBuf::from("/env/activities"));
+            assert_that!(config.script_filename().as_str()).is_equal_to("env.sh");
+        });
+    }
+    #[test]
+    fn load_reads_script_extensions() {
+        let vars = [
+            ("KAS_SCRIPT_EXTENSIONS", Some("sh,bash")),
+            ("KAS_SCRIPT_NAME", Some("hook.bash")),
+        ];
+        with_config_file("", &vars, || {
+            let config = Config::load().unwrap();
+            assert_that!(config.script_filename().as_str()).is_equal_to("hook.bash");
+            assert_that!(config.script_extensions().to_vec())
+                .contains_exactly(["sh", "bash"].map(String::from));
+        });
+        with_config_file("", &[("KAS_SCRIPT_NAME", Some("hook.bash"))], || {
+            assert!(Config::load().is_err());
+        });
+        with_config_file("", &[("KAS_SCRIPT_EXTENSIONS", Some(","))], || {
+            assert!(Config::load().is_err());
+        });
+    }
+    #[test]
+    fn load_reads_the_run_timeout() {
+        with_config_file("", &[], || {
+            assert_that!(Config::load().unwrap().run_timeout()).is_equal_to(DEFAULT_RUN_TIMEOUT);
+        });
+        with_config_file("", &[("KAS_RUN_TIMEOUT", Some("5"))], || {
+            assert_that!(Config::load().unwrap().run_timeout()).is_equal_to(Duration::from_secs(5));
+        });
+        for invalid in ["0", "soon"] {
+            with_config_file("", &[("KAS_RUN_TIMEOUT", Some(invalid))], || {
+                assert!(Config::load().is_err());
+            });
+        }
+    }
+    #[test]
+    fn load_rejects_invalid_config_files() {
+        for file in [
+            "root_path = ",
+            "script_filename = \"hook.txt\"",
+            "colour = \"blue\"",
+        ] {
+            with_config_file(file, &[], || {
+                let error = Config::load().unwrap_err();
+                assert!(matches!(error, error::InvalidValue { .. }), "{error:?}");
+            });
+        }
+    }
+    #[test]
+    fn load_accepts_a_hidden_script_filename_only_when_allowed() {
+        with_config_file("", &[("KAS_SCRIPT_NAME", Some(".hook.sh"))], || {
+            assert!(Config::load().is_err());
+        });
+        let vars = [
+            ("KAS_SCRIPT_NAME", Some(".hook.sh")),
+            ("KAS_ALLOW_HIDDEN", Some("1")),
+        ];
+        with_config_file("", &vars, || {
+            let config = Config::load().unwrap();
+            assert_that!(config.script_filename().as_str()).is_equal_to(".hook.sh");
+        });
+    }
 }
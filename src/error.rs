@@ -1,17 +1,70 @@
+use std::io;
+
 use thiserror::Error;
 
-#[derive(Debug, Clone, Error)]
+/// How far a failed save got, which tells what it left on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveStep {
+    /// Preparing the new links failed before anything on disk was touched.
+    Staging,
+    /// Moving a link into place failed and everything already moved was put back.
+    RolledBack,
+    /// Moving a link into place failed and some changes could not be put back.
+    Incomplete,
+}
+
+impl std::fmt::Display for SaveStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Staging => "Nothing on disk was changed.",
+            Self::RolledBack => "The changes already made were undone.",
+            Self::Incomplete => "Some changes could not be undone.",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    NotFound,
+    PermissionDenied,
+    InvalidInput,
+    ReadOnly,
+    External,
+    Other,
+}
+
+impl From<io::ErrorKind> for ErrorKind {
+    fn from(kind: io::ErrorKind) -> Self {
+        match kind {
+            io::ErrorKind::NotFound => Self::NotFound,
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+            io::ErrorKind::InvalidInput | io::ErrorKind::InvalidData => Self::InvalidInput,
+            io::ErrorKind::ReadOnlyFilesystem => Self::ReadOnly,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
 pub enum Application {
     #[error("Command `{command}` failed wiht: `{error_text}`")]
     CommandFailed {
         command: &'static str,
         error_text: String,
+        #[source]
+        source: Option<io::Error>,
     },
     #[error("Incorrect `{category}` value `{value}` found.")]
     InvalidValue {
         category: &'static str,
         value: String,
     },
+    #[error("Incorrect `{context}` value `{source}` found.")]
+    Io {
+        context: &'static str,
+        #[source]
+        source: io::Error,
+    },
     #[error("The value `{value}` is not a currently supported {category}.")]
     UnsupportedValue {
         category: &'static str,
@@ -22,7 +75,164 @@ pub enum Application {
         activity: String,
         event: &'static str,
         script_path: String,
+        step: SaveStep,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Script `{path}` is not executable.")]
+    NotExecutable { path: String },
+    #[error("Script `{path}` has a syntax error: {error_text}")]
+    ScriptSyntaxError { path: String, error_text: String },
+    #[error("No activity matches `{key}`.")]
+    ActivityNotFound { key: String },
+    #[error(
+        "Script link `{path}` for activity `{activity_id}` and event `{event}` points to a missing file."
+    )]
+    BrokenSymlink {
+        activity_id: String,
+        event: &'static str,
+        path: String,
     },
 }
 
+impl Application {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::CommandFailed {
+                source: Some(source),
+                ..
+            } => source.kind().into(),
+            Self::CommandFailed { source: None, .. } => ErrorKind::External,
+            Self::InvalidValue { .. }
+            | Self::UnsupportedValue { .. }
+            | Self::ScriptSyntaxError { .. } => ErrorKind::InvalidInput,
+            Self::Io { source, .. } | Self::SaveDataError { source, .. } => source.kind().into(),
+            Self::BrokenSymlink { .. } | Self::ActivityNotFound { .. } => ErrorKind::NotFound,
+            Self::NotExecutable { .. } => ErrorKind::PermissionDenied,
+        }
+    }
+}
+
 pub use Application::*;
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+
+    use super::*;
+
+    fn io_error(kind: io::ErrorKind) -> io::Error {
+        io::Error::new(kind, "simulated")
+    }
+
+    #[test]
+    fn kind_classifies_io_errors() {
+        for (io_kind, expected) in [
+            (io::ErrorKind::NotFound, ErrorKind::NotFound),
+            (io::ErrorKind::PermissionDenied, ErrorKind::PermissionDenied),
+            (io::ErrorKind::InvalidInput, ErrorKind::InvalidInput),
+            (io::ErrorKind::InvalidData, ErrorKind::InvalidInput),
+            (io::ErrorKind::ReadOnlyFilesystem, ErrorKind::ReadOnly),
+            (io::ErrorKind::AlreadyExists, ErrorKind::Other),
+        ] {
+            let error = Io {
+                context: "reading root script directory",
+                source: io_error(io_kind),
+            };
+            assert_that!(error.kind())
+                .described_as(format!("{io_kind:?}"))
+                .is_equal_to(expected);
+            let error = SaveDataError {
+                activity: "Work".into(),
+                event: "activated",
+                script_path: "/tmp/a.sh".into(),
+                step: SaveStep::Staging,
+                source: io_error(io_kind),
+            };
+            assert_that!(error.kind())
+                .described_as(format!("{io_kind:?}"))
+                .is_equal_to(expected);
+        }
+    }
+    #[test]
+    fn kind_classifies_commands_and_values() {
+        let missing = CommandFailed {
+            command: "kactivities-cli",
+            error_text: "not found".into(),
+            source: Some(io_error(io::ErrorKind::NotFound)),
+        };
+        assert_that!(missing.kind()).is_equal_to(ErrorKind::NotFound);
+        let failed = CommandFailed {
+            command: "kactivities-cli",
+            error_text: "exit status 1".into(),
+            source: None,
+        };
+        assert_that!(failed.kind()).is_equal_to(ErrorKind::External);
+        let invalid = InvalidValue {
+            category: "ShellScriptFilename",
+            value: "foo".into(),
+        };
+        assert_that!(invalid.kind()).is_equal_to(ErrorKind::InvalidInput);
+        let unsupported = UnsupportedValue {
+            category: "langauge",
+            value: "xx".into(),
+        };
+        assert_that!(unsupported.kind()).is_equal_to(ErrorKind::InvalidInput);
+        let broken = BrokenSymlink {
+            activity_id: "abc-12d-a".into(),
+            event: "started",
+            path: "/scripts/gone.sh".into(),
+        };
+        assert_that!(broken.kind()).is_equal_to(ErrorKind::NotFound);
+        let unknown = ActivityNotFound { key: "Work".into() };
+        assert_that!(unknown.kind()).is_equal_to(ErrorKind::NotFound);
+    }
+    #[test]
+    fn display_keeps_existing_messages() {
+        let source = io_error(io::ErrorKind::NotFound);
+        let text = source.to_string();
+        assert_that!(
+            Io {
+                context: "reading root script directory",
+                source,
+            }
+            .to_string()
+        )
+        .is_equal_to(format!(
+            "Incorrect `reading root script directory` value `{text}` found."
+        ));
+        assert_that!(
+            SaveDataError {
+                activity: "Work".into(),
+                event: "activated",
+                script_path: "/tmp/a.sh".into(),
+                step: SaveStep::RolledBack,
+                source: io_error(io::ErrorKind::PermissionDenied),
+            }
+            .to_string()
+        )
+        .is_equal_to(
+            "Failed to save script `/tmp/a.sh` for activity `Work` and event `activated`."
+                .to_string(),
+        );
+        assert_that!(
+            CommandFailed {
+                command: "kactivities-cli",
+                error_text: "boom".into(),
+                source: None,
+            }
+            .to_string()
+        )
+        .is_equal_to("Command `kactivities-cli` failed wiht: `boom`".to_string());
+        assert_that!(
+            InvalidValue {
+                category: "ShellScriptFilename",
+                value: "foo".into(),
+            }
+            .to_string()
+        )
+        .is_equal_to("Incorrect `ShellScriptFilename` value `foo` found.".to_string());
+    }
+}
@@ -0,0 +1,42 @@
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+impl Format {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        if extension.eq_ignore_ascii_case("json") {
+            Some(Self::Json)
+        } else if extension.eq_ignore_ascii_case("toml") {
+            Some(Self::Toml)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn format_follows_the_extension_in_any_case() {
+        for (path, format) in [
+            ("scripts.json", Some(Format::Json)),
+            ("SCRIPTS.JSON", Some(Format::Json)),
+            ("mapping.toml", Some(Format::Toml)),
+            ("mapping.Toml", Some(Format::Toml)),
+            ("mapping.conf", None),
+            ("mapping", None),
+            ("json", None),
+        ] {
+            assert_that!(Format::from_path(Path::new(path))).is_equal_to(format);
+        }
+    }
+}
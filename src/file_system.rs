@@ -0,0 +1,245 @@
+use std::{
+    fs, io,
+    os::unix,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    Dir,
+    File,
+    Symlink,
+}
+
+pub trait FileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+    fn is_dir(&self, path: &Path) -> bool;
+    fn exists(&self, path: &Path) -> bool;
+    fn symlink_metadata(&self, path: &Path) -> io::Result<EntryKind>;
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()>;
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect()
+    }
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+    fn symlink_metadata(&self, path: &Path) -> io::Result<EntryKind> {
+        let file_type = fs::symlink_metadata(path)?.file_type();
+        Ok(if file_type.is_symlink() {
+            EntryKind::Symlink
+        } else if file_type.is_dir() {
+            EntryKind::Dir
+        } else {
+            EntryKind::File
+        })
+    }
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        fs::read_link(path)
+    }
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+    fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+        unix::fs::symlink(target, link)
+    }
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::copy(from, to).map(|_| ())
+    }
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir_all(path)
+    }
+}
+
+#[cfg(test)]
+pub mod fake {
+    use std::{
+        cell::RefCell,
+        collections::{BTreeMap, BTreeSet},
+    };
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum Entry {
+        Dir,
+        File,
+        Symlink(PathBuf),
+    }
+
+    #[derive(Debug, Default)]
+    pub struct FakeFileSystem {
+        entries: RefCell<BTreeMap<PathBuf, Entry>>,
+        contents: RefCell<BTreeMap<PathBuf, Vec<u8>>>,
+        failing: BTreeSet<PathBuf>,
+    }
+
+    impl FakeFileSystem {
+        pub fn with_entry(self, path: impl Into<PathBuf>, entry: Entry) -> Self {
+            let path = path.into();
+            if let Some(parent) = path.parent() {
+                self.create_dir_all(parent).ok();
+            }
+            self.entries.borrow_mut().insert(path, entry);
+            self
+        }
+        pub fn with_file(self, path: impl Into<PathBuf>, content: &str) -> Self {
+            let path = path.into();
+            self.contents
+                .borrow_mut()
+                .insert(path.clone(), content.into());
+            self.with_entry(path, Entry::File)
+        }
+        pub fn failing_at(mut self, path: impl Into<PathBuf>) -> Self {
+            self.failing.insert(path.into());
+            self
+        }
+        pub fn entry(&self, path: &Path) -> Option<Entry> {
+            self.entries.borrow().get(path).cloned()
+        }
+        fn check_writable(&self, path: &Path) -> io::Result<()> {
+            if self.failing.contains(path) {
+                Err(io::ErrorKind::PermissionDenied.into())
+            } else {
+                Ok(())
+            }
+        }
+        fn take(&self, path: &Path) -> io::Result<Entry> {
+            self.check_writable(path)?;
+            self.entries
+                .borrow_mut()
+                .remove(path)
+                .ok_or_else(|| io::ErrorKind::NotFound.into())
+        }
+    }
+
+    impl FileSystem for FakeFileSystem {
+        fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+            if self.entry(path) != Some(Entry::Dir) {
+                return Err(io::ErrorKind::NotFound.into());
+            }
+            Ok(self
+                .entries
+                .borrow()
+                .keys()
+                .filter(|child| child.parent() == Some(path))
+                .cloned()
+                .collect())
+        }
+        fn is_dir(&self, path: &Path) -> bool {
+            self.entry(path) == Some(Entry::Dir)
+        }
+        fn exists(&self, path: &Path) -> bool {
+            match self.entry(path) {
+                Some(Entry::Symlink(target)) => {
+                    self.exists(&path.parent().unwrap_or(path).join(target))
+                }
+                entry => entry.is_some(),
+            }
+        }
+        fn symlink_metadata(&self, path: &Path) -> io::Result<EntryKind> {
+            match self.entry(path) {
+                Some(Entry::Dir) => Ok(EntryKind::Dir),
+                Some(Entry::File) => Ok(EntryKind::File),
+                Some(Entry::Symlink(_)) => Ok(EntryKind::Symlink),
+                None => Err(io::ErrorKind::NotFound.into()),
+            }
+        }
+        fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+            match self.entry(path) {
+                Some(Entry::Symlink(target)) => Ok(target),
+                _ => Err(io::ErrorKind::InvalidInput.into()),
+            }
+        }
+        fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+            match self.entry(path) {
+                Some(Entry::File) => Ok(self
+                    .contents
+                    .borrow()
+                    .get(path)
+                    .cloned()
+                    .unwrap_or_default()),
+                Some(Entry::Symlink(target)) => {
+                    self.read(&path.parent().unwrap_or(path).join(target))
+                }
+                Some(Entry::Dir) => Err(io::ErrorKind::IsADirectory.into()),
+                None => Err(io::ErrorKind::NotFound.into()),
+            }
+        }
+        fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+            for dir in path.ancestors() {
+                self.check_writable(dir)?;
+                self.entries
+                    .borrow_mut()
+                    .entry(dir.to_path_buf())
+                    .or_insert(Entry::Dir);
+            }
+            Ok(())
+        }
+        fn symlink(&self, target: &Path, link: &Path) -> io::Result<()> {
+            self.check_writable(link)?;
+            let mut entries = self.entries.borrow_mut();
+            if entries.contains_key(link) {
+                return Err(io::ErrorKind::AlreadyExists.into());
+            }
+            entries.insert(link.to_path_buf(), Entry::Symlink(target.to_path_buf()));
+            Ok(())
+        }
+        fn copy(&self, from: &Path, to: &Path) -> io::Result<()> {
+            self.check_writable(to)?;
+            let content = self.read(from)?;
+            self.contents.borrow_mut().insert(to.to_path_buf(), content);
+            self.entries
+                .borrow_mut()
+                .insert(to.to_path_buf(), Entry::File);
+            Ok(())
+        }
+        fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+            self.check_writable(to)?;
+            let entry = self.take(from)?;
+            let mut contents = self.contents.borrow_mut();
+            match contents.remove(from) {
+                Some(content) => contents.insert(to.to_path_buf(), content),
+                None => contents.remove(to),
+            };
+            self.entries.borrow_mut().insert(to.to_path_buf(), entry);
+            Ok(())
+        }
+        fn remove_file(&self, path: &Path) -> io::Result<()> {
+            self.take(path).map(|_| ())
+        }
+        fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+            self.take(path)?;
+            self.entries
+                .borrow_mut()
+                .retain(|entry, _| !entry.starts_with(path));
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,16 @@
+// Allowed in tests
+#![allow(clippy::unwrap_used)]
+
+use crate::activity::{Activity, ScriptMap};
+
+/// Shared by the library and the binary's tests, which can't see each other's test code.
+pub fn known_activities() -> Vec<Activity> {
+    Activity::from_activity_data(
+        r#"
+        [RUNNING] abc-12d-a Work (icon-a)
+        [STOPPED] abc-12d-b Personal (icon-b)
+        "#,
+        ScriptMap::new(),
+    )
+    .unwrap()
+}
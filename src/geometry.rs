@@ -0,0 +1,74 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_dirs::{self, AppDir},
+    error,
+};
+
+const GEOMETRY_FILENAME: &str = "window.toml";
+
+/// Only the size is kept, since Wayland doesn't let windows place themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl WindowGeometry {
+    pub fn read(path: &Path) -> Option<Self> {
+        let source = fs::read_to_string(path).ok()?;
+        toml::from_str(&source)
+            .inspect_err(|e| eprintln!("Ignoring the saved window size due to: {e}"))
+            .ok()
+            .filter(|geometry: &Self| geometry.width > 0 && geometry.height > 0)
+    }
+    pub fn write(&self, path: &Path) -> Result<(), error::Application> {
+        let text = toml::to_string(self).map_err(|e| error::InvalidValue {
+            category: "window size",
+            value: e.to_string(),
+        })?;
+        app_dirs::write_file(path, &text, "writing window size file")
+    }
+}
+
+pub fn geometry_path() -> Option<PathBuf> {
+    AppDir::Cache.file(GEOMETRY_FILENAME)
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn geometry_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kas-selector").join(GEOMETRY_FILENAME);
+        let geometry = WindowGeometry {
+            width: 800,
+            height: 600,
+        };
+
+        geometry.write(&path).unwrap();
+        assert_that!(WindowGeometry::read(&path)).is_equal_to(Some(geometry));
+    }
+    #[test]
+    fn missing_or_malformed_geometry_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("window.toml");
+        assert_that!(WindowGeometry::read(&path)).is_none();
+
+        for text in ["width = ", "width = 800", "width = 0\nheight = 600"] {
+            fs::write(&path, text).unwrap();
+            assert_that!(WindowGeometry::read(&path)).is_none();
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! GTK-free core of kas-selector: reading, editing and saving the scripts linked to KDE
+//! activity events.
+#![deny(clippy::unwrap_used, clippy::expect_used)]
+#![warn(clippy::all, clippy::nursery)]
+
+pub mod activity;
+pub mod activity_source;
+pub mod app_dirs;
+pub mod apply;
+pub mod cli;
+pub mod config;
+pub mod error;
+pub mod file_format;
+pub mod file_system;
+#[cfg(test)]
+pub mod fixture;
+pub mod locale;
+pub mod shell_script_filename;
@@ -3,12 +3,30 @@ use fluent_langneg::{NegotiationStrategy, convert_vec_str_to_langids_lossy, nego
 use indexmap::IndexSet;
 use std::{env, fmt::Debug, fs, path::PathBuf, sync::Arc};
 use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
-use unic_langid::LanguageIdentifier;
+use unic_langid::{CharacterDirection, LanguageIdentifier};
 
 use crate::error;
 
 pub const DEFAULT_LOCALE: &str = "en-US";
 pub const AVAILABLE_LOCALES: [&str; 7] = ["ar", "de", "en-US", "es", "fr", "ru", "zh"];
+pub const LOCALE_NAMES: [&str; 7] = [
+    "العربية",
+    "Deutsch",
+    "English",
+    "Español",
+    "Français",
+    "Русский",
+    "中文",
+];
+const EMBEDDED_LOCALES: [(&str, &str); 7] = [
+    ("ar", include_str!("../locales/ar/main.ftl")),
+    ("de", include_str!("../locales/de/main.ftl")),
+    ("en-US", include_str!("../locales/en-US/main.ftl")),
+    ("es", include_str!("../locales/es/main.ftl")),
+    ("fr", include_str!("../locales/fr/main.ftl")),
+    ("ru", include_str!("../locales/ru/main.ftl")),
+    ("zh", include_str!("../locales/zh/main.ftl")),
+];
 
 fn locale_root_prefix(p: &str) -> Result<PathBuf, error::Application> {
     let mut p: PathBuf = p.parse().map_err(|_| error::InvalidValue {
@@ -19,8 +37,11 @@ fn locale_root_prefix(p: &str) -> Result<PathBuf, error::Application> {
     Ok(p)
 }
 
+/// Folders holding `<lang>/main.ftl`, highest priority first. `KAS_LOCALE_DIR` comes first
+/// so in-progress translations can be tried without installing them.
 fn locale_roots() -> Result<Vec<PathBuf>, error::Application> {
-    let mut path_set = IndexSet::from([PathBuf::from("locales")]);
+    let mut path_set: IndexSet<PathBuf> = custom_locale_dir().into_iter().collect();
+    path_set.insert(PathBuf::from("locales"));
     let mut raw_strs = vec!["/usr/local/share", "/usr/share"];
     let xdg = env::var("XDG_DATA_DIRS").unwrap_or_default();
     raw_strs.extend(xdg.split(':'));
@@ -32,6 +53,13 @@ fn locale_roots() -> Result<Vec<PathBuf>, error::Application> {
     Ok(path_set.into_iter().collect())
 }
 
+pub fn custom_locale_dir() -> Option<PathBuf> {
+    env::var("KAS_LOCALE_DIR")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
 fn negotiated_lang_from_str(lang: &str) -> Result<LanguageIdentifier, error::Application> {
     let lang_id: LanguageIdentifier = lang.parse().map_err(|_| error::InvalidValue {
         category: "Language invalid",
@@ -57,7 +85,7 @@ fn negotiated_lang_from_str(lang: &str) -> Result<LanguageIdentifier, error::App
     .clone())
 }
 
-#[derive(EnumString, EnumIter, Display, Debug)]
+#[derive(EnumString, EnumIter, Display, Debug, Clone, Copy, PartialEq, Eq)]
 #[strum(serialize_all = "kebab-case")]
 pub enum Key {
     Title,
@@ -65,16 +93,133 @@ pub enum Key {
     EventDeactivated,
     EventStarted,
     EventStopped,
+    EventSuspended,
+    EventResumed,
     Open,
     Cancel,
     Quit,
     Save,
-    Edit,
     Help,
     Delete,
     ErrorSaveFailed,
     SavingData,
     Activity,
+    Export,
+    ErrorExportFailed,
+    LoadingData,
+    ErrorLoadFailed,
+    Import,
+    ErrorImportFailed,
+    ImportSkipped,
+    ImportSummary,
+    ImportMissingScripts,
+    Menu,
+    MultiSelect,
+    ActivityScriptCount,
+    FilesChanged,
+    Reload,
+    RecoverTitle,
+    RecoverDetail,
+    Restore,
+    Discard,
+    UnexpectedRoot,
+    Continue,
+    ConfirmRemoveFiles,
+    ConfirmReplaceCopies,
+    TestRun,
+    TestRunFinished,
+    ErrorTestRunFailed,
+    Details,
+    DetailsTarget,
+    DetailsTargetExists,
+    DetailsSize,
+    DetailsBytes,
+    DetailsModified,
+    DetailsExecutable,
+    Yes,
+    No,
+    CreateScript,
+    ErrorCreateScriptFailed,
+    CopyDetails,
+    Close,
+    ConfirmOverwriteLinks,
+    Replace,
+    SortByName,
+    About,
+    AboutComments,
+    Website,
+    ActivityManagerStopped,
+    ActivityManagerStoppedDetail,
+    TitleSummary,
+    QuitButton,
+    SaveButton,
+    HelpButton,
+    StatusFolder,
+    StatusScriptName,
+    OpenFolder,
+    SavePermissionDenied,
+    SavePermissionDeniedDetail,
+    RetryAsAdmin,
+    BrokenLinks,
+    ConfirmDeleteScript,
+    ConfirmDeleteScriptDetail,
+    ScriptMissing,
+    SearchActivities,
+    Undo,
+    Redo,
+    UnsavedChanges,
+    UnsavedChangesDetail,
+    SaveAndQuit,
+    CopyScriptsFrom,
+    ErrorActivitiesUnreachable,
+    FilterShellScripts,
+    FilterAllFiles,
+    ErrorScriptNotAFile,
+    ErrorScriptNotShell,
+    NotExecutable,
+    NotExecutableDetail,
+    MakeExecutable,
+    ErrorMakeExecutableFailed,
+    ImportConflicts,
+    ImportConflictsDetail,
+    KeepCurrentScripts,
+    UseImportedScripts,
+    ErrorReadOnlyFilesystem,
+    SortByStatus,
+    CleanUpOrphans,
+    ConfirmCleanUpOrphans,
+    ConfirmCleanUpOrphansDetail,
+    ErrorCleanUpFailed,
+    ReloadActivities,
+    ConfirmDiscardReload,
+    ScriptSyntaxError,
+    ScriptSyntaxErrorDetail,
+    AssignAnyway,
+    CopyScriptsTo,
+    CopyScriptsToDetail,
+    OverwriteExistingScripts,
+    Copy,
+    NotExecutableScripts,
+    FixPermissions,
+    OpenInEditor,
+    TestRunTimedOut,
+    Language,
+    NoActivities,
+    ErrorStartupFailed,
+    CreateRoot,
+    CreateRootDetail,
+    CreateFolder,
+    AssignToAll,
+    ConfirmAssignToAll,
+    CopyPath,
+    AddScript,
+    RemoveScript,
+    EventScripts,
+    EventScriptCount,
+    ErrorOpenInEditorFailed,
+    ErrorSaveNothingChanged,
+    ErrorSaveUndone,
+    ErrorSaveIncomplete,
 }
 
 #[derive(Clone)]
@@ -83,8 +228,21 @@ pub struct FluentLocale {
 }
 
 impl FluentLocale {
+    /// A `main.ftl` on disk wins over the embedded copy, and a broken one is an error rather
+    /// than falling back, so a translator sees why their edits aren't showing.
     pub fn try_new(lang: &str) -> Result<Self, error::Application> {
-        let locale_roots = locale_roots()?;
+        Self::from_roots(lang, &locale_roots()?)
+    }
+    #[allow(clippy::expect_used)]
+    pub fn embedded(lang: &str) -> Self {
+        let lang_id = negotiated_lang_from_str(lang)
+            .or_else(|_| negotiated_lang_from_str(DEFAULT_LOCALE))
+            .expect("Default language id should be parseable.");
+        let (source, path) =
+            embedded_source(&lang_id).expect("Every available locale should be embedded.");
+        Self::from_source(lang_id, source, &path).expect("Embedded locales should be valid.")
+    }
+    fn from_roots(lang: &str, locale_roots: &[PathBuf]) -> Result<Self, error::Application> {
         let lang_id = negotiated_lang_from_str(lang)?;
         let (source, path) = locale_roots
             .iter()
@@ -99,6 +257,7 @@ impl FluentLocale {
                 )
             })
             .find_map(|(result, path)| result.ok().map(|source| (source, path)))
+            .or_else(|| embedded_source(&lang_id))
             .ok_or_else(|| error::UnsupportedValue {
                 category: "Fluent file",
                 value: locale_roots
@@ -107,9 +266,16 @@ impl FluentLocale {
                     .collect::<Vec<String>>()
                     .join(", "),
             })?;
+        Self::from_source(lang_id, source, &path)
+    }
+    fn from_source(
+        lang_id: LanguageIdentifier,
+        source: String,
+        path: &str,
+    ) -> Result<Self, error::Application> {
         let resource = FluentResource::try_new(source).map_err(|_| error::InvalidValue {
             category: "Fluent syntax error",
-            value: path.clone(),
+            value: path.to_string(),
         })?;
 
         let mut bundle = FluentBundle::new_concurrent(vec![lang_id]);
@@ -117,7 +283,7 @@ impl FluentLocale {
             .add_resource(resource)
             .map_err(|_| error::InvalidValue {
                 category: "Fluent bundle",
-                value: path.clone(),
+                value: path.to_string(),
             })?;
         for key in Key::iter() {
             if !bundle.has_message(key.to_string().as_str()) {
@@ -132,6 +298,19 @@ impl FluentLocale {
         })
     }
 
+    pub fn language(&self) -> String {
+        self.bundle
+            .locales
+            .first()
+            .map_or_else(|| DEFAULT_LOCALE.into(), ToString::to_string)
+    }
+    pub fn is_rtl(&self) -> bool {
+        self.bundle
+            .locales
+            .first()
+            .is_some_and(|lang| lang.character_direction() == CharacterDirection::RTL)
+    }
+
     pub fn text(&self, key: Key, args: Option<&FluentArgs>) -> String {
         #[allow(clippy::expect_used)]
         let pattern = self
@@ -145,6 +324,14 @@ impl FluentLocale {
     }
 }
 
+fn embedded_source(lang_id: &LanguageIdentifier) -> Option<(String, String)> {
+    let lang = lang_id.to_string();
+    EMBEDDED_LOCALES
+        .iter()
+        .find(|(name, _)| *name == lang)
+        .map(|(_, source)| ((*source).to_string(), format!("embedded {lang}")))
+}
+
 impl Debug for FluentLocale {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("FluentLocal")
@@ -156,7 +343,7 @@ impl Debug for FluentLocale {
 #[cfg(test)]
 mod test {
     use asserting::prelude::*;
-    use temp_env::with_var;
+    use temp_env::{with_var, with_vars};
 
     use super::*;
 
@@ -170,7 +357,41 @@ mod test {
                 _ => None,
             }
         });
-        assert_that!(AVAILABLE_LOCALES).contains_exactly_in_any_order(locale_folders);
+        let locale_folders: Vec<String> = locale_folders.collect();
+        assert_that!(AVAILABLE_LOCALES).contains_exactly_in_any_order(locale_folders.clone());
+        assert_that!(EMBEDDED_LOCALES.map(|(name, _)| name))
+            .contains_exactly_in_any_order(locale_folders);
+    }
+    #[test]
+    fn embedded_locale_is_used_without_files_on_disk() {
+        let lang_id: LanguageIdentifier = "de".parse().unwrap();
+        let (source, path) = embedded_source(&lang_id).unwrap();
+        assert_that!(source.contains("title =")).is_true();
+        assert_that!(path.as_str()).is_equal_to("embedded de");
+        assert_that!(embedded_source(&"pt".parse().unwrap())).is_none();
+    }
+    #[test]
+    fn missing_locale_files_fall_back_to_the_embedded_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let roots = [dir.path().to_path_buf()];
+
+        let locale = FluentLocale::from_roots("de", &roots).unwrap();
+
+        assert_that!(locale.language()).is_equal_to("de".to_string());
+        assert_that!(locale.text(Key::Save, None)).is_equal_to("Speichern".to_string());
+    }
+    #[test]
+    fn broken_locale_files_fail_and_leave_the_embedded_copy_to_the_caller() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("de")).unwrap();
+        fs::write(dir.path().join("de/main.ftl"), "title = Nur der Titel\n").unwrap();
+
+        let error = FluentLocale::from_roots("de", &[dir.path().to_path_buf()]).unwrap_err();
+
+        assert_that!(error.kind()).is_equal_to(error::ErrorKind::InvalidInput);
+        assert_that!(FluentLocale::embedded("de").language()).is_equal_to("de".to_string());
+        assert_that!(FluentLocale::embedded("not a language").language())
+            .is_equal_to(DEFAULT_LOCALE.to_string());
     }
     #[test]
     fn all_translations_are_valid() {
@@ -190,6 +411,69 @@ mod test {
         }
     }
     #[test]
+    fn only_arabic_is_rtl() {
+        for lang in AVAILABLE_LOCALES {
+            assert_that!(FluentLocale::try_new(lang).unwrap().is_rtl())
+                .described_as(lang)
+                .is_equal_to(lang == "ar");
+        }
+    }
+    #[test]
+    fn negotiated_language_is_one_of_the_available_locales() {
+        assert_that!(FluentLocale::try_new("de-AT").unwrap().language())
+            .is_equal_to("de".to_string());
+        assert_that!(FluentLocale::try_new("en-US").unwrap().language())
+            .is_equal_to("en-US".to_string());
+    }
+    #[test]
+    fn every_key_formats_under_rtl_locale() {
+        let locale = FluentLocale::try_new("ar").unwrap();
+        let mut args = FluentArgs::new();
+        args.set("name", "عمل");
+        args.set("count", 1);
+        args.set("total", 4);
+        args.set("code", 0);
+        args.set("bytes", 512);
+        args.set("configured", 2);
+        args.set("event", "started");
+        for key in Key::iter() {
+            assert_that!(locale.text(key, Some(&args)).is_empty())
+                .described_as(key.to_string())
+                .is_false();
+        }
+    }
+    #[test]
+    fn script_count_formats_arguments() {
+        let locale = FluentLocale::try_new(DEFAULT_LOCALE).unwrap();
+        let mut args = FluentArgs::new();
+        args.set("name", "Work");
+        args.set("count", 3);
+        args.set("total", 4);
+
+        let text = locale.text(Key::ActivityScriptCount, Some(&args));
+
+        // Placeables are wrapped in Unicode isolation marks so RTL text lays out correctly
+        assert_that!(text.replace(['\u{2068}', '\u{2069}'], ""))
+            .is_equal_to("Work — 3/4".to_string());
+    }
+    #[test]
+    fn title_summary_pluralizes_total() {
+        let locale = FluentLocale::try_new(DEFAULT_LOCALE).unwrap();
+        let summary = |configured: usize, total: usize| {
+            let mut args = FluentArgs::new();
+            args.set("configured", configured);
+            args.set("total", total);
+            locale
+                .text(Key::TitleSummary, Some(&args))
+                .replace(['\u{2068}', '\u{2069}'], "")
+        };
+
+        assert_that!(summary(1, 1))
+            .is_equal_to("KDE Activity Script Selector — 1 of 1 activity configured".to_string());
+        assert_that!(summary(3, 8))
+            .is_equal_to("KDE Activity Script Selector — 3 of 8 activities configured".to_string());
+    }
+    #[test]
     fn locale_roots_with_custom_xdg_dirs() {
         with_var("XDG_DATA_DIRS", Some("/one:/two:/usr/share"), || {
             let roots = locale_roots().unwrap();
@@ -206,6 +490,17 @@ mod test {
         });
     }
     #[test]
+    fn locale_roots_with_custom_locale_dir_first() {
+        with_var("KAS_LOCALE_DIR", Some("/home/me/translations"), || {
+            let roots = locale_roots().unwrap();
+            assert_that!(roots[0].to_string_lossy()).is_equal_to("/home/me/translations");
+            assert_that!(roots[1].to_string_lossy()).is_equal_to("locales");
+        });
+        with_var("KAS_LOCALE_DIR", Some(""), || {
+            assert_that!(locale_roots().unwrap()[0].to_string_lossy()).is_equal_to("locales");
+        });
+    }
+    #[test]
     fn locale_roots_with_empty_env() {
         with_var("XDG_DATA_DIRS", Option::<&str>::None, || {
             assert_that!(locale_roots().unwrap()).contains_all_of([
@@ -217,21 +512,28 @@ mod test {
     }
     #[test]
     fn locale_roots_is_in_priority_order() {
-        with_var("XDG_DATA_DIRS", Some("/one:/two:/three"), || {
-            let root_list = locale_roots().unwrap();
-            let order_list = [
-                "locales",
-                "/usr/local/share",
-                "/usr/share",
-                "/one",
-                "/two",
-                "/three",
-            ];
-            for (i, (root, prefix)) in root_list.iter().zip(order_list).enumerate() {
-                assert_that!(root.to_string_lossy())
-                    .described_as(format!("index: {i}"))
-                    .starts_with(prefix);
-            }
-        });
+        with_vars(
+            [
+                ("XDG_DATA_DIRS", Some("/one:/two:/three")),
+                ("KAS_LOCALE_DIR", Some("/custom")),
+            ],
+            || {
+                let root_list = locale_roots().unwrap();
+                let order_list = [
+                    "/custom",
+                    "locales",
+                    "/usr/local/share",
+                    "/usr/share",
+                    "/one",
+                    "/two",
+                    "/three",
+                ];
+                for (i, (root, prefix)) in root_list.iter().zip(order_list).enumerate() {
+                    assert_that!(root.to_string_lossy())
+                        .described_as(format!("index: {i}"))
+                        .starts_with(prefix);
+                }
+            },
+        );
     }
 }
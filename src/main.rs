@@ -1,87 +1,265 @@
 #![deny(clippy::unwrap_used, clippy::expect_used)]
 #![warn(clippy::all, clippy::nursery)]
 
-mod activity;
-mod config;
-mod error;
-mod locale;
-mod shell_script_filename;
+mod app_state;
+#[cfg(test)]
+mod fixture;
+mod geometry;
+mod recovery;
+mod runner;
+mod session;
+mod startup;
+mod state;
+mod template;
+mod transfer;
+mod watcher;
 
-use activity::{Activity, ActivityEvent};
+use clap::Parser;
+use kas_selector::{
+    activity, app_dirs, apply, cli, config, error, file_format, file_system, locale,
+    shell_script_filename,
+};
+
+use activity::{Activity, ActivityEvent, ActivityState, ExecutableStatus, ScriptStatus};
+use app_state::{AppState, ExitAction, ExitChoice};
 use config::Config;
+use fluent_bundle::FluentArgs;
 use gtk::prelude::*;
 use locale::{DEFAULT_LOCALE, FluentLocale};
 use relm4::prelude::*;
-use relm4_components::open_dialog::{
-    OpenDialog, OpenDialogMsg, OpenDialogResponse, OpenDialogSettings,
+use relm4_components::{
+    open_dialog::{OpenDialog, OpenDialogMsg, OpenDialogResponse, OpenDialogSettings},
+    save_dialog::{SaveDialog, SaveDialogMsg, SaveDialogResponse, SaveDialogSettings},
 };
+use shell_script_filename::AllowedExtensions;
 use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use strum::IntoEnumIterator;
+use transfer::ConflictStrategy;
 
 const STYLE: &str = r#"
 .label {
     font-weight: bold;
 }
+.ok {
+    color: @success_color;
+}
+.missing {
+    color: @error_color;
+}
+.warning {
+    color: @warning_color;
+}
+.status {
+    font-size: smaller;
+}
 "#;
-const DEFAULT_KAS_PATH: &str = ".local/share/kactivitymanagerd/activities";
-const DEFAULT_SCRIPT_FILENAME: &str = "activity_script.sh";
+const DEFAULT_EXPORT_FILENAME: &str = "kas-selector.json";
 const KAS_HELP_URL: &str = "https://github.com/BernardIgiri/kas-selector";
 const WINDOW_WIDTH: i32 = 500;
 const WINDOW_HEIGHT: i32 = 260;
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(1);
+const LOCALE_WARNING_DURATION: Duration = Duration::from_secs(5);
+const FALLBACK_ACTIVITY_ICON: &str = "user-desktop";
 
 #[derive(Debug)]
 struct AppModel {
     config: Config,
-    activities: Vec<Activity>,
-    selected_activity_index: usize,
+    state: AppState,
     locale: FluentLocale,
+    lang: String,
+    /// Bumped whenever `locale` is replaced, so the view knows to relabel itself.
+    locale_generation: usize,
+    locale_watcher: Option<watcher::RootWatcher>,
+    locale_warning: Option<String>,
     open_dialog: Controller<OpenDialog>,
+    export_dialog: Controller<SaveDialog>,
+    import_dialog: Controller<OpenDialog>,
+    template_dialog: Controller<SaveDialog>,
     pending_event: ActivityEvent,
-    is_dirty: bool,
-    is_loading: bool,
-    save_error_dialog_visible: bool,
+    batch_assign: bool,
+    busy_text: locale::Key,
+    watcher: Option<watcher::RootWatcher>,
+    autosave_path: Option<PathBuf>,
+    recovered: Option<recovery::Snapshot>,
+    last_selected_id: Option<String>,
+    autosave_pending: bool,
+    save_progress: Option<(usize, usize)>,
+    script_details: Option<(ActivityEvent, String)>,
+    /// Broken links are only pointed out after the first load, not on every reload.
+    broken_links_checked: bool,
+    /// Set when saving was chosen on quit, so the app closes once the save succeeds.
+    exit_after_save: bool,
+    script_error: Option<locale::Key>,
+    pending_import: Option<transfer::ScriptExport>,
+    orphans: Vec<String>,
+}
+#[derive(Debug)]
+struct EventRow {
+    path_label: gtk::Label,
+    details_button: gtk::MenuButton,
+    details_label: gtk::Label,
+    run_button: gtk::Button,
+    open_button: gtk::Button,
+    create_button: gtk::Button,
+    add_button: gtk::Button,
+    scripts_button: gtk::MenuButton,
+    scripts_box: gtk::Box,
+    shown_scripts: Vec<PathBuf>,
+    script_entries: Vec<gtk::Box>,
+    assign_all_button: gtk::Button,
+    delete_button: gtk::Button,
+    copy_path_button: gtk::Button,
 }
 #[derive(Debug)]
 struct AppWidgets {
-    root: gtk::Window,
-    path_labels: HashMap<ActivityEvent, gtk::Label>,
+    window: gtk::Window,
+    activity_list: gtk::StringList,
+    activity_icons: gtk::StringList,
+    activity_dropdown: gtk::DropDown,
+    multi_select_button: gtk::ToggleButton,
+    copy_from_button: gtk::MenuButton,
+    copy_from_popover: gtk::Popover,
+    copy_from_box: gtk::Box,
+    copy_from_buttons: Vec<gtk::Button>,
+    copy_to_button: gtk::Button,
+    batch_scroller: gtk::ScrolledWindow,
+    batch_box: gtk::Box,
+    batch_checks: Vec<gtk::CheckButton>,
+    event_rows: HashMap<ActivityEvent, EventRow>,
+    events_grid: gtk::Grid,
+    no_activities_label: gtk::Label,
+    stale_banner: gtk::Box,
     save_button: gtk::Button,
-    save_error_dialog: gtk::AlertDialog,
-    save_error_dialog_visible: bool,
+    undo_button: gtk::Button,
+    redo_button: gtk::Button,
+    refresh_button: gtk::Button,
+    import_button: gtk::Button,
+    orphans_button: gtk::Button,
     spinner: gtk::Box,
+    spinner_icon: gtk::Spinner,
+    spinner_label: gtk::Label,
+    save_progress_bar: gtk::ProgressBar,
+    locale_warning_label: gtk::Label,
+    script_error_label: gtk::Label,
+    translations: Vec<Translation>,
+    locale_generation: usize,
+}
+#[derive(Debug)]
+enum Translation {
+    Label(gtk::Label, locale::Key),
+    ButtonLabel(gtk::Button, locale::Key),
+    CheckLabel(gtk::CheckButton, locale::Key),
+    Tooltip(gtk::Widget, locale::Key),
+    Placeholder(gtk::SearchEntry, locale::Key),
 }
 #[derive(Debug)]
 enum AppMsg {
     ChooseActivity(usize),
+    FilterActivities(String),
+    ToggleMultiSelect(bool),
+    ToggleSortByName(bool),
+    ToggleSortByStatus(bool),
+    ToggleBatchActivity(usize, bool),
+    CopyScriptsFrom(usize),
+    ChooseCopyTargets,
+    CopyScriptsTo {
+        targets: Vec<usize>,
+        overwrite: bool,
+    },
     ChooseScript(ActivityEvent),
-    DeleteScript(ActivityEvent),
+    BatchAssignScript(ActivityEvent),
+    BatchAssignConfirmed(ActivityEvent, PathBuf),
+    BatchAssignCancelled,
+    RequestDeleteScript(ActivityEvent),
+    UndoLastChange,
+    RedoLastChange,
+    DeleteScript,
+    CancelDeleteScript,
+    RemoveScript(ActivityEvent, PathBuf),
+    TestRun(ActivityEvent),
+    OpenScriptInEditor(ActivityEvent),
+    CopyPathToClipboard(ActivityEvent),
+    ShowScriptDetails(ActivityEvent),
     ScriptChosen(PathBuf),
+    ScriptSyntaxError {
+        event: ActivityEvent,
+        path: PathBuf,
+        error_text: String,
+    },
+    AssignScript(ActivityEvent, PathBuf),
+    MakeExecutable(ActivityEvent, PathBuf),
     ChooseScriptCancel,
-    Exit,
+    ChooseTemplate(ActivityEvent),
+    CreateScript(PathBuf),
+    ChooseTemplateCancel,
+    ChooseExport,
+    Export(PathBuf),
+    ChooseExportCancel,
+    ChooseImport,
+    Import(PathBuf),
+    ImportConflictResolution(ConflictStrategy),
+    ChooseImportCancel,
+    CleanUpOrphans,
+    ConfirmCleanUpOrphans,
+    FilesChanged,
+    Reload,
+    ReloadNow,
+    RestoreAutosave(recovery::Snapshot),
+    DiscardAutosave,
+    ReloadLocale,
+    ChangeLanguage(String),
+    RequestExit,
+    ExitChosen(Option<ExitChoice>),
+    SaveAndExit,
+    DiscardAndExit,
+    ForceExit,
     Help,
+    About,
+    OpenRoot,
     Save,
-    CloseSaveErrorDialog,
+    ConfirmActivityManager,
+    ConfirmSave,
+    ConfirmFileRemovals,
+    ConfirmOverwrites,
+    BrokenScriptWarning(Vec<error::Application>),
+    FixPermissions(Vec<PathBuf>),
+    SaveElevated {
+        command: Vec<OsString>,
+        mapping_path: PathBuf,
+    },
 }
 #[derive(Debug)]
 enum AppCmd {
-    SaveFinished(Result<(), error::Application>),
+    LoadFinished(Result<(Vec<Activity>, Vec<String>), error::Application>),
+    SaveProgress {
+        done: usize,
+        total: usize,
+    },
+    Autosave,
+    TestRunFinished(Result<runner::RunOutput, error::Application>),
+    SyntaxChecked {
+        event: ActivityEvent,
+        path: PathBuf,
+        result: Result<(), error::Application>,
+    },
+    ClearLocaleWarning,
+    SaveFinished(Result<activity::SaveReport, error::Application>),
+    ElevatedSaveFinished(Result<(), error::Application>),
+    SaveSettled(usize),
+    FixPermissionsFinished(Result<usize, error::Application>),
+    EditorClosed(Result<(), error::Application>),
 }
 #[derive(Debug)]
 struct AppInit {
     config: Config,
-    activities: Vec<Activity>,
     lang: String,
+    locale: FluentLocale,
 }
 
-impl AppModel {
-    const fn can_save(&self) -> bool {
-        self.is_dirty && !self.is_loading
-    }
-}
-
-#[allow(clippy::expect_used)]
 impl Component for AppModel {
     type Init = AppInit;
     type Input = AppMsg;
@@ -98,8 +276,8 @@ impl Component for AppModel {
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
-        let locale =
-            FluentLocale::try_new(&init.lang).expect("Failed to initialize localization: {e}");
+        let locale = init.locale;
+        gtk::Widget::set_default_direction(text_direction(&locale));
         let open_dialog = OpenDialog::builder()
             .transient_for_native(&root)
             .launch(OpenDialogSettings {
@@ -108,57 +286,285 @@ impl Component for AppModel {
                 accept_label: locale.text(locale::Key::Open, None),
                 create_folders: false,
                 is_modal: true,
-                filters: Vec::new(),
+                filters: script_filters(&locale, init.config.script_extensions()),
             })
             .forward(sender.input_sender(), |response| match response {
                 OpenDialogResponse::Accept(path) => AppMsg::ScriptChosen(path),
                 OpenDialogResponse::Cancel => AppMsg::ChooseScriptCancel,
             });
+        let export_dialog = SaveDialog::builder()
+            .transient_for_native(&root)
+            .launch(SaveDialogSettings {
+                cancel_label: locale.text(locale::Key::Cancel, None),
+                accept_label: locale.text(locale::Key::Save, None),
+                create_folders: true,
+                is_modal: true,
+                filters: Vec::new(),
+            })
+            .forward(sender.input_sender(), |response| match response {
+                SaveDialogResponse::Accept(path) => AppMsg::Export(path),
+                SaveDialogResponse::Cancel => AppMsg::ChooseExportCancel,
+            });
+        let import_dialog = OpenDialog::builder()
+            .transient_for_native(&root)
+            .launch(OpenDialogSettings {
+                folder_mode: false,
+                cancel_label: locale.text(locale::Key::Cancel, None),
+                accept_label: locale.text(locale::Key::Open, None),
+                create_folders: false,
+                is_modal: true,
+                filters: Vec::new(),
+            })
+            .forward(sender.input_sender(), |response| match response {
+                OpenDialogResponse::Accept(path) => AppMsg::Import(path),
+                OpenDialogResponse::Cancel => AppMsg::ChooseImportCancel,
+            });
+        let watcher = if *init.config.watch() {
+            let input = sender.input_sender().clone();
+            let staging = init.config.root_path().join(activity::STAGING_DIR);
+            watcher::RootWatcher::start(init.config.root_path(), Some(staging), move || {
+                input.emit(AppMsg::FilesChanged)
+            })
+            .inspect_err(|e| eprintln!("Watching for changes failed due to: {e}"))
+            .ok()
+        } else {
+            None
+        };
+        let locale_watcher = locale::custom_locale_dir().and_then(|dir| {
+            let input = sender.input_sender().clone();
+            watcher::RootWatcher::start(&dir, None, move || input.emit(AppMsg::ReloadLocale))
+                .inspect_err(|e| eprintln!("Watching translations failed due to: {e}"))
+                .ok()
+        });
+        let autosave_path = recovery::autosave_path();
+        let recovered = autosave_path
+            .as_deref()
+            .filter(|_| !*init.config.read_only())
+            .and_then(|path| {
+                recovery::Snapshot::read(path)
+                    .inspect_err(|e| eprintln!("Reading autosave failed due to: {e}"))
+                    .ok()
+                    .flatten()
+            });
+        let template_dialog = SaveDialog::builder()
+            .transient_for_native(&root)
+            .launch(SaveDialogSettings {
+                cancel_label: locale.text(locale::Key::Cancel, None),
+                accept_label: locale.text(locale::Key::Save, None),
+                create_folders: true,
+                is_modal: true,
+                filters: Vec::new(),
+            })
+            .forward(sender.input_sender(), |response| match response {
+                SaveDialogResponse::Accept(path) => AppMsg::CreateScript(path),
+                SaveDialogResponse::Cancel => AppMsg::ChooseTemplateCancel,
+            });
+        let mut state = AppState::loading();
+        state.set_sort_by_name(*init.config.sort_by_name());
+        state.set_read_only(*init.config.read_only());
         let model = Self {
             config: init.config,
-            activities: init.activities,
-            selected_activity_index: 0,
+            state,
             locale,
+            lang: init.lang,
+            locale_generation: 0,
+            locale_watcher,
+            locale_warning: None,
             open_dialog,
+            export_dialog,
+            import_dialog,
+            template_dialog,
             pending_event: ActivityEvent::Activated,
-            is_dirty: false,
-            is_loading: false,
-            save_error_dialog_visible: false,
+            batch_assign: false,
+            busy_text: locale::Key::LoadingData,
+            watcher,
+            autosave_path,
+            recovered,
+            last_selected_id: state::state_path()
+                .map(|path| state::StateFile::load(&path).selected_activity_id)
+                .filter(|id| !id.is_empty()),
+            autosave_pending: false,
+            save_progress: None,
+            script_details: None,
+            broken_links_checked: false,
+            orphans: Vec::new(),
+            exit_after_save: false,
+            script_error: None,
+            pending_import: None,
         };
+        spawn_load(&model.config, &sender);
         let provider = gtk::CssProvider::new();
         provider.load_from_string(STYLE);
-        let display = gtk::gdk::Display::default().expect("Display should connect!");
-        gtk::style_context_add_provider_for_display(
-            &display,
-            &provider,
-            gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
-        );
-        root.set_default_width(WINDOW_WIDTH);
-        root.set_default_height(WINDOW_HEIGHT);
+        if let Some(display) = gtk::gdk::Display::default() {
+            gtk::style_context_add_provider_for_display(
+                &display,
+                &provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+        let geometry = geometry::geometry_path()
+            .and_then(|path| geometry::WindowGeometry::read(&path))
+            .unwrap_or(geometry::WindowGeometry {
+                width: WINDOW_WIDTH,
+                height: WINDOW_HEIGHT,
+            });
+        root.set_default_width(geometry.width);
+        root.set_default_height(geometry.height);
         root.set_title(Some(model.locale.text(locale::Key::Title, None).as_str()));
+        let activity_list = gtk::StringList::new(&[]);
+        let activity_icons = gtk::StringList::new(&[]);
+        let batch_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        let import_button = gtk::Button::with_label(&model.locale.text(locale::Key::Import, None));
+        import_button.add_css_class("flat");
+        import_button.set_sensitive(false);
+        let export_button = gtk::Button::with_label(&model.locale.text(locale::Key::Export, None));
+        export_button.add_css_class("flat");
+        let sort_check =
+            gtk::CheckButton::with_label(&model.locale.text(locale::Key::SortByName, None));
+        sort_check.set_active(model.state.is_sorted_by_name());
+        let status_sort_check =
+            gtk::CheckButton::with_label(&model.locale.text(locale::Key::SortByStatus, None));
+        status_sort_check.set_active(model.state.is_sorted_by_status());
+        let menu_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        menu_box.append(&import_button);
+        menu_box.append(&export_button);
+        menu_box.append(&sort_check);
+        menu_box.append(&status_sort_check);
+        let orphans_button =
+            gtk::Button::with_label(&model.locale.text(locale::Key::CleanUpOrphans, None));
+        orphans_button.add_css_class("flat");
+        orphans_button.set_visible(false);
+        menu_box.append(&orphans_button);
+        let language_label = gtk::Label::new(Some(&model.locale.text(locale::Key::Language, None)));
+        let language_dropdown = gtk::DropDown::from_strings(&locale::LOCALE_NAMES);
+        let language = model.locale.language();
+        if let Some(index) = locale::AVAILABLE_LOCALES
+            .iter()
+            .position(|lang| *lang == language)
+        {
+            language_dropdown.set_selected(index as u32);
+        }
+        let language_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        language_box.set_margin_start(6);
+        language_box.append(&language_label);
+        language_box.append(&language_dropdown);
+        menu_box.append(&language_box);
+        let about_button = gtk::Button::with_label(&model.locale.text(locale::Key::About, None));
+        about_button.add_css_class("flat");
+        menu_box.append(&about_button);
+        let menu_popover = gtk::Popover::new();
+        menu_popover.set_child(Some(&menu_box));
+        let copy_from_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        let copy_from_popover = gtk::Popover::new();
+        copy_from_popover.set_child(Some(&copy_from_box));
         relm4::view! {
-            save_error_dialog = gtk::AlertDialog {
-                set_modal: true,
-                set_message: &model.locale.text(locale::Key::ErrorSaveFailed, None),
-            },
             container = gtk::Box {
                 set_orientation: gtk::Orientation::Vertical,
                 set_spacing: 12,
                 set_margin_all: 12,
 
-                gtk::DropDown::from_strings(&model.activities.iter().map(|a| a.name().as_str()).collect::<Vec<_>>()) {
-                    connect_selected_notify[sender] => move |dropdown| {
-                        sender.input(AppMsg::ChooseActivity(dropdown.selected() as usize))
+                #[name = "stale_banner"]
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 6,
+                    set_visible: false,
+
+                    #[name = "stale_label"]
+                    gtk::Label {
+                        set_label: &model.locale.text(locale::Key::FilesChanged, None),
+                        set_hexpand: true,
+                        set_halign: gtk::Align::Start,
+                        set_wrap: true,
+                    },
+                    #[name = "reload_button"]
+                    gtk::Button {
+                        set_label: &model.locale.text(locale::Key::Reload, None),
+                        connect_clicked[sender] => move |_| {
+                            sender.input(AppMsg::ReloadNow)
+                        },
+                    },
+                },
+
+                #[name = "activity_search"]
+                gtk::SearchEntry {
+                    set_placeholder_text: Some(&model.locale.text(locale::Key::SearchActivities, None)),
+                    connect_search_changed[sender] => move |entry| {
+                        sender.input(AppMsg::FilterActivities(entry.text().to_string()))
                     },
-                    set_selected: model.selected_activity_index as u32,
-                    set_tooltip: &model.locale.text(locale::Key::Activity, None),
                 },
 
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 6,
+
+                    #[name = "activity_dropdown"]
+                    gtk::DropDown {
+                        set_model: Some(&activity_list),
+                        set_factory: Some(&activity_item_factory(&activity_icons)),
+                        set_hexpand: true,
+                        set_sensitive: false,
+                        connect_selected_notify[sender] => move |dropdown| {
+                            let index = dropdown.selected();
+                            if index != gtk::INVALID_LIST_POSITION {
+                                sender.input(AppMsg::ChooseActivity(index as usize))
+                            }
+                        },
+                        set_tooltip: &model.locale.text(locale::Key::Activity, None),
+                    },
+                    #[name = "multi_select_button"]
+                    gtk::ToggleButton {
+                        set_icon_name: "edit-select-all",
+                        set_sensitive: false,
+                        set_tooltip: &model.locale.text(locale::Key::MultiSelect, None),
+                        connect_toggled[sender] => move |button| {
+                            sender.input(AppMsg::ToggleMultiSelect(button.is_active()))
+                        },
+                    },
+                    #[name = "copy_from_button"]
+                    gtk::MenuButton {
+                        set_icon_name: "edit-copy",
+                        set_sensitive: false,
+                        set_popover: Some(&copy_from_popover),
+                        set_tooltip: &model.locale.text(locale::Key::CopyScriptsFrom, None),
+                    },
+                    #[name = "copy_to_button"]
+                    gtk::Button::from_icon_name("document-send") {
+                        set_sensitive: false,
+                        set_tooltip: &model.locale.text(locale::Key::CopyScriptsTo, None),
+                        connect_clicked[sender] => move |_| {
+                            sender.input(AppMsg::ChooseCopyTargets)
+                        },
+                    },
+                },
+
+                #[name = "batch_scroller"]
+                gtk::ScrolledWindow {
+                    set_visible: false,
+                    set_max_content_height: 120,
+                    set_propagate_natural_height: true,
+                    set_child: Some(&batch_box),
+                },
+
+                #[name = "no_activities_label"]
+                gtk::Label {
+                    set_label: &model.locale.text(locale::Key::NoActivities, None),
+                    set_visible: false,
+                    set_vexpand: true,
+                    set_wrap: true,
+                    add_css_class: "dim-label",
+                },
                 #[name = "events_grid"]
                 gtk::Grid {
                     set_row_spacing: 6,
                     set_column_spacing: 6,
                 },
+                #[name = "script_error_label"]
+                gtk::Label {
+                    set_visible: false,
+                    set_halign: gtk::Align::Start,
+                    set_wrap: true,
+                    add_css_class: "warning",
+                },
 
                 gtk::Box {
                     set_orientation: gtk::Orientation::Horizontal,
@@ -170,62 +576,217 @@ impl Component for AppModel {
                     gtk::Box {
                         set_orientation: gtk::Orientation::Horizontal,
                         set_spacing: 6,
-                        set_visible: false,
+                        set_visible: true,
 
+                        #[name = "spinner_icon"]
                         gtk::Spinner {
                             set_spinning: true,
                         },
+                        #[name = "save_progress_bar"]
+                        gtk::ProgressBar {
+                            set_valign: gtk::Align::Center,
+                            set_visible: false,
+                        },
+                        #[name = "spinner_label"]
                         gtk::Label {
-                            set_label: &model.locale.text(locale::Key::SavingData, None),
+                            set_label: &model.locale.text(model.busy_text, None),
                         }
                     },
+                    #[name = "locale_warning_label"]
+                    gtk::Label {
+                        set_visible: false,
+                        set_hexpand: true,
+                        set_halign: gtk::Align::Start,
+                        set_ellipsize: gtk::pango::EllipsizeMode::End,
+                        add_css_class: "warning",
+                    },
                     gtk::Box {
                         set_hexpand: true,
                     },
+                    #[name = "refresh_button"]
+                    gtk::Button::from_icon_name("view-refresh") {
+                        set_tooltip: &model.locale.text(locale::Key::ReloadActivities, None),
+                        connect_clicked[sender] => move |_| {
+                            sender.input(AppMsg::Reload)
+                        },
+                    },
+                    #[name = "undo_button"]
+                    gtk::Button::from_icon_name("edit-undo") {
+                        set_sensitive: false,
+                        set_tooltip: &model.locale.text(locale::Key::Undo, None),
+                        connect_clicked[sender] => move |_| {
+                            sender.input(AppMsg::UndoLastChange)
+                        },
+                    },
+                    #[name = "redo_button"]
+                    gtk::Button::from_icon_name("edit-redo") {
+                        set_sensitive: false,
+                        set_tooltip: &model.locale.text(locale::Key::Redo, None),
+                        connect_clicked[sender] => move |_| {
+                            sender.input(AppMsg::RedoLastChange)
+                        },
+                    },
                     #[name = "quit_button"]
                     gtk::Button {
-                        set_label: &model.locale.text(locale::Key::Quit, None),
+                        set_label: &model.locale.text(locale::Key::QuitButton, None),
+                        set_use_underline: true,
                         set_size_request: (80, -1),
                     },
                     #[name = "save_button"]
                     gtk::Button {
-                        set_label: &model.locale.text(locale::Key::Save, None),
+                        set_label: &model.locale.text(locale::Key::SaveButton, None),
+                        set_use_underline: true,
                         set_sensitive: false,
                         set_size_request: (80, -1),
                     },
+                    #[name = "menu_button"]
+                    gtk::MenuButton {
+                        set_icon_name: "open-menu-symbolic",
+                        set_popover: Some(&menu_popover),
+                        set_tooltip: &model.locale.text(locale::Key::Menu, None),
+                    },
                     #[name = "help_button"]
-                    gtk::Button::from_icon_name("help-about") {
-                        set_tooltip: &model.locale.text(locale::Key::Help, None),
+                    gtk::Button {
+                        set_label: &model.locale.text(locale::Key::HelpButton, None),
+                        set_use_underline: true,
+                    },
+                },
+
+                gtk::Box {
+                    set_orientation: gtk::Orientation::Horizontal,
+                    set_spacing: 6,
+                    add_css_class: "status",
+
+                    #[name = "status_folder_label"]
+                    gtk::Label {
+                        set_label: &model.locale.text(locale::Key::StatusFolder, None),
+                        add_css_class: "dim-label",
+                    },
+                    #[name = "root_button"]
+                    gtk::Button {
+                        add_css_class: "flat",
+                        set_hexpand: true,
+                        set_tooltip: &model.locale.text(locale::Key::OpenFolder, None),
+                        connect_clicked[sender] => move |_| {
+                            sender.input(AppMsg::OpenRoot)
+                        },
+                        #[wrap(Some)]
+                        set_child = &gtk::Label {
+                            set_label: &model.config.root_path().display().to_string(),
+                            set_direction: gtk::TextDirection::Ltr,
+                            set_halign: gtk::Align::Start,
+                            set_ellipsize: gtk::pango::EllipsizeMode::Middle,
+                        },
+                    },
+                    #[name = "status_script_name_label"]
+                    gtk::Label {
+                        set_label: &model.locale.text(locale::Key::StatusScriptName, None),
+                        add_css_class: "dim-label",
+                    },
+                    gtk::Label {
+                        set_label: model.config.script_filename().as_str(),
+                        set_direction: gtk::TextDirection::Ltr,
+                        set_selectable: true,
                     },
                 }
             }
         }
         root.set_child(Some(&container));
-        let mut path_labels = HashMap::new();
+        root.add_controller(edit_shortcuts(&sender));
+        let mut translations = vec![
+            Translation::ButtonLabel(import_button.clone(), locale::Key::Import),
+            Translation::ButtonLabel(export_button.clone(), locale::Key::Export),
+            Translation::CheckLabel(sort_check.clone(), locale::Key::SortByName),
+            Translation::CheckLabel(status_sort_check.clone(), locale::Key::SortByStatus),
+            Translation::ButtonLabel(orphans_button.clone(), locale::Key::CleanUpOrphans),
+            Translation::Label(language_label, locale::Key::Language),
+            Translation::ButtonLabel(about_button.clone(), locale::Key::About),
+            Translation::Label(stale_label, locale::Key::FilesChanged),
+            Translation::Label(no_activities_label.clone(), locale::Key::NoActivities),
+            Translation::ButtonLabel(reload_button, locale::Key::Reload),
+            Translation::Placeholder(activity_search, locale::Key::SearchActivities),
+            Translation::Tooltip(activity_dropdown.clone().upcast(), locale::Key::Activity),
+            Translation::Tooltip(
+                multi_select_button.clone().upcast(),
+                locale::Key::MultiSelect,
+            ),
+            Translation::Tooltip(
+                copy_from_button.clone().upcast(),
+                locale::Key::CopyScriptsFrom,
+            ),
+            Translation::Tooltip(copy_to_button.clone().upcast(), locale::Key::CopyScriptsTo),
+            Translation::Tooltip(undo_button.clone().upcast(), locale::Key::Undo),
+            Translation::Tooltip(redo_button.clone().upcast(), locale::Key::Redo),
+            Translation::Tooltip(
+                refresh_button.clone().upcast(),
+                locale::Key::ReloadActivities,
+            ),
+            Translation::ButtonLabel(quit_button.clone(), locale::Key::QuitButton),
+            Translation::ButtonLabel(save_button.clone(), locale::Key::SaveButton),
+            Translation::Tooltip(menu_button.upcast(), locale::Key::Menu),
+            Translation::ButtonLabel(help_button.clone(), locale::Key::HelpButton),
+            Translation::Label(status_folder_label, locale::Key::StatusFolder),
+            Translation::Tooltip(root_button.upcast(), locale::Key::OpenFolder),
+            Translation::Label(status_script_name_label, locale::Key::StatusScriptName),
+        ];
+        let mut event_rows = HashMap::new();
         let sender_clone = sender.clone();
         save_button.connect_clicked(move |_| {
             sender_clone.input(AppMsg::Save);
         });
         let sender_clone = sender.clone();
         quit_button.connect_clicked(move |_| {
-            sender_clone.input(AppMsg::Exit);
+            sender_clone.input(AppMsg::RequestExit);
         });
         let sender_clone = sender.clone();
-        help_button.connect_clicked(move |_| {
-            sender_clone.input(AppMsg::Help);
+        root.connect_close_request(move |_| {
+            sender_clone.input(AppMsg::RequestExit);
+            gtk::glib::Propagation::Stop
+        });
+        let sender_clone = sender.clone();
+        let popover_clone = menu_popover.clone();
+        import_button.connect_clicked(move |_| {
+            popover_clone.popdown();
+            sender_clone.input(AppMsg::ChooseImport);
+        });
+        let sender_clone = sender.clone();
+        let popover_clone = menu_popover.clone();
+        export_button.connect_clicked(move |_| {
+            popover_clone.popdown();
+            sender_clone.input(AppMsg::ChooseExport);
+        });
+        let sender_clone = sender.clone();
+        let popover_clone = menu_popover.clone();
+        orphans_button.connect_clicked(move |_| {
+            popover_clone.popdown();
+            sender_clone.input(AppMsg::CleanUpOrphans);
+        });
+        let sender_clone = sender.clone();
+        let popover_clone = menu_popover.clone();
+        about_button.connect_clicked(move |_| {
+            popover_clone.popdown();
+            sender_clone.input(AppMsg::About);
         });
         let sender_clone = sender.clone();
-        save_error_dialog.connect_cancel_button_notify(move |_| {
-            sender_clone.input(AppMsg::CloseSaveErrorDialog);
+        sort_check.connect_toggled(move |check| {
+            sender_clone.input(AppMsg::ToggleSortByName(check.is_active()));
+        });
+        let sender_clone = sender.clone();
+        status_sort_check.connect_toggled(move |check| {
+            sender_clone.input(AppMsg::ToggleSortByStatus(check.is_active()));
+        });
+        let sender_clone = sender.clone();
+        language_dropdown.connect_selected_notify(move |dropdown| {
+            if let Some(lang) = locale::AVAILABLE_LOCALES.get(dropdown.selected() as usize) {
+                sender_clone.input(AppMsg::ChangeLanguage((*lang).into()));
+            }
+        });
+        let sender_clone = sender.clone();
+        help_button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::Help);
         });
 
         for (row, event) in ActivityEvent::iter().enumerate() {
-            let script_path = model
-                .activities
-                .get(model.selected_activity_index)
-                .and_then(|a| a.get_script(&event))
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_default();
             relm4::view! {
                 event_label = gtk::Label {
                     set_label: &model.locale.text(event.as_key(), None),
@@ -233,118 +794,2014 @@ impl Component for AppModel {
                     add_css_class: "label"
                 },
                 path_label = gtk::Label {
-                    set_label: &script_path,
                     set_hexpand: true,
-                    set_halign: gtk::Align::Start,
-                    set_ellipsize: gtk::pango::EllipsizeMode::Start,
                 },
-                edit_button = gtk::Button::from_icon_name("edit"),
-                delete_button = gtk::Button::from_icon_name("delete"),
+                run_button = gtk::Button::from_icon_name("media-playback-start") {
+                    set_sensitive: false,
+                },
+                open_button = gtk::Button::from_icon_name("document-open") {
+                    set_sensitive: false,
+                },
+                create_button = gtk::Button::from_icon_name("document-new") {
+                    set_sensitive: false,
+                },
+                add_button = gtk::Button::from_icon_name("list-add") {
+                    set_sensitive: false,
+                },
+                assign_all_button = gtk::Button::from_icon_name("edit-select-all") {
+                    set_sensitive: false,
+                },
+                delete_button = gtk::Button::from_icon_name("delete") {
+                    set_sensitive: false,
+                },
+                copy_path_button = gtk::Button::from_icon_name("edit-copy") {
+                    set_sensitive: false,
+                },
             }
+            let details_label = gtk::Label::new(None);
+            details_label.set_selectable(true);
+            details_label.set_margin_start(6);
+            details_label.set_margin_end(6);
+            let details_popover = gtk::Popover::new();
+            details_popover.set_child(Some(&details_label));
+            let sender_clone = sender.clone();
+            details_popover.connect_show(move |_| {
+                sender_clone.input(AppMsg::ShowScriptDetails(event));
+            });
+            let details_button = gtk::MenuButton::new();
+            details_button.set_icon_name("dialog-information-symbolic");
+            details_button.set_popover(Some(&details_popover));
+            details_button.set_sensitive(false);
+            details_button.set_tooltip(&model.locale.text(locale::Key::Details, None));
+            let scripts_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            let scripts_popover = gtk::Popover::new();
+            scripts_popover.set_child(Some(&scripts_box));
+            let scripts_button = gtk::MenuButton::new();
+            scripts_button.set_icon_name("view-list-symbolic");
+            scripts_button.set_popover(Some(&scripts_popover));
+            scripts_button.set_sensitive(false);
+            // Start is mirrored by GTK for right-to-left locales, and the filename is at the
+            // logical end of a path in either direction
+            path_label.set_halign(gtk::Align::Start);
+            path_label.set_ellipsize(gtk::pango::EllipsizeMode::Start);
+            let sender_clone = sender.clone();
+            run_button.set_tooltip(&model.locale.text(locale::Key::TestRun, None));
+            run_button.connect_clicked(move |_| {
+                sender_clone.input(AppMsg::TestRun(event));
+            });
+            let sender_clone = sender.clone();
+            open_button.set_tooltip(&model.locale.text(locale::Key::OpenInEditor, None));
+            open_button.connect_clicked(move |_| {
+                sender_clone.input(AppMsg::OpenScriptInEditor(event));
+            });
+            let sender_clone = sender.clone();
+            create_button.set_tooltip(&model.locale.text(locale::Key::CreateScript, None));
+            create_button.connect_clicked(move |_| {
+                sender_clone.input(AppMsg::ChooseTemplate(event));
+            });
             let sender_clone = sender.clone();
-            edit_button.set_tooltip(&model.locale.text(locale::Key::Edit, None));
-            edit_button.connect_clicked(move |_| {
+            add_button.set_tooltip(&model.locale.text(locale::Key::AddScript, None));
+            add_button.connect_clicked(move |_| {
                 sender_clone.input(AppMsg::ChooseScript(event));
             });
             let sender_clone = sender.clone();
+            assign_all_button.set_tooltip(&model.locale.text(locale::Key::AssignToAll, None));
+            assign_all_button.connect_clicked(move |_| {
+                sender_clone.input(AppMsg::BatchAssignScript(event));
+            });
+            let sender_clone = sender.clone();
             delete_button.set_tooltip(&model.locale.text(locale::Key::Delete, None));
             delete_button.connect_clicked(move |_| {
-                sender_clone.input(AppMsg::DeleteScript(event));
+                sender_clone.input(AppMsg::RequestDeleteScript(event));
+            });
+            let sender_clone = sender.clone();
+            copy_path_button.set_tooltip(&model.locale.text(locale::Key::CopyPath, None));
+            copy_path_button.connect_clicked(move |_| {
+                sender_clone.input(AppMsg::CopyPathToClipboard(event));
             });
 
+            translations.extend([
+                Translation::Label(event_label.clone(), event.as_key()),
+                Translation::Tooltip(details_button.clone().upcast(), locale::Key::Details),
+                Translation::Tooltip(run_button.clone().upcast(), locale::Key::TestRun),
+                Translation::Tooltip(open_button.clone().upcast(), locale::Key::OpenInEditor),
+                Translation::Tooltip(create_button.clone().upcast(), locale::Key::CreateScript),
+                Translation::Tooltip(add_button.clone().upcast(), locale::Key::AddScript),
+                Translation::Tooltip(scripts_button.clone().upcast(), locale::Key::EventScripts),
+                Translation::Tooltip(assign_all_button.clone().upcast(), locale::Key::AssignToAll),
+                Translation::Tooltip(delete_button.clone().upcast(), locale::Key::Delete),
+                Translation::Tooltip(copy_path_button.clone().upcast(), locale::Key::CopyPath),
+            ]);
+            // Tab walks the grid by position, so the columns here are the focus order in a row
             events_grid.attach(&event_label, 0, row as i32, 1, 1);
             events_grid.attach(&path_label, 1, row as i32, 1, 1);
-            events_grid.attach(&edit_button, 2, row as i32, 1, 1);
-            events_grid.attach(&delete_button, 3, row as i32, 1, 1);
+            events_grid.attach(&details_button, 2, row as i32, 1, 1);
+            events_grid.attach(&run_button, 3, row as i32, 1, 1);
+            events_grid.attach(&open_button, 4, row as i32, 1, 1);
+            events_grid.attach(&create_button, 5, row as i32, 1, 1);
+            events_grid.attach(&add_button, 6, row as i32, 1, 1);
+            events_grid.attach(&scripts_button, 7, row as i32, 1, 1);
+            events_grid.attach(&assign_all_button, 8, row as i32, 1, 1);
+            events_grid.attach(&delete_button, 9, row as i32, 1, 1);
+            events_grid.attach(&copy_path_button, 10, row as i32, 1, 1);
 
-            path_labels.insert(event, path_label);
+            event_rows.insert(
+                event,
+                EventRow {
+                    path_label,
+                    details_button,
+                    details_label,
+                    run_button,
+                    open_button,
+                    create_button,
+                    add_button,
+                    scripts_button,
+                    scripts_box,
+                    shown_scripts: Vec::new(),
+                    script_entries: Vec::new(),
+                    assign_all_button,
+                    delete_button,
+                    copy_path_button,
+                },
+            );
+        }
+        for translation in &translations {
+            translation.apply(&model.locale);
         }
         ComponentParts {
             model,
             widgets: Self::Widgets {
-                root,
-                path_labels,
+                window: root.clone(),
+                activity_list,
+                activity_icons,
+                activity_dropdown,
+                multi_select_button,
+                copy_from_button,
+                copy_from_popover,
+                copy_from_box,
+                copy_from_buttons: Vec::new(),
+                copy_to_button,
+                batch_scroller,
+                batch_box,
+                batch_checks: Vec::new(),
+                event_rows,
+                events_grid,
+                no_activities_label,
+                stale_banner,
                 save_button,
-                save_error_dialog,
-                save_error_dialog_visible: false,
+                undo_button,
+                redo_button,
+                refresh_button,
+                import_button,
+                orphans_button,
                 spinner,
+                spinner_icon,
+                spinner_label,
+                save_progress_bar,
+                locale_warning_label,
+                script_error_label,
+                translations,
+                locale_generation: 0,
             },
         }
     }
-    fn update_view(&self, widgets: &mut Self::Widgets, _sender: ComponentSender<Self>) {
-        let activity = &self.activities[self.selected_activity_index];
-        for (event, label) in widgets.path_labels.iter() {
-            let path = activity
-                .get_script(event)
-                .map_or_else(|| "", |v| v.as_path().to_str().unwrap_or_default());
-            label.set_text(path);
+    fn update_view(&self, widgets: &mut Self::Widgets, sender: ComponentSender<Self>) {
+        let relabel = widgets.locale_generation != self.locale_generation;
+        if relabel {
+            widgets.locale_generation = self.locale_generation;
+            for translation in &widgets.translations {
+                translation.apply(&self.locale);
+            }
         }
-        widgets.save_button.set_sensitive(self.can_save());
-        if self.save_error_dialog_visible && !widgets.save_error_dialog_visible {
-            widgets.save_error_dialog.show(Some(&widgets.root));
+        widgets
+            .window
+            .set_title(Some(&window_title(&self.locale, &self.state)));
+        widgets
+            .locale_warning_label
+            .set_visible(self.locale_warning.is_some());
+        if let Some(warning) = &self.locale_warning {
+            widgets.locale_warning_label.set_text(warning);
+            widgets.locale_warning_label.set_tooltip(warning);
+        }
+        widgets
+            .script_error_label
+            .set_visible(self.script_error.is_some());
+        if let Some(key) = self.script_error {
+            widgets
+                .script_error_label
+                .set_text(&self.locale.text(key, None));
+        }
+        let activities = self.state.activities();
+        let labels: Vec<String> = self
+            .state
+            .filtered_indices()
+            .iter()
+            .filter_map(|&index| activities.get(index))
+            .map(|a| activity_label(&self.locale, a))
+            .collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let icons: Vec<&str> = self
+            .state
+            .filtered_indices()
+            .iter()
+            .filter_map(|&index| activities.get(index))
+            .map(|a| a.icon().as_str())
+            .collect();
+        // Icons first, so rows rebound for new labels pick up their new icons
+        sync_string_list(&widgets.activity_icons, &icons);
+        sync_string_list(&widgets.activity_list, &labels);
+        let names: Vec<&str> = self
+            .state
+            .activities()
+            .iter()
+            .map(|a| a.name().as_str())
+            .collect();
+        let check_names: Vec<String> = widgets
+            .batch_checks
+            .iter()
+            .map(|check| check.label().unwrap_or_default().to_string())
+            .collect();
+        if check_names != names {
+            for check in widgets.batch_checks.drain(..) {
+                widgets.batch_box.remove(&check);
+            }
+            for (index, name) in names.iter().enumerate() {
+                let check = gtk::CheckButton::with_label(name);
+                let sender = sender.clone();
+                check.connect_toggled(move |check| {
+                    sender.input(AppMsg::ToggleBatchActivity(index, check.is_active()));
+                });
+                widgets.batch_box.append(&check);
+                widgets.batch_checks.push(check);
+            }
+            for button in widgets.copy_from_buttons.drain(..) {
+                widgets.copy_from_box.remove(&button);
+            }
+            for (index, name) in names.iter().enumerate() {
+                let button = gtk::Button::with_label(name);
+                button.add_css_class("flat");
+                let sender = sender.clone();
+                let popover = widgets.copy_from_popover.clone();
+                button.connect_clicked(move |_| {
+                    popover.popdown();
+                    sender.input(AppMsg::CopyScriptsFrom(index));
+                });
+                widgets.copy_from_box.append(&button);
+                widgets.copy_from_buttons.push(button);
+            }
+        }
+        let selected = self
+            .state
+            .filtered_position()
+            .map_or(gtk::INVALID_LIST_POSITION, |position| position as u32);
+        if widgets.activity_dropdown.selected() != selected {
+            widgets.activity_dropdown.set_selected(selected);
+        }
+        widgets
+            .activity_dropdown
+            .set_sensitive(!self.state.is_loading() && !labels.is_empty());
+        let activity = self.state.selected_activity();
+        let can_edit = self.state.can_edit();
+        let can_modify = self.state.can_modify();
+        widgets.multi_select_button.set_sensitive(can_modify);
+        widgets
+            .copy_from_button
+            .set_sensitive(can_modify && names.len() > 1);
+        widgets.copy_to_button.set_sensitive(
+            can_modify && names.len() > 1 && activity.is_some_and(|a| a.script_count() > 0),
+        );
+        for (index, button) in widgets.copy_from_buttons.iter().enumerate() {
+            button.set_sensitive(index != self.state.selected_activity_index());
+        }
+        widgets
+            .batch_scroller
+            .set_visible(self.state.is_multi_select());
+        let targets = self.state.target_indices();
+        for (index, check) in widgets.batch_checks.iter().enumerate() {
+            let is_target = targets.contains(&index);
+            if check.is_active() != is_target {
+                check.set_active(is_target);
+            }
+            check.set_sensitive(can_modify && index != self.state.selected_activity_index());
+        }
+        for (event, row) in widgets.event_rows.iter_mut() {
+            let scripts = self.state.selected_scripts(*event);
+            let path = if scripts.len() > 1 {
+                let mut args = FluentArgs::new();
+                args.set("count", scripts.len());
+                self.locale.text(locale::Key::EventScriptCount, Some(&args))
+            } else {
+                script_path_text(scripts.first())
+            };
+            row.path_label.set_text(&path);
+            if relabel || row.shown_scripts != scripts {
+                row.rebuild_scripts(*event, scripts, &self.locale, &sender);
+            }
+            let status = activity.map_or(ScriptStatus::Unassigned, |a| a.script_status(event));
+            row.path_label.remove_css_class("ok");
+            row.path_label.remove_css_class("missing");
+            match status {
+                ScriptStatus::Ok => row.path_label.add_css_class("ok"),
+                ScriptStatus::Missing => row.path_label.add_css_class("missing"),
+                ScriptStatus::Unassigned => {}
+            }
+            let tooltip = (status == ScriptStatus::Missing)
+                .then(|| self.locale.text(locale::Key::ScriptMissing, None));
+            row.path_label.set_tooltip_text(tooltip.as_deref());
+            row.run_button
+                .set_sensitive(can_edit && status == ScriptStatus::Ok);
+            row.details_button
+                .set_sensitive(can_edit && status != ScriptStatus::Unassigned);
+            row.open_button.set_sensitive(status == ScriptStatus::Ok);
+            row.create_button
+                .set_sensitive(can_modify && status == ScriptStatus::Unassigned);
+            if let Some((details_event, text)) = &self.script_details
+                && details_event == event
+            {
+                row.details_label.set_text(text);
+            }
+            row.add_button.set_sensitive(can_modify);
+            row.scripts_button.set_sensitive(scripts.len() > 1);
+            row.assign_all_button.set_sensitive(can_modify);
+            row.delete_button.set_sensitive(can_modify);
+            row.copy_path_button
+                .set_sensitive(status != ScriptStatus::Unassigned);
+        }
+        let no_activities = self.state.has_no_activities();
+        widgets.no_activities_label.set_visible(no_activities);
+        widgets.events_grid.set_visible(!no_activities);
+        widgets.stale_banner.set_visible(self.state.is_stale());
+        widgets.save_button.set_sensitive(self.state.can_save());
+        widgets.undo_button.set_sensitive(self.state.can_undo());
+        widgets.redo_button.set_sensitive(self.state.can_redo());
+        widgets
+            .refresh_button
+            .set_sensitive(!self.state.is_loading());
+        widgets.import_button.set_sensitive(can_modify);
+        widgets.orphans_button.set_visible(!self.orphans.is_empty());
+        widgets.orphans_button.set_sensitive(can_modify);
+        widgets
+            .spinner_label
+            .set_label(&self.locale.text(self.busy_text, None));
+        widgets.spinner.set_visible(self.state.is_loading());
+        widgets
+            .spinner_icon
+            .set_visible(self.save_progress.is_none());
+        widgets
+            .save_progress_bar
+            .set_visible(self.save_progress.is_some());
+        if let Some((done, total)) = self.save_progress {
+            widgets
+                .save_progress_bar
+                .set_fraction(done as f64 / total.max(1) as f64);
         }
-        widgets.spinner.set_visible(self.is_loading);
     }
     fn update_cmd(
         &mut self,
         message: Self::CommandOutput,
-        _sender: ComponentSender<Self>,
-        _root: &Self::Root,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
     ) {
-        let AppCmd::SaveFinished(result) = message;
-        self.is_dirty = false;
-        self.is_loading = false;
-        if let Err(e) = result {
-            eprintln!("Save failed due to: {e}");
-            self.save_error_dialog_visible = true;
+        match message {
+            AppCmd::LoadFinished(Ok((activities, orphans))) => {
+                self.state.load_finished(activities);
+                if let Some(id) = self.last_selected_id.take() {
+                    self.state.select_id(&id);
+                }
+                self.orphans = orphans;
+                if !self.broken_links_checked {
+                    self.broken_links_checked = true;
+                    let broken = Activity::broken_links(
+                        &file_system::RealFileSystem,
+                        self.config.root_path(),
+                        self.config.script_filename(),
+                        self.state.activities(),
+                    );
+                    if !broken.is_empty() {
+                        sender.input(AppMsg::BrokenScriptWarning(broken));
+                    }
+                    let not_executable = Activity::non_executable_scripts(self.state.activities());
+                    if !not_executable.is_empty() && !*self.config.read_only() {
+                        self.offer_fix_permissions(not_executable, &sender, root);
+                    }
+                }
+                if let Some(snapshot) = self.recovered.take() {
+                    if snapshot.differs_from(self.state.activities()) {
+                        self.offer_recovery(snapshot, &sender, root);
+                    } else {
+                        self.remove_autosave();
+                    }
+                }
+            }
+            AppCmd::LoadFinished(Err(e)) => {
+                eprintln!("Loading activities failed due to: {e}");
+                self.state.load_failed();
+                let message = if matches!(e, error::CommandFailed { .. }) {
+                    locale::Key::ErrorActivitiesUnreachable
+                } else {
+                    locale::Key::ErrorLoadFailed
+                };
+                show_alert_dialog(root, &self.locale.text(message, None), &e.to_string());
+            }
+            AppCmd::SaveProgress { done, total } => {
+                self.save_progress = Some((done, total));
+            }
+            AppCmd::Autosave => {
+                self.autosave_pending = false;
+                if self.state.is_dirty() {
+                    self.write_autosave();
+                }
+            }
+            AppCmd::TestRunFinished(Ok(output)) => {
+                let mut args = FluentArgs::new();
+                let message = if output.timed_out() {
+                    args.set("seconds", self.config.run_timeout().as_secs());
+                    locale::Key::TestRunTimedOut
+                } else {
+                    args.set(
+                        "code",
+                        output
+                            .exit_code()
+                            .map_or_else(|| "-".into(), |code| code.to_string()),
+                    );
+                    locale::Key::TestRunFinished
+                };
+                show_alert_dialog(
+                    root,
+                    &self.locale.text(message, Some(&args)),
+                    output.output_tail(),
+                );
+            }
+            AppCmd::SyntaxChecked {
+                event,
+                path,
+                result,
+            } => match result {
+                Err(error::ScriptSyntaxError { error_text, .. }) => {
+                    sender.input(AppMsg::ScriptSyntaxError {
+                        event,
+                        path,
+                        error_text,
+                    });
+                }
+                Err(e) => {
+                    // Without bash the check is skipped rather than blocking the choice
+                    eprintln!("Checking script syntax failed due to: {e}");
+                    sender.input(AppMsg::AssignScript(event, path));
+                }
+                Ok(()) => sender.input(AppMsg::AssignScript(event, path)),
+            },
+            AppCmd::FixPermissionsFinished(Ok(_)) => {}
+            AppCmd::FixPermissionsFinished(Err(e)) => {
+                eprintln!("Fixing script permissions failed due to: {e}");
+                let message = if e.kind() == error::ErrorKind::ReadOnly {
+                    locale::Key::ErrorReadOnlyFilesystem
+                } else {
+                    locale::Key::ErrorMakeExecutableFailed
+                };
+                show_alert_dialog(root, &self.locale.text(message, None), &e.to_string());
+            }
+            AppCmd::EditorClosed(Ok(())) => {}
+            AppCmd::EditorClosed(Err(e)) => {
+                eprintln!("Could not open the script in an editor due to: {e}");
+                show_alert_dialog(
+                    root,
+                    &self.locale.text(locale::Key::ErrorOpenInEditorFailed, None),
+                    &e.to_string(),
+                );
+            }
+            AppCmd::TestRunFinished(Err(e)) => {
+                eprintln!("Test run failed due to: {e}");
+                show_alert_dialog(
+                    root,
+                    &self.locale.text(locale::Key::ErrorTestRunFailed, None),
+                    &e.to_string(),
+                );
+            }
+            AppCmd::ClearLocaleWarning => {
+                self.locale_warning = None;
+            }
+            AppCmd::SaveFinished(result) => {
+                self.save_progress = None;
+                match result {
+                    Ok(_) => {
+                        self.state.save_finished();
+                        self.write_state_file();
+                        self.remove_autosave();
+                        if self.exit_after_save {
+                            sender.input(AppMsg::ForceExit);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Save failed ({:?}) due to: {e}", e.kind());
+                        self.state.save_failed();
+                        self.exit_after_save = false;
+                        if e.kind() == error::ErrorKind::PermissionDenied {
+                            self.offer_elevated_save(e, &sender, root);
+                        } else {
+                            self.show_save_error(&e, root);
+                        }
+                    }
+                }
+                self.settle_save(&sender);
+            }
+            AppCmd::ElevatedSaveFinished(result) => {
+                match result {
+                    Ok(()) => {
+                        self.state.save_finished();
+                        self.remove_autosave();
+                        if self.exit_after_save {
+                            sender.input(AppMsg::ForceExit);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Elevated save failed due to: {e}");
+                        self.state.save_failed();
+                        self.exit_after_save = false;
+                        self.show_save_error(&e, root);
+                    }
+                }
+                self.settle_save(&sender);
+            }
+            AppCmd::SaveSettled(generation) => {
+                if self.state.save_settled(generation) {
+                    sender.input(AppMsg::ReloadNow);
+                }
+            }
         }
     }
-    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, _root: &Self::Root) {
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
         match message {
-            AppMsg::ChooseActivity(index) => {
-                self.selected_activity_index = index;
+            AppMsg::ChooseActivity(position) => {
+                self.state.select_filtered(position);
+            }
+            AppMsg::FilterActivities(query) => {
+                self.state.set_filter(&query);
+            }
+            AppMsg::UndoLastChange => {
+                if self.state.can_modify() {
+                    self.state.undo();
+                }
+            }
+            AppMsg::RedoLastChange => {
+                if self.state.can_modify() {
+                    self.state.redo();
+                }
+            }
+            AppMsg::ChangeLanguage(lang) => {
+                if lang != self.locale.language() {
+                    self.lang = lang;
+                    sender.input(AppMsg::ReloadLocale);
+                }
+            }
+            AppMsg::ReloadLocale => {
+                let locale = match FluentLocale::try_new(&self.lang) {
+                    Ok(locale) => {
+                        self.locale_warning = None;
+                        locale
+                    }
+                    Err(e) => {
+                        eprintln!("Reloading translations failed due to: {e}");
+                        self.locale_warning = Some(e.to_string());
+                        sender.spawn_oneshot_command(|| {
+                            std::thread::sleep(LOCALE_WARNING_DURATION);
+                            AppCmd::ClearLocaleWarning
+                        });
+                        FluentLocale::embedded(&self.lang)
+                    }
+                };
+                gtk::Widget::set_default_direction(text_direction(&locale));
+                self.locale = locale;
+                self.locale_generation += 1;
+            }
+            AppMsg::ToggleMultiSelect(enabled) => {
+                self.state.set_multi_select(enabled);
+            }
+            AppMsg::ToggleSortByName(enabled) => {
+                self.state.set_sort_by_name(enabled);
+            }
+            AppMsg::ToggleSortByStatus(enabled) => {
+                self.state.set_sort_by_status(enabled);
+            }
+            AppMsg::ToggleBatchActivity(index, selected) => {
+                self.state.set_batch_selected(index, selected);
+            }
+            AppMsg::CopyScriptsFrom(index) => {
+                if self.state.can_modify() {
+                    self.state.copy_scripts_from(index);
+                }
+            }
+            AppMsg::ChooseCopyTargets => {
+                if self.state.can_modify() {
+                    self.choose_copy_targets(&sender, root);
+                }
+            }
+            AppMsg::CopyScriptsTo { targets, overwrite } => {
+                if self.state.can_modify() {
+                    self.state.copy_scripts_to(&targets, overwrite);
+                }
             }
             AppMsg::ChooseScript(event) => {
                 self.pending_event = event;
+                self.batch_assign = false;
+                self.script_error = None;
+                self.open_dialog.emit(OpenDialogMsg::Open);
+            }
+            AppMsg::BatchAssignScript(event) => {
+                self.pending_event = event;
+                self.batch_assign = true;
+                self.script_error = None;
                 self.open_dialog.emit(OpenDialogMsg::Open);
             }
+            AppMsg::BatchAssignConfirmed(event, path_buf) => {
+                if self.state.can_modify() {
+                    self.state.set_script_for_all(event, &path_buf);
+                }
+            }
+            AppMsg::BatchAssignCancelled => {}
             AppMsg::ScriptChosen(path_buf) => {
-                self.is_dirty = true;
-                self.activities[self.selected_activity_index]
-                    .set_script(self.pending_event, path_buf);
+                self.script_error = script_choice_error(
+                    &path_buf,
+                    self.config.script_extensions(),
+                    *self.config.allow_any_script(),
+                );
+                if self.script_error.is_some() {
+                    return;
+                }
+                let event = self.pending_event;
+                // Only scripts bash can parse are checked, others may be in any language
+                if path_buf
+                    .extension()
+                    .is_some_and(|ext| ext == "sh" || ext == "bash")
+                {
+                    sender.spawn_oneshot_command(move || {
+                        let result = runner::check_syntax(&path_buf);
+                        AppCmd::SyntaxChecked {
+                            event,
+                            path: path_buf,
+                            result,
+                        }
+                    });
+                } else {
+                    sender.input(AppMsg::AssignScript(event, path_buf));
+                }
+            }
+            AppMsg::ScriptSyntaxError {
+                event,
+                path,
+                error_text,
+            } => {
+                self.warn_syntax_error(event, path, &error_text, &sender, root);
+            }
+            AppMsg::AssignScript(event, path_buf) => match activity::executable_status(&path_buf) {
+                ExecutableStatus::Executable => {
+                    self.assign_chosen(event, path_buf, &sender, root);
+                }
+                ExecutableStatus::NotExecutable => {
+                    self.offer_make_executable(event, path_buf, &sender, root);
+                }
+                ExecutableStatus::Missing => {
+                    self.script_error = Some(locale::Key::ErrorScriptNotAFile);
+                }
+            },
+            AppMsg::MakeExecutable(event, path_buf) => match activity::make_executable(&path_buf) {
+                Ok(()) => self.assign_chosen(event, path_buf, &sender, root),
+                Err(e) => {
+                    eprintln!("Making script executable failed due to: {e}");
+                    let message = if e.kind() == error::ErrorKind::ReadOnly {
+                        locale::Key::ErrorReadOnlyFilesystem
+                    } else {
+                        locale::Key::ErrorMakeExecutableFailed
+                    };
+                    show_alert_dialog(root, &self.locale.text(message, None), &e.to_string());
+                }
+            },
+            AppMsg::ChooseScriptCancel => self.batch_assign = false,
+            AppMsg::ChooseTemplate(event) => {
+                let Some(activity) = self.state.selected_activity() else {
+                    return;
+                };
+                self.pending_event = event;
+                self.template_dialog
+                    .emit(SaveDialogMsg::SaveAs(template::template_filename(
+                        activity.name(),
+                        event,
+                    )));
+            }
+            AppMsg::CreateScript(path) => {
+                let name = self
+                    .state
+                    .selected_activity()
+                    .map(|a| a.name().clone())
+                    .unwrap_or_default();
+                match template::create_script(&path, &name, self.pending_event) {
+                    Ok(()) => self.state.set_script(self.pending_event, path),
+                    Err(e) => {
+                        eprintln!("Creating script failed due to: {e}");
+                        show_alert_dialog(
+                            root,
+                            &self.locale.text(locale::Key::ErrorCreateScriptFailed, None),
+                            &e.to_string(),
+                        );
+                    }
+                }
+            }
+            AppMsg::ChooseTemplateCancel => {}
+            AppMsg::ChooseExport => {
+                self.export_dialog
+                    .emit(SaveDialogMsg::SaveAs(DEFAULT_EXPORT_FILENAME.into()));
+            }
+            AppMsg::Export(path) => {
+                if let Err(e) =
+                    transfer::ScriptExport::from_activities(self.state.activities()).write(&path)
+                {
+                    eprintln!("Export failed due to: {e}");
+                    show_alert_dialog(
+                        root,
+                        &self.locale.text(locale::Key::ErrorExportFailed, None),
+                        &e.to_string(),
+                    );
+                }
+            }
+            AppMsg::ChooseExportCancel => {}
+            AppMsg::ChooseImport => {
+                self.import_dialog.emit(OpenDialogMsg::Open);
+            }
+            AppMsg::Import(path) => match transfer::ScriptExport::read(&path) {
+                Ok(export) => {
+                    let conflicts = export.conflicts(self.state.activities());
+                    self.pending_import = Some(export);
+                    if conflicts == 0 {
+                        sender.input(AppMsg::ImportConflictResolution(
+                            ConflictStrategy::ImportedWins,
+                        ));
+                    } else {
+                        self.confirm_import_conflicts(conflicts, &sender, root);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("Import failed due to: {e}");
+                    show_alert_dialog(
+                        root,
+                        &self.locale.text(locale::Key::ErrorImportFailed, None),
+                        &e.to_string(),
+                    );
+                }
+            },
+            AppMsg::ImportConflictResolution(strategy) => {
+                let Some(export) = self.pending_import.take() else {
+                    return;
+                };
+                let summary = self.state.import(&export, strategy);
+                if !summary.is_complete() {
+                    show_alert_dialog(
+                        root,
+                        &self.locale.text(locale::Key::ImportSummary, None),
+                        &import_summary_detail(&self.locale, &summary),
+                    );
+                }
+            }
+            AppMsg::ChooseImportCancel => {}
+            AppMsg::CleanUpOrphans => {
+                if self.state.can_modify() && !self.orphans.is_empty() {
+                    self.confirm_clean_up_orphans(&sender, root);
+                }
+            }
+            AppMsg::ConfirmCleanUpOrphans => {
+                let orphans = std::mem::take(&mut self.orphans);
+                if let Err(e) = Activity::remove_orphans(
+                    &file_system::RealFileSystem,
+                    self.config.root_path(),
+                    &orphans,
+                ) {
+                    eprintln!("Cleaning up orphaned activities failed due to: {e}");
+                    show_alert_dialog(
+                        root,
+                        &self.locale.text(locale::Key::ErrorCleanUpFailed, None),
+                        &e.to_string(),
+                    );
+                    // Some folders may be gone already, so only offer the rest again
+                    let root_path = self.config.root_path();
+                    self.orphans = orphans
+                        .into_iter()
+                        .filter(|id| root_path.join(id).exists())
+                        .collect();
+                }
+            }
+            AppMsg::RequestDeleteScript(activity_event) => {
+                if self.state.request_delete_script(activity_event) {
+                    self.confirm_delete_script(activity_event, &sender, root);
+                }
+            }
+            AppMsg::DeleteScript => self.state.confirm_delete_script(),
+            AppMsg::CancelDeleteScript => self.state.cancel_delete_script(),
+            AppMsg::RemoveScript(event, path) => {
+                if self.state.can_modify() {
+                    self.state.remove_script(event, &path);
+                }
+            }
+            AppMsg::CopyPathToClipboard(event) => {
+                if let Some(script) = self.state.selected_script(event)
+                    && let Some(display) = gtk::gdk::Display::default()
+                {
+                    display
+                        .clipboard()
+                        .set_text(&script_path_text(Some(script)));
+                }
+            }
+            AppMsg::ShowScriptDetails(event) => {
+                let details =
+                    self.state.selected_script(event).map(
+                        |script| match activity::script_metadata(script) {
+                            Ok(meta) => script_details_text(&self.locale, &meta),
+                            Err(e) => e.to_string(),
+                        },
+                    );
+                self.script_details = details.map(|text| (event, text));
+            }
+            AppMsg::TestRun(event) => {
+                let Some(script) = self.state.selected_script(event).cloned() else {
+                    return;
+                };
+                let timeout = self.config.run_timeout();
+                sender.spawn_oneshot_command(move || {
+                    AppCmd::TestRunFinished(runner::run_script(&script, timeout))
+                });
             }
-            AppMsg::ChooseScriptCancel => {}
-            AppMsg::DeleteScript(activity_event) => {
-                self.is_dirty = true;
-                self.activities[self.selected_activity_index].delete_script(activity_event);
+            AppMsg::FilesChanged => {
+                if self.state.files_changed() {
+                    sender.input(AppMsg::ReloadNow);
+                }
+            }
+            AppMsg::Reload => {
+                if !self.state.is_loading() {
+                    if self.state.is_dirty() {
+                        self.confirm_discard_reload(&sender, root);
+                    } else {
+                        sender.input(AppMsg::ReloadNow);
+                    }
+                }
+            }
+            AppMsg::ReloadNow => {
+                self.state.reload_started();
+                self.busy_text = locale::Key::LoadingData;
+                spawn_load(&self.config, &sender);
+            }
+            AppMsg::RestoreAutosave(snapshot) => {
+                self.state.restore(&snapshot);
+            }
+            AppMsg::DiscardAutosave => {
+                self.remove_autosave();
+            }
+            AppMsg::ForceExit => {
+                if self.state.is_dirty() {
+                    self.write_autosave();
+                } else {
+                    self.remove_autosave();
+                }
+                self.quit(root);
             }
-            AppMsg::Exit => {
-                relm4::main_application().quit();
+            AppMsg::DiscardAndExit => {
+                self.remove_autosave();
+                self.quit(root);
             }
             AppMsg::Help => {
                 if let Err(e) = open::that(KAS_HELP_URL) {
                     eprintln!("Could not show help due to: {e}");
                 };
             }
+            AppMsg::OpenScriptInEditor(event) => {
+                let Some(script) = self.state.selected_script(event) else {
+                    return;
+                };
+                let script = script.clone();
+                sender.spawn_oneshot_command(move || {
+                    AppCmd::EditorClosed(runner::open_in_editor(&script))
+                });
+            }
+            AppMsg::About => self.show_about_dialog(root),
+            AppMsg::OpenRoot => {
+                if let Err(e) = open::that(self.config.root_path()) {
+                    eprintln!("Could not open the activities folder due to: {e}");
+                };
+            }
+            AppMsg::RequestExit => {
+                if self.state.can_save() {
+                    self.confirm_exit(&sender, root);
+                } else {
+                    sender.input(AppMsg::ForceExit);
+                }
+            }
+            AppMsg::ExitChosen(choice) => match self.state.exit_action(choice) {
+                ExitAction::Quit => sender.input(AppMsg::ForceExit),
+                ExitAction::Stay => {}
+                ExitAction::DiscardAndQuit => sender.input(AppMsg::DiscardAndExit),
+                ExitAction::SaveAndQuit => sender.input(AppMsg::SaveAndExit),
+            },
+            AppMsg::SaveAndExit => {
+                if !self.state.can_save() {
+                    return;
+                }
+                self.exit_after_save = true;
+                self.check_activity_manager(&sender, root, session::activity_manager_running);
+            }
             AppMsg::Save => {
-                self.is_loading = true;
-                let activities = self.activities.clone();
-                let config = self.config.clone();
+                if !self.state.can_save() {
+                    return;
+                }
+                // A quit that was cancelled partway through saving shouldn't carry over
+                self.exit_after_save = false;
+                self.check_activity_manager(&sender, root, session::activity_manager_running);
+            }
+            AppMsg::ConfirmActivityManager => {
+                self.check_root(&sender, root);
+            }
+            AppMsg::ConfirmSave => {
+                self.check_file_removals(&sender, root);
+            }
+            AppMsg::ConfirmFileRemovals => {
+                self.check_overwrites(&sender, root);
+            }
+            AppMsg::ConfirmOverwrites => {
+                self.start_save(&sender);
+            }
+            AppMsg::BrokenScriptWarning(broken) => {
+                let details: Vec<String> = broken.iter().map(ToString::to_string).collect();
+                // Not modal, since the missing scripts can be fixed from the main window
+                gtk::AlertDialog::builder()
+                    .modal(false)
+                    .message(self.locale.text(locale::Key::BrokenLinks, None))
+                    .detail(details.join("\n"))
+                    .build()
+                    .show(Some(root));
+            }
+            AppMsg::FixPermissions(paths) => {
+                sender.spawn_oneshot_command(move || {
+                    AppCmd::FixPermissionsFinished(activity::fix_permissions(&paths))
+                });
+            }
+            AppMsg::SaveElevated {
+                command,
+                mapping_path,
+            } => {
+                let mapping = apply::Mapping::from_activities(self.state.activities());
+                self.state.save_started();
+                self.busy_text = locale::Key::SavingData;
                 sender.spawn_oneshot_command(move || {
-                    AppCmd::SaveFinished(Activity::save_activities(
-                        config.root_path(),
-                        config.script_filename(),
-                        &activities,
-                    ))
-                })
+                    let result = mapping.write_json(&mapping_path).and_then(|()| {
+                        eprintln!("Running: {}", command_text(&command));
+                        apply::run_elevated(&command)
+                    });
+                    if let Err(e) = std::fs::remove_file(&mapping_path) {
+                        eprintln!("Removing mapping file failed due to: {e}");
+                    }
+                    AppCmd::ElevatedSaveFinished(result)
+                });
+            }
+        }
+        if self.state.is_dirty() && !self.autosave_pending {
+            self.autosave_pending = true;
+            sender.spawn_oneshot_command(|| {
+                std::thread::sleep(AUTOSAVE_DELAY);
+                AppCmd::Autosave
+            });
+        }
+    }
+}
+
+impl AppModel {
+    fn start_save(&mut self, sender: &ComponentSender<Self>) {
+        self.state.save_started();
+        self.busy_text = locale::Key::SavingData;
+        let activities = self.state.activities().to_vec();
+        self.save_progress = Some((0, activities.len()));
+        let config = self.config.clone();
+        sender.spawn_command(move |out| {
+            let result = Activity::save_activities_with_progress(
+                &file_system::RealFileSystem,
+                config.root_path(),
+                config.script_filename(),
+                &activities,
+                *config.backup(),
+                *config.link_mode(),
+                |done, total| out.emit(AppCmd::SaveProgress { done, total }),
+            );
+            out.emit(AppCmd::SaveFinished(result));
+        })
+    }
+    /// Keeps the watcher from reloading on the save's own changes, which it reports once
+    /// they have settled.
+    fn settle_save(&self, sender: &ComponentSender<Self>) {
+        let generation = self.state.save_generation();
+        sender.spawn_oneshot_command(move || {
+            std::thread::sleep(watcher::SETTLE_DELAY * 2);
+            AppCmd::SaveSettled(generation)
+        });
+    }
+    /// Advises against saving while kactivitymanagerd is stopped, as it may miss new links.
+    fn check_activity_manager(
+        &mut self,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+        is_running: impl FnOnce() -> bool,
+    ) {
+        if is_running() {
+            self.check_root(sender, root);
+            return;
+        }
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(self.locale.text(locale::Key::ActivityManagerStopped, None))
+            .detail(
+                self.locale
+                    .text(locale::Key::ActivityManagerStoppedDetail, None),
+            )
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::Continue, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(1);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::ConfirmActivityManager);
+            }
+        });
+    }
+    fn check_root(&mut self, sender: &ComponentSender<Self>, root: &gtk::Window) {
+        let root_path = self.config.root_path();
+        if *root_path == config::default_root_path()
+            || activity::looks_like_kas_root(root_path, self.state.activities())
+        {
+            self.check_file_removals(sender, root);
+        } else {
+            self.confirm_unexpected_root(sender, root);
+        }
+    }
+    fn check_file_removals(&mut self, sender: &ComponentSender<Self>, root: &gtk::Window) {
+        let removals = Activity::planned_file_removals(
+            &file_system::RealFileSystem,
+            self.config.root_path(),
+            self.config.script_filename(),
+            self.state.activities(),
+            *self.config.backup(),
+            *self.config.link_mode(),
+        );
+        if removals.is_empty() {
+            self.check_overwrites(sender, root);
+            return;
+        }
+        let (copies, files): (Vec<_>, Vec<_>) = removals
+            .iter()
+            .partition(|(_, entry)| *entry == activity::ManagedEntry::OwnedCopy);
+        let lines = |removals: Vec<&(PathBuf, activity::ManagedEntry)>| {
+            removals
+                .iter()
+                .map(|(path, _)| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let (files, copies) = (lines(files), lines(copies));
+        let copies_text = self.locale.text(locale::Key::ConfirmReplaceCopies, None);
+        let (message, detail) = match (files.is_empty(), copies.is_empty()) {
+            (true, _) => (copies_text, copies),
+            (false, true) => (
+                self.locale.text(locale::Key::ConfirmRemoveFiles, None),
+                files,
+            ),
+            (false, false) => (
+                self.locale.text(locale::Key::ConfirmRemoveFiles, None),
+                format!("{files}\n\n{copies_text}\n{copies}"),
+            ),
+        };
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(message)
+            .detail(detail)
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::Delete, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(0);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::ConfirmFileRemovals);
+            }
+        });
+    }
+    fn check_overwrites(&mut self, sender: &ComponentSender<Self>, root: &gtk::Window) {
+        let overwrites = Activity::planned_overwrites(
+            &file_system::RealFileSystem,
+            self.config.root_path(),
+            self.config.script_filename(),
+            self.state.activities(),
+            self.state.saved_activities(),
+        );
+        if overwrites.is_empty() {
+            self.start_save(sender);
+            return;
+        }
+        let lines: Vec<String> = overwrites
+            .iter()
+            .map(|(link, target)| format!("{} → {}", link.display(), target.display()))
+            .collect();
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(self.locale.text(locale::Key::ConfirmOverwriteLinks, None))
+            .detail(lines.join("\n"))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::Replace, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(0);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::ConfirmOverwrites);
+            }
+        });
+    }
+    fn confirm_unexpected_root(&self, sender: &ComponentSender<Self>, root: &gtk::Window) {
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(self.locale.text(locale::Key::UnexpectedRoot, None))
+            .detail(self.config.root_path().to_string_lossy())
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::Continue, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(0);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::ConfirmSave);
+            }
+        });
+    }
+    fn offer_recovery(
+        &self,
+        snapshot: recovery::Snapshot,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+    ) {
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(self.locale.text(locale::Key::RecoverTitle, None))
+            .detail(self.locale.text(locale::Key::RecoverDetail, None))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Discard, None),
+            &self.locale.text(locale::Key::Restore, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(1);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::RestoreAutosave(snapshot));
+            } else {
+                sender.input(AppMsg::DiscardAutosave);
+            }
+        });
+    }
+    fn quit(&mut self, root: &gtk::Window) {
+        if !root.is_maximized()
+            && let Some(path) = geometry::geometry_path()
+        {
+            let (width, height) = root.default_size();
+            if let Err(e) = (geometry::WindowGeometry { width, height }).write(&path) {
+                eprintln!("Saving the window size failed due to: {e}");
+            }
+        }
+        self.write_state_file();
+        self.watcher = None;
+        self.locale_watcher = None;
+        relm4::main_application().quit();
+    }
+    fn write_state_file(&self) {
+        let (Some(path), Some(activity)) = (state::state_path(), self.state.selected_activity())
+        else {
+            return;
+        };
+        let state_file = state::StateFile {
+            selected_activity_id: activity.id().clone(),
+        };
+        if let Err(e) = state_file.save(&path) {
+            eprintln!("Saving the selected activity failed due to: {e}");
+        }
+    }
+    fn confirm_import_conflicts(
+        &self,
+        conflicts: usize,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+    ) {
+        let mut args = FluentArgs::new();
+        args.set("count", conflicts);
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(self.locale.text(locale::Key::ImportConflicts, Some(&args)))
+            .detail(self.locale.text(locale::Key::ImportConflictsDetail, None))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::KeepCurrentScripts, None),
+            &self.locale.text(locale::Key::UseImportedScripts, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(2);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            let strategy = match choice {
+                Ok(1) => ConflictStrategy::KeepExisting,
+                Ok(2) => ConflictStrategy::ImportedWins,
+                _ => return,
+            };
+            sender.input(AppMsg::ImportConflictResolution(strategy));
+        });
+    }
+    /// Not modal, so the rest of the window stays usable, and the script is only assigned
+    /// once it can run.
+    fn offer_make_executable(
+        &self,
+        event: ActivityEvent,
+        path: PathBuf,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+    ) {
+        let mut args = FluentArgs::new();
+        args.set("name", script_path_text(Some(&path)));
+        let dialog = gtk::AlertDialog::builder()
+            .modal(false)
+            .message(self.locale.text(locale::Key::NotExecutable, Some(&args)))
+            .detail(self.locale.text(locale::Key::NotExecutableDetail, None))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::MakeExecutable, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(1);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::MakeExecutable(event, path));
+            }
+        });
+    }
+    fn choose_copy_targets(&self, sender: &ComponentSender<Self>, root: &gtk::Window) {
+        let Some(source) = self.state.selected_activity() else {
+            return;
+        };
+        let title = self.locale.text(locale::Key::CopyScriptsTo, None);
+        let window = gtk::Window::builder()
+            .title(title.trim_end_matches('…'))
+            .transient_for(root)
+            .modal(true)
+            .default_width(320)
+            .build();
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 8);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+        let mut args = FluentArgs::new();
+        args.set("name", source.name().clone());
+        let heading = gtk::Label::new(Some(
+            &self
+                .locale
+                .text(locale::Key::CopyScriptsToDetail, Some(&args)),
+        ));
+        heading.set_wrap(true);
+        heading.set_xalign(0.0);
+        content.append(&heading);
+        let selected = self.state.selected_activity_index();
+        let checks: Vec<(usize, gtk::CheckButton)> = self
+            .state
+            .activities()
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != selected)
+            .map(|(index, activity)| {
+                let check = gtk::CheckButton::with_label(&activity_label(&self.locale, activity));
+                (index, check)
+            })
+            .collect();
+        let check_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        for (_, check) in &checks {
+            check_box.append(check);
+        }
+        let scroller = gtk::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .max_content_height(240)
+            .propagate_natural_height(true)
+            .child(&check_box)
+            .build();
+        content.append(&scroller);
+        let overwrite_check = gtk::CheckButton::with_label(
+            &self
+                .locale
+                .text(locale::Key::OverwriteExistingScripts, None),
+        );
+        content.append(&overwrite_check);
+        let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        buttons.set_halign(gtk::Align::End);
+        let cancel_button = gtk::Button::with_label(&self.locale.text(locale::Key::Cancel, None));
+        let copy_button = gtk::Button::with_label(&self.locale.text(locale::Key::Copy, None));
+        copy_button.add_css_class("suggested-action");
+        copy_button.set_sensitive(false);
+        buttons.append(&cancel_button);
+        buttons.append(&copy_button);
+        content.append(&buttons);
+        window.set_child(Some(&content));
+
+        for (_, check) in &checks {
+            let copy_button = copy_button.clone();
+            let all: Vec<gtk::CheckButton> = checks.iter().map(|(_, c)| c.clone()).collect();
+            check.connect_toggled(move |_| {
+                copy_button.set_sensitive(all.iter().any(|check| check.is_active()));
+            });
+        }
+        let window_clone = window.clone();
+        cancel_button.connect_clicked(move |_| window_clone.close());
+        let window_clone = window.clone();
+        let sender = sender.clone();
+        copy_button.connect_clicked(move |_| {
+            let targets = checks
+                .iter()
+                .filter(|(_, check)| check.is_active())
+                .map(|(index, _)| *index)
+                .collect();
+            sender.input(AppMsg::CopyScriptsTo {
+                targets,
+                overwrite: overwrite_check.is_active(),
+            });
+            window_clone.close();
+        });
+        window.present();
+    }
+    fn warn_syntax_error(
+        &self,
+        event: ActivityEvent,
+        path: PathBuf,
+        error_text: &str,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+    ) {
+        let mut args = FluentArgs::new();
+        args.set("name", script_path_text(Some(&path)));
+        let dialog = gtk::AlertDialog::builder()
+            .modal(false)
+            .message(
+                self.locale
+                    .text(locale::Key::ScriptSyntaxError, Some(&args)),
+            )
+            .detail(format!(
+                "{}\n\n{error_text}",
+                self.locale.text(locale::Key::ScriptSyntaxErrorDetail, None)
+            ))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::AssignAnyway, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(0);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::AssignScript(event, path));
+            }
+        });
+    }
+    fn offer_fix_permissions(
+        &self,
+        paths: Vec<PathBuf>,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+    ) {
+        let names: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+        let dialog = gtk::AlertDialog::builder()
+            .modal(false)
+            .message(self.locale.text(locale::Key::NotExecutableScripts, None))
+            .detail(format!(
+                "{}\n\n{}",
+                self.locale.text(locale::Key::NotExecutableDetail, None),
+                names.join("\n")
+            ))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::FixPermissions, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(1);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::FixPermissions(paths));
+            }
+        });
+    }
+    fn confirm_exit(&self, sender: &ComponentSender<Self>, root: &gtk::Window) {
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(self.locale.text(locale::Key::UnsavedChanges, None))
+            .detail(self.locale.text(locale::Key::UnsavedChangesDetail, None))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::Discard, None),
+            &self.locale.text(locale::Key::SaveAndQuit, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(2);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            let choice = match choice {
+                Ok(0) => Some(ExitChoice::Cancel),
+                Ok(1) => Some(ExitChoice::Discard),
+                Ok(2) => Some(ExitChoice::Save),
+                _ => None,
+            };
+            sender.input(AppMsg::ExitChosen(choice));
+        });
+    }
+    fn assign_chosen(
+        &mut self,
+        event: ActivityEvent,
+        path: PathBuf,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+    ) {
+        if std::mem::take(&mut self.batch_assign) {
+            self.confirm_batch_assign(event, path, sender, root);
+        } else {
+            self.state.add_script(event, path);
+        }
+    }
+    fn confirm_batch_assign(
+        &self,
+        event: ActivityEvent,
+        path: PathBuf,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+    ) {
+        let count = self.state.assign_all_count(event, &path);
+        if count == 0 {
+            return;
+        }
+        let mut args = FluentArgs::new();
+        args.set("count", count);
+        args.set("event", self.locale.text(event.as_key(), None));
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(
+                self.locale
+                    .text(locale::Key::ConfirmAssignToAll, Some(&args)),
+            )
+            .detail(script_path_text(Some(&path)))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::AssignToAll, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(1);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            sender.input(if matches!(choice, Ok(1)) {
+                AppMsg::BatchAssignConfirmed(event, path)
+            } else {
+                AppMsg::BatchAssignCancelled
+            });
+        });
+    }
+    fn confirm_delete_script(
+        &self,
+        event: ActivityEvent,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+    ) {
+        let mut args = FluentArgs::new();
+        args.set("event", self.locale.text(event.as_key(), None));
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(
+                self.locale
+                    .text(locale::Key::ConfirmDeleteScript, Some(&args)),
+            )
+            .detail(
+                self.locale
+                    .text(locale::Key::ConfirmDeleteScriptDetail, None),
+            )
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::Delete, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(0);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::DeleteScript);
+            } else {
+                sender.input(AppMsg::CancelDeleteScript);
+            }
+        });
+    }
+    fn confirm_discard_reload(&self, sender: &ComponentSender<Self>, root: &gtk::Window) {
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(self.locale.text(locale::Key::ConfirmDiscardReload, None))
+            .detail(self.locale.text(locale::Key::UnsavedChangesDetail, None))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::Discard, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(0);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::ReloadNow);
+            }
+        });
+    }
+    fn confirm_clean_up_orphans(&self, sender: &ComponentSender<Self>, root: &gtk::Window) {
+        let mut args = FluentArgs::new();
+        args.set("count", self.orphans.len());
+        let folders: Vec<String> = self
+            .orphans
+            .iter()
+            .map(|id| self.config.root_path().join(id).display().to_string())
+            .collect();
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(
+                self.locale
+                    .text(locale::Key::ConfirmCleanUpOrphans, Some(&args)),
+            )
+            .detail(format!(
+                "{}\n\n{}",
+                self.locale
+                    .text(locale::Key::ConfirmCleanUpOrphansDetail, None),
+                folders.join("\n")
+            ))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::Delete, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(0);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::ConfirmCleanUpOrphans);
+            }
+        });
+    }
+    fn show_save_error(&self, error: &error::Application, root: &gtk::Window) {
+        let mut details = error_details(error);
+        if let error::SaveDataError { step, .. } = error {
+            let outcome = match step {
+                error::SaveStep::Staging => locale::Key::ErrorSaveNothingChanged,
+                error::SaveStep::RolledBack => locale::Key::ErrorSaveUndone,
+                error::SaveStep::Incomplete => locale::Key::ErrorSaveIncomplete,
+            };
+            details = format!("{}\n\n{details}", self.locale.text(outcome, None));
+        }
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(self.locale.text(locale::Key::ErrorSaveFailed, None))
+            .detail(&details)
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Close, None),
+            &self.locale.text(locale::Key::CopyDetails, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(0);
+        let root_clone = root.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                root_clone.display().clipboard().set_text(&details);
+            }
+        });
+    }
+    fn show_about_dialog(&self, root: &gtk::Window) {
+        let authors: Vec<&str> = env!("CARGO_PKG_AUTHORS").split(':').collect();
+        gtk::AboutDialog::builder()
+            .modal(true)
+            .transient_for(root)
+            .program_name(self.locale.text(locale::Key::Title, None))
+            .version(env!("CARGO_PKG_VERSION"))
+            .comments(self.locale.text(locale::Key::AboutComments, None))
+            .authors(authors)
+            .license_type(gtk::License::MitX11)
+            .website(KAS_HELP_URL)
+            .website_label(self.locale.text(locale::Key::Website, None))
+            .logo_icon_name("preferences-system")
+            .build()
+            .present();
+    }
+    fn write_autosave(&self) {
+        if let Some(path) = &self.autosave_path
+            && let Err(e) = recovery::Snapshot::from_activities(self.state.activities()).write(path)
+        {
+            eprintln!("Autosave failed due to: {e}");
+        }
+    }
+    fn offer_elevated_save(
+        &self,
+        error: error::Application,
+        sender: &ComponentSender<Self>,
+        root: &gtk::Window,
+    ) {
+        let program = match std::env::current_exe() {
+            Ok(program) => program,
+            Err(e) => {
+                eprintln!("Finding the kas-selector executable failed due to: {e}");
+                self.show_save_error(&error, root);
+                return;
+            }
+        };
+        // The user's runtime directory isn't shared with other users, unlike /tmp
+        let mapping_path = std::env::var_os("XDG_RUNTIME_DIR")
+            .map_or_else(std::env::temp_dir, PathBuf::from)
+            .join(format!("kas-selector-{}.json", std::process::id()));
+        let command = apply::elevated_command(
+            &self.config,
+            &program,
+            &mapping_path,
+            std::env::var_os("DBUS_SESSION_BUS_ADDRESS").as_deref(),
+        );
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(self.locale.text(locale::Key::SavePermissionDenied, None))
+            .detail(format!(
+                "{}\n\n{}",
+                self.locale
+                    .text(locale::Key::SavePermissionDeniedDetail, None),
+                command_text(&command)
+            ))
+            .build();
+        dialog.set_buttons(&[
+            &self.locale.text(locale::Key::Cancel, None),
+            &self.locale.text(locale::Key::RetryAsAdmin, None),
+        ]);
+        dialog.set_cancel_button(0);
+        dialog.set_default_button(0);
+        let sender = sender.clone();
+        dialog.choose(Some(root), gtk::gio::Cancellable::NONE, move |choice| {
+            if matches!(choice, Ok(1)) {
+                sender.input(AppMsg::SaveElevated {
+                    command,
+                    mapping_path,
+                });
+            }
+        });
+    }
+    fn remove_autosave(&self) {
+        if let Some(path) = &self.autosave_path
+            && let Err(e) = recovery::Snapshot::remove(path)
+        {
+            eprintln!("Removing autosave failed due to: {e}");
+        }
+    }
+}
+
+fn spawn_load(config: &Config, sender: &ComponentSender<AppModel>) {
+    let config = config.clone();
+    sender.spawn_oneshot_command(move || {
+        AppCmd::LoadFinished(Activity::from_env(
+            config.root_path(),
+            config.script_filename(),
+        ))
+    });
+}
+
+fn script_filters(locale: &FluentLocale, extensions: &AllowedExtensions) -> Vec<gtk::FileFilter> {
+    let scripts = gtk::FileFilter::new();
+    scripts.set_name(Some(&locale.text(locale::Key::FilterShellScripts, None)));
+    for extension in extensions.iter() {
+        scripts.add_pattern(&format!("*.{extension}"));
+    }
+    scripts.add_mime_type("text/x-shellscript");
+    let all = gtk::FileFilter::new();
+    all.set_name(Some(&locale.text(locale::Key::FilterAllFiles, None)));
+    all.add_pattern("*");
+    vec![scripts, all]
+}
+
+fn script_choice_error(
+    path: &Path,
+    extensions: &AllowedExtensions,
+    allow_any_script: bool,
+) -> Option<locale::Key> {
+    if !path.is_file() {
+        return Some(locale::Key::ErrorScriptNotAFile);
+    }
+    let is_shell_script = path.extension().is_some_and(|ext| extensions.contains(ext));
+    (!allow_any_script && !is_shell_script).then_some(locale::Key::ErrorScriptNotShell)
+}
+
+fn activity_item_factory(icons: &gtk::StringList) -> gtk::SignalListItemFactory {
+    let factory = gtk::SignalListItemFactory::new();
+    factory.connect_setup(|_, item| {
+        let Some(item) = item.downcast_ref::<gtk::ListItem>() else {
+            return;
+        };
+        let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        row.append(&gtk::Image::new());
+        row.append(&gtk::Label::new(None));
+        item.set_child(Some(&row));
+    });
+    let icons = icons.clone();
+    factory.connect_bind(move |_, item| {
+        let Some(item) = item.downcast_ref::<gtk::ListItem>() else {
+            return;
+        };
+        let Some(row) = item.child() else {
+            return;
+        };
+        let image = row.first_child().and_downcast::<gtk::Image>();
+        let label = row.last_child().and_downcast::<gtk::Label>();
+        if let Some(label) = label {
+            let text = item.item().and_downcast::<gtk::StringObject>();
+            label.set_text(&text.map(|s| s.string()).unwrap_or_default());
+        }
+        if let Some(image) = image {
+            let icon = icons.string(item.position()).unwrap_or_default();
+            set_activity_icon(&image, &icon);
+        }
+    });
+    factory
+}
+
+fn set_activity_icon(image: &gtk::Image, icon: &str) {
+    if Path::new(icon).is_absolute() {
+        image.set_from_file(Some(icon));
+        return;
+    }
+    let themed = !icon.is_empty()
+        && gtk::gdk::Display::default()
+            .is_some_and(|display| gtk::IconTheme::for_display(&display).has_icon(icon));
+    image.set_icon_name(Some(if themed { icon } else { FALLBACK_ACTIVITY_ICON }));
+}
+
+fn sync_string_list(list: &gtk::StringList, strings: &[&str]) {
+    let current: Vec<String> = (0..list.n_items())
+        .filter_map(|i| list.string(i))
+        .map(|s| s.to_string())
+        .collect();
+    if current != strings {
+        list.splice(0, list.n_items(), strings);
+    }
+}
+
+fn activity_label(locale: &FluentLocale, activity: &Activity) -> String {
+    let mut args = FluentArgs::new();
+    args.set("name", activity.name().as_str());
+    args.set("count", activity.script_count());
+    args.set("total", ActivityEvent::iter().count());
+    format!(
+        "{}{}",
+        state_marker(activity.state()),
+        locale.text(locale::Key::ActivityScriptCount, Some(&args))
+    )
+}
+
+const fn state_marker(state: ActivityState) -> &'static str {
+    match state {
+        ActivityState::Current => "★ ",
+        ActivityState::Running | ActivityState::Starting => "● ",
+        ActivityState::Stopping | ActivityState::Stopped | ActivityState::Unknown => "",
+    }
+}
+
+fn import_summary_detail(locale: &FluentLocale, summary: &transfer::ImportSummary) -> String {
+    let mut sections = Vec::new();
+    if !summary.unmatched_activities().is_empty() {
+        sections.push(format!(
+            "{}\n{}",
+            locale.text(locale::Key::ImportSkipped, None),
+            summary.unmatched_activities().join("\n")
+        ));
+    }
+    if !summary.missing_scripts().is_empty() {
+        let paths: Vec<String> = summary
+            .missing_scripts()
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect();
+        sections.push(format!(
+            "{}\n{}",
+            locale.text(locale::Key::ImportMissingScripts, None),
+            paths.join("\n")
+        ));
+    }
+    sections.join("\n\n")
+}
+
+fn script_details_text(locale: &FluentLocale, meta: &activity::ScriptMetadata) -> String {
+    let yes_no = |value: bool| {
+        locale.text(
+            if value {
+                locale::Key::Yes
+            } else {
+                locale::Key::No
+            },
+            None,
+        )
+    };
+    let mut lines = vec![
+        (
+            locale::Key::DetailsTarget,
+            meta.target().display().to_string(),
+        ),
+        (
+            locale::Key::DetailsTargetExists,
+            yes_no(*meta.target_exists()),
+        ),
+    ];
+    if let Some(bytes) = meta.size() {
+        let mut args = FluentArgs::new();
+        args.set("bytes", *bytes);
+        lines.push((
+            locale::Key::DetailsSize,
+            locale.text(locale::Key::DetailsBytes, Some(&args)),
+        ));
+    }
+    if let Some(modified) = meta
+        .modified()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .and_then(|since| gtk::glib::DateTime::from_unix_local(since.as_secs() as i64).ok())
+        .and_then(|time| time.format("%c").ok())
+    {
+        lines.push((locale::Key::DetailsModified, modified.to_string()));
+    }
+    if let Some(executable) = meta.executable() {
+        lines.push((locale::Key::DetailsExecutable, yes_no(*executable)));
+    }
+    lines
+        .into_iter()
+        .map(|(key, value)| format!("{}: {value}", locale.text(key, None)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn error_details(error: &dyn std::error::Error) -> String {
+    let mut lines = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(cause) = source {
+        lines.push(cause.to_string());
+        source = cause.source();
+    }
+    lines.join("\n")
+}
+
+fn show_alert_dialog(root: &gtk::Window, message: &str, detail: &str) {
+    gtk::AlertDialog::builder()
+        .modal(true)
+        .message(message)
+        .detail(detail)
+        .build()
+        .show(Some(root));
+}
+
+/// Text fields handle these keys first, so undoing typing still works as usual.
+fn edit_shortcuts(sender: &ComponentSender<AppModel>) -> gtk::ShortcutController {
+    let controller = gtk::ShortcutController::new();
+    for (accelerator, undo) in [
+        ("<Control>z", true),
+        ("<Control>y", false),
+        ("<Control><Shift>z", false),
+    ] {
+        let sender = sender.clone();
+        let action = gtk::CallbackAction::new(move |_, _| {
+            sender.input(if undo {
+                AppMsg::UndoLastChange
+            } else {
+                AppMsg::RedoLastChange
+            });
+            gtk::glib::Propagation::Stop
+        });
+        controller.add_shortcut(gtk::Shortcut::new(
+            gtk::ShortcutTrigger::parse_string(accelerator),
+            Some(action),
+        ));
+    }
+    controller
+}
+
+impl Translation {
+    fn apply(&self, locale: &FluentLocale) {
+        match self {
+            Self::Label(label, key) => label.set_label(&locale.text(*key, None)),
+            Self::ButtonLabel(button, key) => button.set_label(&locale.text(*key, None)),
+            Self::CheckLabel(check, key) => check.set_label(Some(&locale.text(*key, None))),
+            Self::Tooltip(widget, key) => {
+                let text = locale.text(*key, None);
+                widget.set_tooltip(&text);
+                // Icon-only buttons have no label for screen readers to announce otherwise
+                widget.update_property(&[gtk::accessible::Property::Label(&text)]);
             }
-            AppMsg::CloseSaveErrorDialog => {
-                self.save_error_dialog_visible = false;
+            Self::Placeholder(entry, key) => {
+                let text = locale.text(*key, None);
+                entry.set_placeholder_text(Some(&text));
+                entry.update_property(&[gtk::accessible::Property::Label(&text)]);
             }
         }
     }
 }
 
+impl EventRow {
+    fn rebuild_scripts(
+        &mut self,
+        event: ActivityEvent,
+        scripts: &[PathBuf],
+        locale: &FluentLocale,
+        sender: &ComponentSender<AppModel>,
+    ) {
+        for entry in self.script_entries.drain(..) {
+            self.scripts_box.remove(&entry);
+        }
+        for script in scripts {
+            let label = gtk::Label::new(Some(&script_path_text(Some(script))));
+            label.set_hexpand(true);
+            label.set_halign(gtk::Align::Start);
+            let remove_button = gtk::Button::from_icon_name("list-remove");
+            remove_button.add_css_class("flat");
+            let tooltip = locale.text(locale::Key::RemoveScript, None);
+            remove_button.set_tooltip(&tooltip);
+            remove_button.update_property(&[gtk::accessible::Property::Label(&tooltip)]);
+            let sender = sender.clone();
+            let script = script.clone();
+            remove_button.connect_clicked(move |_| {
+                sender.input(AppMsg::RemoveScript(event, script.clone()));
+            });
+            let entry = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            entry.append(&label);
+            entry.append(&remove_button);
+            self.scripts_box.append(&entry);
+            self.script_entries.push(entry);
+        }
+        self.shown_scripts = scripts.to_vec();
+    }
+}
+
+fn window_title(locale: &FluentLocale, state: &AppState) -> String {
+    let total = state.activities().len();
+    if total == 0 {
+        return locale.text(locale::Key::Title, None);
+    }
+    let mut args = FluentArgs::new();
+    args.set("configured", state.configured_count());
+    args.set("total", total);
+    locale.text(locale::Key::TitleSummary, Some(&args))
+}
+
+fn command_text(command: &[OsString]) -> String {
+    command
+        .iter()
+        .map(|arg| {
+            let arg = arg.to_string_lossy();
+            if arg.contains(char::is_whitespace) || arg.contains(['"', '\'']) {
+                format!("{arg:?}")
+            } else {
+                arg.into_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Lossy but never empty for an assigned script, so paths that aren't UTF-8 stay visible.
+fn script_path_text(script: Option<&PathBuf>) -> String {
+    script.map_or_else(String::new, |path| path.display().to_string())
+}
+
+fn text_direction(locale: &FluentLocale) -> gtk::TextDirection {
+    if locale.is_rtl() {
+        gtk::TextDirection::Rtl
+    } else {
+        gtk::TextDirection::Ltr
+    }
+}
+
 fn get_env_lang() -> String {
     for var in ["LANGUAGE", "LC_MESSAGES", "LANG"] {
         if let Ok(val) = std::env::var(var)
@@ -356,23 +2813,143 @@ fn get_env_lang() -> String {
     DEFAULT_LOCALE.into()
 }
 
-#[allow(clippy::expect_used)]
 fn main() {
-    let root_path = std::env::var("KAS_ROOT").map_or_else(
-        |_| PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(DEFAULT_KAS_PATH),
-        PathBuf::from,
-    );
-    let script_filename = std::env::var("KAS_SCRIPT_NAME")
-        .unwrap_or_else(|_| DEFAULT_SCRIPT_FILENAME.into())
-        .parse()
-        .expect("Script filename validation check.");
-    let config = Config::new(root_path, script_filename);
-    let activities = Activity::from_env(config.root_path(), config.script_filename())
-        .expect("Loading activity data.");
+    let args = cli::Args::parse();
+    let opens_window = args.opens_window(cli::display_available());
     let lang = get_env_lang();
+    let locale = FluentLocale::try_new(&lang).unwrap_or_else(|e| {
+        eprintln!("Loading translations failed due to: {e}");
+        FluentLocale::embedded(&lang)
+    });
+    let config = match startup::load_config(&args) {
+        Ok(config) => config,
+        Err(e) => {
+            if opens_window {
+                report_startup_error(&locale, &e);
+            } else {
+                eprintln!("{e}");
+            }
+            std::process::exit(1);
+        }
+    };
+    if let Some(code) = cli::run_headless(&config, &args, cli::display_available()) {
+        std::process::exit(code);
+    }
+    if let Some(root) = startup::missing_root(&config)
+        && confirm_create_root(&locale, root)
+        && let Err(e) = startup::create_root(root)
+    {
+        report_startup_error(&locale, &e);
+        std::process::exit(1);
+    }
     relm4::RelmApp::new("kas-selector").run::<AppModel>(AppInit {
         config,
-        activities,
         lang,
+        locale,
     });
 }
+
+/// Translations that fail to load fall back to the embedded ones, so there is always a locale.
+fn report_startup_error(locale: &FluentLocale, error: &error::Application) {
+    eprintln!("{error}");
+    choose_before_start(
+        &locale.text(locale::Key::ErrorStartupFailed, None),
+        &error.to_string(),
+        &[&locale.text(locale::Key::Close, None)],
+    );
+}
+
+fn confirm_create_root(locale: &FluentLocale, root: &Path) -> bool {
+    let mut args = FluentArgs::new();
+    args.set("path", root.to_string_lossy().to_string());
+    let choice = choose_before_start(
+        &locale.text(locale::Key::CreateRoot, None),
+        &locale.text(locale::Key::CreateRootDetail, Some(&args)),
+        &[
+            &locale.text(locale::Key::Cancel, None),
+            &locale.text(locale::Key::CreateFolder, None),
+        ],
+    );
+    choice == Some(1)
+}
+
+/// Returns `None` when GTK can't start, such as without a display.
+fn choose_before_start(message: &str, detail: &str, buttons: &[&str]) -> Option<i32> {
+    gtk::init().ok()?;
+    let dialog = gtk::AlertDialog::builder()
+        .modal(true)
+        .message(message)
+        .detail(detail)
+        .build();
+    dialog.set_buttons(buttons);
+    dialog.set_cancel_button(0);
+    dialog.set_default_button(0);
+    let main_loop = gtk::glib::MainLoop::new(None, false);
+    let choice = std::rc::Rc::new(std::cell::Cell::new(None));
+    dialog.choose(None::<&gtk::Window>, gtk::gio::Cancellable::NONE, {
+        let main_loop = main_loop.clone();
+        let choice = choice.clone();
+        move |result| {
+            choice.set(result.ok());
+            main_loop.quit();
+        }
+    });
+    main_loop.run();
+    choice.get()
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn non_utf8_script_paths_are_shown() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let path = PathBuf::from(OsStr::from_bytes(b"/scripts/caf\xe9.sh"));
+        assert_eq!(script_path_text(Some(&path)), "/scripts/caf\u{FFFD}.sh");
+        assert_eq!(script_path_text(None), "");
+    }
+    #[test]
+    fn command_text_quotes_arguments_with_spaces() {
+        let command = [
+            OsString::from("pkexec"),
+            "env".into(),
+            "KAS_ROOT=/home/me/My Activities".into(),
+        ];
+        assert_eq!(
+            command_text(&command),
+            r#"pkexec env "KAS_ROOT=/home/me/My Activities""#
+        );
+    }
+    #[test]
+    fn script_choice_requires_a_shell_script_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let script = tmp.path().join("work.sh");
+        let binary = tmp.path().join("work");
+        std::fs::write(&script, "#!/bin/sh").unwrap();
+        std::fs::write(&binary, "#!/bin/sh").unwrap();
+
+        let sh = AllowedExtensions::default();
+        assert_eq!(script_choice_error(&script, &sh, false), None);
+        assert_eq!(
+            script_choice_error(&binary, &sh, false),
+            Some(locale::Key::ErrorScriptNotShell)
+        );
+        assert_eq!(script_choice_error(&binary, &sh, true), None);
+        assert_eq!(
+            script_choice_error(tmp.path(), &sh, true),
+            Some(locale::Key::ErrorScriptNotAFile)
+        );
+        let zsh = tmp.path().join("work.zsh");
+        std::fs::write(&zsh, "#!/bin/zsh").unwrap();
+        assert_eq!(
+            script_choice_error(&zsh, &sh, false),
+            Some(locale::Key::ErrorScriptNotShell)
+        );
+        let shells: AllowedExtensions = "sh,zsh".parse().unwrap();
+        assert_eq!(script_choice_error(&zsh, &shells, false), None);
+    }
+}
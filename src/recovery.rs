@@ -0,0 +1,158 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    activity::{Activity, ActivityEvent, Scripts},
+    app_dirs::{self, AppDir},
+    error,
+};
+
+const AUTOSAVE_FILENAME: &str = "autosave.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Snapshot(BTreeMap<String, BTreeMap<ActivityEvent, Scripts>>);
+
+impl Snapshot {
+    pub fn from_activities(activities: &[Activity]) -> Self {
+        Self(
+            activities
+                .iter()
+                .map(|activity| (activity.id().clone(), activity.scripts_by_event()))
+                .collect(),
+        )
+    }
+    pub fn differs_from(&self, activities: &[Activity]) -> bool {
+        activities.iter().any(|activity| {
+            self.0
+                .get(activity.id())
+                .is_some_and(|events| *events != activity.scripts_by_event())
+        })
+    }
+    pub fn restore_into(&self, activities: &mut [Activity]) {
+        for activity in activities.iter_mut() {
+            let Some(events) = self.0.get(activity.id()) else {
+                continue;
+            };
+            for event in ActivityEvent::iter() {
+                let scripts = events.get(&event).map(|s| s.to_vec()).unwrap_or_default();
+                activity.set_scripts(event, scripts);
+            }
+        }
+    }
+    pub fn write(&self, path: &Path) -> Result<(), error::Application> {
+        let json = serde_json::to_string(self).map_err(|e| error::InvalidValue {
+            category: "autosave",
+            value: e.to_string(),
+        })?;
+        app_dirs::write_file(path, &json, "writing autosave file")
+    }
+    pub fn read(path: &Path) -> Result<Option<Self>, error::Application> {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(error::Io {
+                    context: "reading autosave file",
+                    source: e,
+                });
+            }
+        };
+        serde_json::from_str(&source)
+            .map(Some)
+            .map_err(|e| error::InvalidValue {
+                category: "autosave",
+                value: e.to_string(),
+            })
+    }
+    pub fn remove(path: &Path) -> Result<(), error::Application> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(error::Io {
+                context: "removing autosave file",
+                source: e,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+pub fn autosave_path() -> Option<PathBuf> {
+    AppDir::State.file(AUTOSAVE_FILENAME)
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{activity::EventMap, fixture::known_activities};
+
+    fn loaded_activities() -> Vec<Activity> {
+        let mut activities = known_activities();
+        activities[0].set_script(ActivityEvent::Activated, "/scripts/work-on.sh".into());
+        activities
+    }
+
+    #[test]
+    fn unchanged_snapshot_does_not_differ() {
+        let activities = loaded_activities();
+        let snapshot = Snapshot::from_activities(&activities);
+        assert_that!(snapshot.differs_from(&activities)).is_false();
+    }
+    #[test]
+    fn edited_snapshot_restores_edits() {
+        let mut edited = loaded_activities();
+        edited[0].delete_script(ActivityEvent::Activated);
+        edited[1].set_script(ActivityEvent::Stopped, "/scripts/personal-off.sh".into());
+        let snapshot = Snapshot::from_activities(&edited);
+        let mut loaded = loaded_activities();
+
+        assert_that!(snapshot.differs_from(&loaded)).is_true();
+        snapshot.restore_into(&mut loaded);
+
+        assert_that!(snapshot.differs_from(&loaded)).is_false();
+        assert_that!(loaded[0].get_script(&ActivityEvent::Activated)).is_none();
+        assert_that!(loaded[1].get_script(&ActivityEvent::Stopped).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/personal-off.sh")));
+    }
+    #[test]
+    fn unknown_activities_are_ignored() {
+        let gone = vec![Activity::new("Gone".into(), "gone-id".into(), {
+            let mut events = EventMap::new();
+            events.insert(
+                ActivityEvent::Started,
+                PathBuf::from("/scripts/gone.sh").into(),
+            );
+            events
+        })];
+        let snapshot = Snapshot::from_activities(&gone);
+        let mut loaded = loaded_activities();
+
+        assert_that!(snapshot.differs_from(&loaded)).is_false();
+        snapshot.restore_into(&mut loaded);
+        assert_that!(Snapshot::from_activities(&loaded))
+            .is_equal_to(Snapshot::from_activities(&loaded_activities()));
+    }
+    #[test]
+    fn write_read_and_remove() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("state/kas-selector/autosave.json");
+        let snapshot = Snapshot::from_activities(&loaded_activities());
+
+        assert_that!(Snapshot::read(&path).unwrap()).is_none();
+        snapshot.write(&path).unwrap();
+        assert_that!(Snapshot::read(&path).unwrap()).is_equal_to(Some(snapshot));
+        Snapshot::remove(&path).unwrap();
+        assert_that!(path.exists()).is_false();
+        assert_that!(Snapshot::remove(&path)).is_ok();
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    io::{self, Read},
+    path::Path,
+    process::{Command, Stdio},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use derive_getters::Getters;
+
+use crate::error;
+
+const TAIL_LINES: usize = 20;
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long to wait for output once the script is gone. Programs it started in the
+/// background can keep its output open for as long as they run.
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Getters)]
+pub struct RunOutput {
+    exit_code: Option<i32>,
+    output_tail: String,
+    #[getter(copy)]
+    timed_out: bool,
+}
+
+pub fn run_script(script: &Path, timeout: Duration) -> Result<RunOutput, error::Application> {
+    let failed = |e: io::Error| error::CommandFailed {
+        command: "test run",
+        error_text: format!("{}: {e}", script.display()),
+        source: Some(e),
+    };
+    let mut child = Command::new(script)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(failed)?;
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait().map_err(failed)? {
+            Some(status) => break Some(status),
+            None if Instant::now() >= deadline => {
+                child.kill().ok();
+                child.wait().ok();
+                break None;
+            }
+            None => thread::sleep(POLL_INTERVAL),
+        }
+    };
+    let mut text = collect_output(stdout);
+    text.push_str(&collect_output(stderr));
+    Ok(RunOutput {
+        exit_code: status.and_then(|status| status.code()),
+        output_tail: tail(&text, TAIL_LINES),
+        timed_out: status.is_none(),
+    })
+}
+
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> JoinHandle<String> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        pipe.read_to_end(&mut bytes).ok();
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+fn collect_output(reader: Option<JoinHandle<String>>) -> String {
+    let Some(reader) = reader else {
+        return String::new();
+    };
+    let deadline = Instant::now() + OUTPUT_GRACE;
+    while !reader.is_finished() && Instant::now() < deadline {
+        thread::sleep(POLL_INTERVAL);
+    }
+    if reader.is_finished() {
+        reader.join().unwrap_or_default()
+    } else {
+        String::new()
+    }
+}
+
+pub fn check_syntax(script: &Path) -> Result<(), error::Application> {
+    let output = Command::new("bash")
+        .arg("-n")
+        .arg(script)
+        .output()
+        .map_err(|e| error::CommandFailed {
+            command: "bash -n",
+            error_text: e.to_string(),
+            source: Some(e),
+        })?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(error::ScriptSyntaxError {
+            path: script.display().to_string(),
+            error_text: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
+}
+
+/// Blocks until the editor closes, so it is reaped rather than left behind as a zombie.
+pub fn open_in_editor(script: &Path) -> Result<(), error::Application> {
+    let failed = |e: io::Error| error::CommandFailed {
+        command: "editor",
+        error_text: format!("{}: {e}", script.display()),
+        source: Some(e),
+    };
+    visual_editor_command(script)
+        .map_or_else(
+            || open::that(script),
+            |mut command| command.spawn().and_then(|mut child| child.wait()).map(drop),
+        )
+        .map_err(failed)
+}
+
+/// `$EDITOR` is left alone since it usually names a terminal editor, which has nowhere to
+/// show up when started from the window.
+fn visual_editor_command(script: &Path) -> Option<Command> {
+    editor_command(&std::env::var("VISUAL").ok()?, script)
+}
+
+fn editor_command(editor: &str, script: &Path) -> Option<Command> {
+    let mut words = editor.split_whitespace();
+    let mut command = Command::new(words.next()?);
+    command.args(words).arg(script);
+    Some(command)
+}
+
+fn tail(text: &str, lines: usize) -> String {
+    let all: Vec<&str> = text.trim_end().lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    use asserting::prelude::*;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn tail_keeps_last_lines() {
+        assert_that!(tail("a\nb\nc\n", 2)).is_equal_to("b\nc".to_string());
+        assert_that!(tail("a\n", 5)).is_equal_to("a".to_string());
+        assert_that!(tail("", 5)).is_equal_to(String::new());
+    }
+    #[test]
+    fn editor_command_keeps_the_editor_arguments() {
+        let command = editor_command("code --wait", Path::new("/s/hook.sh")).unwrap();
+        assert_that!(command.get_program().to_str()).is_equal_to(Some("code"));
+        let args: Vec<_> = command.get_args().filter_map(|a| a.to_str()).collect();
+        assert_that!(args).contains_exactly(["--wait", "/s/hook.sh"]);
+
+        assert!(editor_command("  ", Path::new("/s/hook.sh")).is_none());
+        temp_env::with_var("VISUAL", None::<&str>, || {
+            assert!(visual_editor_command(Path::new("/s/hook.sh")).is_none());
+        });
+    }
+    #[test]
+    fn run_script_captures_exit_code_and_output() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("hook.sh");
+        fs::write(&script, "#!/bin/sh\necho out\necho err >&2\nexit 3\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let output = run_script(&script, Duration::from_secs(30)).unwrap();
+
+        assert_that!(*output.exit_code()).is_equal_to(Some(3));
+        assert_that!(output.output_tail().as_str()).is_equal_to("out\nerr");
+        assert_that!(output.timed_out()).is_false();
+    }
+    #[test]
+    fn run_script_kills_scripts_that_run_too_long() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("hang.sh");
+        fs::write(&script, "#!/bin/sh\necho started\nexec sleep 30\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = Instant::now();
+        let output = run_script(&script, Duration::from_millis(300)).unwrap();
+
+        assert_that!(output.timed_out()).is_true();
+        assert_that!(*output.exit_code()).is_none();
+        assert_that!(output.output_tail().as_str()).is_equal_to("started");
+        assert_that!(started.elapsed() < Duration::from_secs(10)).is_true();
+    }
+    #[test]
+    fn run_script_returns_while_background_programs_keep_running() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("launch.sh");
+        fs::write(&script, "#!/bin/sh\nsleep 30 &\nexit 0\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let started = Instant::now();
+        let output = run_script(&script, Duration::from_secs(30)).unwrap();
+
+        assert_that!(*output.exit_code()).is_equal_to(Some(0));
+        assert_that!(started.elapsed() < Duration::from_secs(10)).is_true();
+    }
+    #[test]
+    fn run_script_reports_missing_script() {
+        let dir = tempdir().unwrap();
+        let result = run_script(&dir.path().join("missing.sh"), Duration::from_secs(30));
+        assert_that!(result.unwrap_err().kind()).is_equal_to(ErrorKind::NotFound);
+    }
+    #[test]
+    fn open_in_editor_waits_for_the_editor_and_reports_spawn_errors() {
+        temp_env::with_var("VISUAL", Some("true"), || {
+            assert_that!(open_in_editor(Path::new("/s/hook.sh"))).is_ok();
+        });
+        temp_env::with_var("VISUAL", Some("/nonexistent/editor --wait"), || {
+            let result = open_in_editor(Path::new("/s/hook.sh"));
+            assert_that!(result.unwrap_err().kind()).is_equal_to(ErrorKind::NotFound);
+        });
+    }
+    #[test]
+    fn check_syntax_reports_what_bash_found() {
+        let dir = tempdir().unwrap();
+        let valid = dir.path().join("valid.sh");
+        fs::write(&valid, "#!/bin/bash\nif true; then echo ok; fi\n").unwrap();
+        let broken = dir.path().join("broken.sh");
+        fs::write(&broken, "#!/bin/bash\nif true; then echo ok\n").unwrap();
+
+        assert_that!(check_syntax(&valid)).is_ok();
+        // Checking never runs the script, so it needs no execute bit
+        let error = check_syntax(&broken).unwrap_err();
+        assert_that!(error.kind()).is_equal_to(ErrorKind::InvalidInput);
+        assert!(
+            matches!(
+                &error,
+                error::ScriptSyntaxError { path, error_text }
+                    if *path == broken.display().to_string() && error_text.contains("syntax error")
+            ),
+            "{error:?}"
+        );
+    }
+}
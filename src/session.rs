@@ -0,0 +1,27 @@
+use gtk::{gio, glib, prelude::*};
+
+const ACTIVITY_MANAGER_NAME: &str = "org.kde.ActivityManager";
+const DBUS_TIMEOUT_MS: i32 = 1000;
+
+/// An unreachable bus counts as not running, since the manager can't be reached either.
+pub fn activity_manager_running() -> bool {
+    name_has_owner(ACTIVITY_MANAGER_NAME)
+        .inspect_err(|e| eprintln!("Checking for the activity manager failed due to: {e}"))
+        .unwrap_or(false)
+}
+
+fn name_has_owner(name: &str) -> Result<bool, glib::Error> {
+    let connection = gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE)?;
+    let reply = connection.call_sync(
+        Some("org.freedesktop.DBus"),
+        "/org/freedesktop/DBus",
+        "org.freedesktop.DBus",
+        "NameHasOwner",
+        Some(&(name,).to_variant()),
+        None,
+        gio::DBusCallFlags::NONE,
+        DBUS_TIMEOUT_MS,
+        gio::Cancellable::NONE,
+    )?;
+    Ok(reply.get::<(bool,)>().is_some_and(|(owned,)| owned))
+}
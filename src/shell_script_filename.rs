@@ -1,21 +1,67 @@
-use derive_more::{AsRef, Debug, Display};
-use std::{path::Path, str::FromStr};
+use derive_more::{AsRef, Debug, Deref, Display};
+use std::{ffi::OsStr, path::Path, str::FromStr};
 
 use crate::error::Application;
 
-#[derive(Debug, Display, AsRef, Clone, PartialEq, Eq, Hash)]
-pub struct ShellScriptFilename(String);
+#[derive(Debug, Deref, Clone, PartialEq, Eq)]
+pub struct AllowedExtensions(Vec<String>);
 
-impl ShellScriptFilename {
-    pub const fn as_str(&self) -> &str {
-        self.0.as_str()
+impl AllowedExtensions {
+    pub fn contains(&self, extension: &OsStr) -> bool {
+        self.0.iter().any(|allowed| extension == allowed.as_str())
     }
 }
 
-impl FromStr for ShellScriptFilename {
+impl Default for AllowedExtensions {
+    fn default() -> Self {
+        Self(vec!["sh".into()])
+    }
+}
+
+impl FromStr for AllowedExtensions {
     type Err = Application;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut extensions = Vec::new();
+        for extension in s.split(',').map(|e| e.trim().trim_start_matches('.')) {
+            if extension.is_empty() {
+                continue;
+            }
+            if extension.contains(['/', '.', '\0']) {
+                return Err(Application::InvalidValue {
+                    category: "script extension",
+                    value: extension.to_owned(),
+                });
+            }
+            if !extensions.iter().any(|e| e == extension) {
+                extensions.push(extension.to_owned());
+            }
+        }
+        if extensions.is_empty() {
+            return Err(Application::InvalidValue {
+                category: "script extensions",
+                value: s.to_owned(),
+            });
+        }
+        Ok(Self(extensions))
+    }
+}
+
+#[derive(Debug, Display, AsRef, Clone, PartialEq, Eq, Hash)]
+pub struct ShellScriptFilename(String);
+
+impl ShellScriptFilename {
+    pub const fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+    pub fn try_new(s: &str, allow_hidden: bool) -> Result<Self, Application> {
+        Self::with_extensions(s, &AllowedExtensions::default(), allow_hidden)
+    }
+    pub fn with_extensions(
+        s: &str,
+        allowed: &AllowedExtensions,
+        allow_hidden: bool,
+    ) -> Result<Self, Application> {
         if s.is_empty() || s == "." || s == ".." || s.contains('\0') || s.len() > 255 {
             return Err(Self::error(s));
         }
@@ -26,7 +72,7 @@ impl FromStr for ShellScriptFilename {
             return Err(Self::error(s));
         }
 
-        if !matches!(path.extension().and_then(|ext| ext.to_str()), Some("sh")) {
+        if !path.extension().is_some_and(|ext| allowed.contains(ext)) {
             return Err(Self::error(s));
         }
 
@@ -34,10 +80,22 @@ impl FromStr for ShellScriptFilename {
             return Err(Self::error(s));
         }
 
+        if !allow_hidden && s.starts_with('.') {
+            return Err(Self::error(s));
+        }
+
         Ok(Self(s.to_owned()))
     }
 }
 
+impl FromStr for ShellScriptFilename {
+    type Err = Application;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_new(s, false)
+    }
+}
+
 impl ShellScriptFilename {
     fn error(s: &str) -> Application {
         Application::InvalidValue {
@@ -69,5 +127,58 @@ mod test {
         assert!("".parse::<ShellScriptFilename>().is_err());
         assert!("/etc/passwd".parse::<ShellScriptFilename>().is_err());
         assert!("sh".repeat(300).parse::<ShellScriptFilename>().is_err()); // too long
+        assert!(".sh".parse::<ShellScriptFilename>().is_err()); // extension only
+        assert!(".bashrc.sh".parse::<ShellScriptFilename>().is_err()); // hidden
+    }
+
+    #[test]
+    fn hidden_shell_filenames_when_allowed() {
+        let f = ShellScriptFilename::try_new(".bashrc.sh", true).unwrap();
+        assert_eq!(f.as_str(), ".bashrc.sh");
+
+        assert!(ShellScriptFilename::try_new(".sh", true).is_err());
+        assert!(ShellScriptFilename::try_new("..", true).is_err());
+    }
+
+    #[test]
+    fn allowed_extensions_broaden_validation() {
+        let allowed: AllowedExtensions = "sh,bash".parse().unwrap();
+        let f = ShellScriptFilename::with_extensions("hook.bash", &allowed, false).unwrap();
+        assert_eq!(f.as_str(), "hook.bash");
+        let f = ShellScriptFilename::with_extensions(".hook.bash", &allowed, true).unwrap();
+        assert_eq!(f.as_str(), ".hook.bash");
+
+        for invalid in ["hook", "hook.py", "a/hook.sh", "..", ".hook.sh", "a\0b.sh"] {
+            assert!(ShellScriptFilename::with_extensions(invalid, &allowed, false).is_err());
+        }
+        let long = format!("{}.sh", "a".repeat(300));
+        assert!(ShellScriptFilename::with_extensions(&long, &allowed, true).is_err());
+    }
+
+    #[test]
+    fn extension_sets_accept_bash_and_zsh() {
+        let allowed: AllowedExtensions = "sh, .bash,zsh".parse().unwrap();
+        assert_eq!(*allowed, ["sh", "bash", "zsh"]);
+        for name in ["hook.sh", "hook.bash", "hook.zsh"] {
+            let f = ShellScriptFilename::with_extensions(name, &allowed, false).unwrap();
+            assert_eq!(f.as_str(), name);
+        }
+        assert!(ShellScriptFilename::with_extensions("hook.py", &allowed, false).is_err());
+    }
+
+    #[test]
+    fn default_extensions_reject_other_scripts() {
+        let allowed = AllowedExtensions::default();
+        assert!(ShellScriptFilename::with_extensions("hook.sh", &allowed, false).is_ok());
+        assert!(ShellScriptFilename::with_extensions("hook.py", &allowed, false).is_err());
+        assert!(ShellScriptFilename::with_extensions("hook.bash", &allowed, false).is_err());
+        assert!(allowed.contains(OsStr::new("sh")));
+    }
+
+    #[test]
+    fn extension_sets_are_never_empty() {
+        assert!("".parse::<AllowedExtensions>().is_err());
+        assert!(" , ,".parse::<AllowedExtensions>().is_err());
+        assert!("sh,tar.gz".parse::<AllowedExtensions>().is_err());
     }
 }
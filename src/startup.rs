@@ -0,0 +1,77 @@
+use std::{fs, path::Path};
+
+use kas_selector::{cli::Args, config::Config, error};
+
+pub fn load_config(args: &Args) -> Result<Config, error::Application> {
+    let config = Config::load()?;
+    let read_only = args.read_only || *config.read_only();
+    Ok(config.with_read_only(read_only))
+}
+
+pub fn missing_root(config: &Config) -> Option<&Path> {
+    let root = config.root_path();
+    (!root.exists()).then_some(root.as_path())
+}
+
+pub fn create_root(root: &Path) -> Result<(), error::Application> {
+    fs::create_dir_all(root).map_err(|e| error::Io {
+        context: "creating the activities folder",
+        source: e,
+    })
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use asserting::prelude::*;
+    use clap::Parser;
+    use tempfile::tempdir;
+
+    #[test]
+    fn invalid_settings_are_returned_as_errors() {
+        let tmp = tempdir().unwrap();
+        let args = Args::try_parse_from(["kas-selector", "--read-only"]).unwrap();
+        temp_env::with_vars(
+            [
+                ("XDG_CONFIG_HOME", Some(tmp.path().to_str().unwrap())),
+                ("KAS_RUN_TIMEOUT", Some("soon")),
+            ],
+            || {
+                assert_that!(load_config(&args)).is_err();
+            },
+        );
+        temp_env::with_vars(
+            [
+                ("XDG_CONFIG_HOME", Some(tmp.path().to_str().unwrap())),
+                ("KAS_RUN_TIMEOUT", None),
+                ("KAS_READONLY", None),
+            ],
+            || {
+                assert!(*load_config(&args).unwrap().read_only());
+            },
+        );
+    }
+    #[test]
+    fn missing_root_is_found_and_created() {
+        let tmp = tempdir().unwrap();
+        let root = tmp.path().join("kactivitymanagerd/activities");
+        temp_env::with_vars(
+            [
+                ("XDG_CONFIG_HOME", Some(tmp.path().to_str().unwrap())),
+                ("KAS_ROOT", Some(root.to_str().unwrap())),
+            ],
+            || {
+                let config = Config::load().unwrap();
+                assert_that!(missing_root(&config)).is_equal_to(Some(root.as_path()));
+
+                create_root(&root).unwrap();
+                assert_that!(missing_root(&config)).is_none();
+            },
+        );
+        let file = tmp.path().join("file");
+        fs::write(&file, "").unwrap();
+        assert_that!(create_root(&file.join("activities"))).is_err();
+    }
+}
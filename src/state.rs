@@ -0,0 +1,75 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_dirs::{self, AppDir},
+    error,
+};
+
+const STATE_FILENAME: &str = "state.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StateFile {
+    pub selected_activity_id: String,
+}
+
+impl StateFile {
+    pub fn load(path: &Path) -> Self {
+        let Ok(source) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+        toml::from_str(&source)
+            .inspect_err(|e| eprintln!("Ignoring the saved state due to: {e}"))
+            .unwrap_or_default()
+    }
+    pub fn save(&self, path: &Path) -> Result<(), error::Application> {
+        let text = toml::to_string(self).map_err(|e| error::InvalidValue {
+            category: "state file",
+            value: e.to_string(),
+        })?;
+        app_dirs::write_file(path, &text, "writing state file")
+    }
+}
+
+pub fn state_path() -> Option<PathBuf> {
+    AppDir::State.file(STATE_FILENAME)
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn state_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kas-selector").join(STATE_FILENAME);
+        let state = StateFile {
+            selected_activity_id: "abc-12d-b".into(),
+        };
+
+        state.save(&path).unwrap();
+        assert_that!(StateFile::load(&path)).is_equal_to(state);
+    }
+    #[test]
+    fn missing_or_malformed_state_starts_afresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.toml");
+        assert_that!(StateFile::load(&path)).is_equal_to(StateFile::default());
+
+        for text in ["selected_activity_id = ", "selected_activity_id = 7"] {
+            fs::write(&path, text).unwrap();
+            assert_that!(StateFile::load(&path)).is_equal_to(StateFile::default());
+        }
+        fs::write(&path, "").unwrap();
+        assert_that!(StateFile::load(&path)).is_equal_to(StateFile::default());
+    }
+}
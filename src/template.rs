@@ -0,0 +1,81 @@
+use std::{fs, os::unix::fs::PermissionsExt, path::Path};
+
+use crate::{activity::ActivityEvent, error};
+
+pub fn script_template(activity_name: &str, event: ActivityEvent) -> String {
+    format!(
+        "#!/bin/sh\n\
+         # Runs when the KDE activity \"{activity_name}\" is {event}.\n\
+         # Assigned with kas-selector.\n\
+         \n\
+         # Add your commands below.\n"
+    )
+}
+
+pub fn template_filename(activity_name: &str, event: ActivityEvent) -> String {
+    let slug: String = activity_name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug: Vec<&str> = slug.split('-').filter(|part| !part.is_empty()).collect();
+    if slug.is_empty() {
+        format!("{event}.sh")
+    } else {
+        format!("{}-{event}.sh", slug.join("-"))
+    }
+}
+
+pub fn create_script(
+    path: &Path,
+    activity_name: &str,
+    event: ActivityEvent,
+) -> Result<(), error::Application> {
+    fs::write(path, script_template(activity_name, event)).map_err(|e| error::Io {
+        context: "writing starter script",
+        source: e,
+    })?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(|e| error::Io {
+        context: "making starter script executable",
+        source: e,
+    })
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn template_mentions_activity_and_event() {
+        let script = script_template("Work", ActivityEvent::Activated);
+        assert_that!(script.starts_with("#!/bin/sh\n")).is_true();
+        assert_that!(script.contains("\"Work\" is activated")).is_true();
+    }
+    #[test]
+    fn template_filename_is_slugged() {
+        assert_that!(template_filename(
+            "Filing Taxes & Accounting",
+            ActivityEvent::Stopped
+        ))
+        .is_equal_to("filing-taxes-accounting-stopped.sh".to_string());
+        assert_that!(template_filename("&&", ActivityEvent::Started))
+            .is_equal_to("started.sh".to_string());
+    }
+    #[test]
+    fn create_script_is_executable() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("work-activated.sh");
+
+        create_script(&path, "Work", ActivityEvent::Activated).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_that!(mode & 0o111).is_equal_to(0o111);
+        assert_that!(fs::read_to_string(&path).unwrap())
+            .is_equal_to(script_template("Work", ActivityEvent::Activated));
+    }
+}
@@ -0,0 +1,364 @@
+use std::{collections::BTreeMap, fs, path::Path, path::PathBuf};
+
+use derive_getters::Getters;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    activity::{Activity, ActivityEvent, Scripts},
+    cli, error,
+    file_format::Format,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActivityScripts {
+    id: String,
+    name: String,
+    scripts: BTreeMap<ActivityEvent, Scripts>,
+}
+
+/// Activity names are kept alongside ids because ids differ between machines.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptExport {
+    activities: Vec<ActivityScripts>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Getters)]
+pub struct ImportSummary {
+    matched: usize,
+    unmatched_activities: Vec<String>,
+    missing_scripts: Vec<PathBuf>,
+}
+
+impl ImportSummary {
+    pub const fn is_complete(&self) -> bool {
+        self.unmatched_activities.is_empty() && self.missing_scripts.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    #[default]
+    ImportedWins,
+    KeepExisting,
+}
+
+impl ScriptExport {
+    pub fn from_activities(activities: &[Activity]) -> Self {
+        let activities = activities
+            .iter()
+            .map(|activity| ActivityScripts {
+                id: activity.id().clone(),
+                name: activity.name().clone(),
+                scripts: activity.scripts_by_event(),
+            })
+            .collect();
+        Self { activities }
+    }
+    pub fn serialize(&self, format: Format) -> Result<String, error::Application> {
+        match format {
+            Format::Json => serde_json::to_string_pretty(self).map_err(|e| error::InvalidValue {
+                category: "JSON export",
+                value: e.to_string(),
+            }),
+            Format::Toml => toml::to_string_pretty(self).map_err(|e| error::InvalidValue {
+                category: "TOML export",
+                value: e.to_string(),
+            }),
+        }
+    }
+    pub fn deserialize(source: &str, format: Format) -> Result<Self, error::Application> {
+        match format {
+            Format::Json => serde_json::from_str(source).map_err(|e| error::InvalidValue {
+                category: "JSON export",
+                value: e.to_string(),
+            }),
+            Format::Toml => toml::from_str(source).map_err(|e| error::InvalidValue {
+                category: "TOML export",
+                value: e.to_string(),
+            }),
+        }
+    }
+    /// Writes TOML to `.toml` files and JSON to everything else.
+    pub fn write(&self, path: &Path) -> Result<(), error::Application> {
+        let format = Format::from_path(path).unwrap_or(Format::Json);
+        fs::write(path, self.serialize(format)?).map_err(|e| error::Io {
+            context: "writing export file",
+            source: e,
+        })
+    }
+    pub fn read(path: &Path) -> Result<Self, error::Application> {
+        let source = fs::read_to_string(path).map_err(|e| error::Io {
+            context: "reading export file",
+            source: e,
+        })?;
+        Self::deserialize(&source, Format::from_path(path).unwrap_or(Format::Json))
+    }
+    pub fn conflicts(&self, activities: &[Activity]) -> usize {
+        self.activities
+            .iter()
+            .filter_map(|exported| {
+                exported
+                    .find_in(activities)
+                    .and_then(|index| activities.get(index))
+                    .map(|activity| (exported, activity))
+            })
+            .map(|(exported, activity)| {
+                exported
+                    .scripts
+                    .iter()
+                    .filter(|(event, scripts)| {
+                        let current = activity.scripts(event);
+                        !current.is_empty() && current != scripts.as_slice()
+                    })
+                    .count()
+            })
+            .sum()
+    }
+    pub fn merge_into(
+        &self,
+        activities: &mut [Activity],
+        strategy: ConflictStrategy,
+    ) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+        for exported in &self.activities {
+            let index = exported.find_in(activities);
+            let Some(activity) = index.and_then(|i| activities.get_mut(i)) else {
+                summary.unmatched_activities.push(exported.name.clone());
+                continue;
+            };
+            summary.matched += 1;
+            for (event, scripts) in &exported.scripts {
+                if strategy == ConflictStrategy::KeepExisting && !activity.scripts(event).is_empty()
+                {
+                    continue;
+                }
+                summary
+                    .missing_scripts
+                    .extend(scripts.iter().filter(|script| !script.exists()).cloned());
+                activity.set_scripts(*event, scripts.to_vec());
+            }
+        }
+        summary
+    }
+}
+
+impl ActivityScripts {
+    /// Ids differ between machines, so an activity that isn't found by id is looked up by
+    /// name, the same way the command line does.
+    fn find_in(&self, activities: &[Activity]) -> Option<usize> {
+        let activity = cli::find_activity(activities, &self.id)
+            .or_else(|_| cli::find_activity(activities, &self.name))
+            .ok()?;
+        activities.iter().position(|a| a.id() == activity.id())
+    }
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use asserting::prelude::*;
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{activity::EventMap, fixture::known_activities};
+
+    fn sample_activities() -> Vec<Activity> {
+        let mut activities = known_activities();
+        activities[0].set_script(ActivityEvent::Activated, "/scripts/work-on.sh".into());
+        activities[0].set_script(ActivityEvent::Stopped, "/scripts/work-off.sh".into());
+        activities
+    }
+
+    #[test]
+    fn from_activities_keeps_assigned_events_only() {
+        let export = ScriptExport::from_activities(&sample_activities());
+
+        assert_that!(export.activities.len()).is_equal_to(2);
+        let work = &export.activities[0];
+        assert_that!(work.id.as_str()).is_equal_to("abc-12d-a");
+        assert_that!(work.name.as_str()).is_equal_to("Work");
+        assert_that!(work.scripts.keys().copied().collect::<Vec<_>>())
+            .contains_exactly([ActivityEvent::Activated, ActivityEvent::Stopped]);
+        assert_that!(export.activities[1].scripts.is_empty()).is_true();
+    }
+    #[test]
+    fn json_uses_event_names() {
+        let json = ScriptExport::from_activities(&sample_activities())
+            .serialize(Format::Json)
+            .unwrap();
+
+        assert_that!(json.contains(r#""activated": "/scripts/work-on.sh""#)).is_true();
+        assert_that!(json.contains(r#""stopped": "/scripts/work-off.sh""#)).is_true();
+    }
+    #[test]
+    fn write_produces_parseable_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("export.json");
+        let export = ScriptExport::from_activities(&sample_activities());
+
+        export.write(&path).unwrap();
+
+        let parsed: ScriptExport =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_that!(parsed).is_equal_to(export);
+    }
+    #[test]
+    fn export_then_import_preserves_assignments() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("export.json");
+        let original = sample_activities();
+        ScriptExport::from_activities(&original)
+            .write(&path)
+            .unwrap();
+        let mut loaded = known_activities();
+
+        let summary = ScriptExport::read(&path)
+            .unwrap()
+            .merge_into(&mut loaded, ConflictStrategy::ImportedWins);
+
+        assert_that!(*summary.matched()).is_equal_to(2);
+        assert_that!(summary.unmatched_activities().clone()).is_empty();
+        assert_that!(ScriptExport::from_activities(&loaded))
+            .is_equal_to(ScriptExport::from_activities(&original));
+    }
+    #[test]
+    fn merge_falls_back_to_name_and_reports_unmatched() {
+        let export = ScriptExport::deserialize(
+            r#"{"activities": [
+                {"id": "other-id", "name": "WORK", "scripts": {"started": "/scripts/s.sh"}},
+                {"id": "gone-id", "name": "Gone", "scripts": {"stopped": "/scripts/g.sh"}}
+            ]}"#,
+            Format::Json,
+        )
+        .unwrap();
+        let mut activities = sample_activities();
+
+        let summary = export.merge_into(&mut activities, ConflictStrategy::ImportedWins);
+
+        assert_that!(*summary.matched()).is_equal_to(1);
+        assert_that!(summary.unmatched_activities().clone()).contains_exactly(["Gone".to_string()]);
+        assert_that!(summary.missing_scripts().clone())
+            .contains_exactly([PathBuf::from("/scripts/s.sh")]);
+        let work = &activities[0];
+        assert_that!(work.get_script(&ActivityEvent::Started).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/s.sh")));
+        assert_that!(work.get_script(&ActivityEvent::Activated).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/work-on.sh")));
+        assert_that!(activities[1].get_script(&ActivityEvent::Stopped)).is_none();
+    }
+    #[test]
+    fn toml_round_trip_by_extension() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("export.toml");
+        let export = ScriptExport::from_activities(&sample_activities());
+
+        export.write(&path).unwrap();
+
+        let source = fs::read_to_string(&path).unwrap();
+        assert_that!(source.contains("[[activities]]")).is_true();
+        assert_that!(ScriptExport::read(&path).unwrap()).is_equal_to(export);
+    }
+    #[test]
+    fn existing_scripts_are_not_reported_missing() {
+        let dir = tempdir().unwrap();
+        let script = dir.path().join("work.sh");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Activated, script.into());
+        let export = ScriptExport::from_activities(&[Activity::new(
+            "Work".into(),
+            "machine-a-id".into(),
+            events,
+        )]);
+        let mut activities = sample_activities();
+
+        let summary = export.merge_into(&mut activities, ConflictStrategy::ImportedWins);
+
+        assert_that!(summary.is_complete()).is_true();
+        assert_that!(*summary.matched()).is_equal_to(1);
+    }
+    #[test]
+    fn scripts_missing_at_export_time_are_kept() {
+        let dir = tempdir().unwrap();
+        let gone = dir.path().join("gone.sh");
+        let mut events = EventMap::new();
+        events.insert(ActivityEvent::Started, gone.clone().into());
+        let export = ScriptExport::from_activities(&[Activity::new(
+            "Work".into(),
+            "abc-12d-a".into(),
+            events,
+        )]);
+
+        let json = export.serialize(Format::Json).unwrap();
+        let parsed = ScriptExport::deserialize(&json, Format::Json).unwrap();
+
+        assert_that!(parsed.activities[0].scripts[&ActivityEvent::Started].to_vec())
+            .is_equal_to(vec![gone]);
+    }
+    #[test]
+    fn conflicts_count_only_differing_scripts() {
+        let export = ScriptExport::deserialize(
+            r#"{"activities": [{"id": "abc-12d-a", "name": "Work", "scripts": {
+                "activated": "/scripts/work-on.sh",
+                "stopped": "/scripts/other-off.sh",
+                "started": "/scripts/start.sh"
+            }}]}"#,
+            Format::Json,
+        )
+        .unwrap();
+
+        assert_that!(export.conflicts(&sample_activities())).is_equal_to(1);
+    }
+    #[test]
+    fn keep_existing_only_fills_empty_events() {
+        let export = ScriptExport::deserialize(
+            r#"{"activities": [{"id": "abc-12d-a", "name": "Work", "scripts": {
+                "stopped": "/scripts/other-off.sh",
+                "started": "/scripts/start.sh"
+            }}]}"#,
+            Format::Json,
+        )
+        .unwrap();
+        let mut kept = sample_activities();
+        let mut replaced = sample_activities();
+
+        export.merge_into(&mut kept, ConflictStrategy::KeepExisting);
+        export.merge_into(&mut replaced, ConflictStrategy::ImportedWins);
+
+        assert_that!(kept[0].get_script(&ActivityEvent::Stopped).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/work-off.sh")));
+        assert_that!(replaced[0].get_script(&ActivityEvent::Stopped).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/other-off.sh")));
+        for activities in [&kept, &replaced] {
+            assert_that!(activities[0].get_script(&ActivityEvent::Started).cloned())
+                .is_equal_to(Some(PathBuf::from("/scripts/start.sh")));
+            assert_that!(activities[0].script_count()).is_equal_to(3);
+        }
+    }
+    #[test]
+    fn partial_import_applies_matching_ids_only() {
+        let export = ScriptExport::deserialize(
+            r#"{"activities": [
+                {"id": "abc-12d-b", "name": "Home", "scripts": {"started": "/scripts/home.sh"}},
+                {"id": "gone-id", "name": "Gone", "scripts": {"stopped": "/scripts/g.sh"}}
+            ]}"#,
+            Format::Json,
+        )
+        .unwrap();
+        let mut activities = sample_activities();
+
+        let summary = export.merge_into(&mut activities, ConflictStrategy::ImportedWins);
+
+        assert_that!(*summary.matched()).is_equal_to(1);
+        assert_that!(summary.unmatched_activities().clone()).contains_exactly(["Gone".to_string()]);
+        assert_that!(activities[0].script_count()).is_equal_to(2);
+        assert_that!(activities[1].get_script(&ActivityEvent::Started).cloned())
+            .is_equal_to(Some(PathBuf::from("/scripts/home.sh")));
+    }
+    #[test]
+    fn deserialize_rejects_invalid_input() {
+        assert_that!(ScriptExport::deserialize("{not json", Format::Json)).is_err();
+        assert_that!(ScriptExport::deserialize("[[activities", Format::Toml)).is_err();
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::error;
+
+pub const SETTLE_DELAY: Duration = Duration::from_millis(500);
+
+#[derive(Debug)]
+pub struct RootWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+impl RootWatcher {
+    /// Calls `on_change` once changes under `root` have settled, leaving out changes that
+    /// only touch `ignored`.
+    pub fn start(
+        root: &Path,
+        ignored: Option<PathBuf>,
+        on_change: impl Fn() + Send + 'static,
+    ) -> Result<Self, error::Application> {
+        let (tx, rx) = mpsc::channel::<()>();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            if event.is_ok_and(|event| is_relevant(&event, ignored.as_deref())) {
+                let _ = tx.send(());
+            }
+        })
+        .map_err(watch_error)?;
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .map_err(watch_error)?;
+        // Exits once the watcher, and with it the sender, is dropped.
+        thread::spawn(move || {
+            while rx.recv().is_ok() {
+                loop {
+                    match rx.recv_timeout(SETTLE_DELAY) {
+                        Ok(()) => continue,
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                on_change();
+            }
+        });
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+fn watch_error(e: notify::Error) -> error::Application {
+    let source = match e.kind {
+        notify::ErrorKind::Io(source) => source,
+        notify::ErrorKind::PathNotFound => io::ErrorKind::NotFound.into(),
+        kind => io::Error::other(format!("{kind:?}")),
+    };
+    error::Io {
+        context: "watching directory for changes",
+        source,
+    }
+}
+
+fn is_relevant(event: &Event, ignored: Option<&Path>) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) && !ignored.is_some_and(|ignored| event.paths.iter().all(|path| path.starts_with(ignored)))
+}
+
+// Allowed in tests
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use asserting::prelude::*;
+    use notify::event::{AccessKind, CreateKind, ModifyKind, RemoveKind};
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn only_tree_changes_are_relevant() {
+        assert_that!(is_relevant(
+            &Event::new(EventKind::Create(CreateKind::File)),
+            None
+        ))
+        .is_true();
+        assert_that!(is_relevant(
+            &Event::new(EventKind::Modify(ModifyKind::Any)),
+            None
+        ))
+        .is_true();
+        assert_that!(is_relevant(
+            &Event::new(EventKind::Remove(RemoveKind::Any)),
+            None
+        ))
+        .is_true();
+        assert_that!(is_relevant(
+            &Event::new(EventKind::Access(AccessKind::Any)),
+            None
+        ))
+        .is_false();
+        assert_that!(is_relevant(&Event::new(EventKind::Other), None)).is_false();
+    }
+    #[test]
+    fn changes_only_inside_the_ignored_folder_are_not_relevant() {
+        let ignored = Path::new("/kas/.kas-staging");
+        let event = |paths: &[&str]| {
+            paths.iter().fold(
+                Event::new(EventKind::Create(CreateKind::File)),
+                |event, path| event.add_path(path.into()),
+            )
+        };
+
+        assert_that!(is_relevant(
+            &event(&["/kas/.kas-staging/a-1"]),
+            Some(ignored)
+        ))
+        .is_false();
+        assert_that!(is_relevant(
+            &event(&["/kas/.kas-staging/a-1/x.sh", "/kas/a-1/started/x.sh"]),
+            Some(ignored)
+        ))
+        .is_true();
+        assert_that!(is_relevant(&event(&["/kas/a-1"]), Some(ignored))).is_true();
+    }
+    #[test]
+    fn burst_of_changes_is_reported_once() {
+        let dir = tempdir().unwrap();
+        let (tx, rx) = mpsc::channel();
+        let _watcher = RootWatcher::start(dir.path(), None, move || tx.send(()).unwrap()).unwrap();
+
+        for name in ["a", "b", "c"] {
+            fs::create_dir(dir.path().join(name)).unwrap();
+        }
+
+        assert_that!(rx.recv_timeout(Duration::from_secs(5))).is_ok();
+        assert_that!(rx.recv_timeout(SETTLE_DELAY * 2)).is_err();
+    }
+    #[test]
+    fn missing_root_is_not_found() {
+        let dir = tempdir().unwrap();
+        let result = RootWatcher::start(&dir.path().join("missing"), None, || {});
+        assert_that!(result.unwrap_err().kind()).is_equal_to(error::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,227 @@
+//! Runs the headless commands against a temporary activities root, with a stand-in for
+//! kactivities-cli on the `PATH`.
+// Allowed in tests
+#![allow(clippy::unwrap_used)]
+
+use std::{
+    fs,
+    os::unix::fs::{PermissionsExt, symlink},
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+const ACTIVITY_LIST: &str = "\
+[CURRENT] abc-12d-a Work (icon-a)
+[STOPPED] abc-12d-b Personal (icon-b)
+";
+
+struct Fixture {
+    dir: TempDir,
+}
+
+impl Fixture {
+    /// Work has an activated script, Personal has none.
+    fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let bin = dir.path().join("bin");
+        fs::create_dir(&bin).unwrap();
+        let fake_cli = bin.join("kactivities-cli");
+        fs::write(
+            &fake_cli,
+            format!("#!/bin/sh\ncat <<'EOF'\n{ACTIVITY_LIST}EOF\n"),
+        )
+        .unwrap();
+        fs::set_permissions(&fake_cli, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let fixture = Self { dir };
+        fs::create_dir(fixture.scripts()).unwrap();
+        fs::write(fixture.script("on.sh"), "#!/bin/sh\necho on\n").unwrap();
+        fs::write(fixture.script("start.sh"), "#!/bin/sh\necho start\n").unwrap();
+        let activated = fixture.root().join("abc-12d-a/activated");
+        fs::create_dir_all(&activated).unwrap();
+        symlink(fixture.script("on.sh"), activated.join("kas-script.sh")).unwrap();
+        fixture
+    }
+    fn root(&self) -> PathBuf {
+        self.dir.path().join("activities")
+    }
+    fn scripts(&self) -> PathBuf {
+        self.dir.path().join("scripts")
+    }
+    fn script(&self, name: &str) -> PathBuf {
+        self.scripts().join(name)
+    }
+    fn run(&self, args: &[&str]) -> Output {
+        let path = std::env::var("PATH").unwrap_or_default();
+        Command::new(env!("CARGO_BIN_EXE_kas-selector"))
+            .args(args)
+            .env(
+                "PATH",
+                format!("{}:{path}", self.dir.path().join("bin").display()),
+            )
+            .env("XDG_CONFIG_HOME", self.dir.path())
+            .env("KAS_ROOT", self.root())
+            .env("KAS_SCRIPT_NAME", "kas-script.sh")
+            .env("KAS_SORT", "0")
+            .env("KAS_ACTIVITY_SOURCE", "cli")
+            .env_remove("KAS_READONLY")
+            .env_remove("KAS_LINK_MODE")
+            .env_remove("DISPLAY")
+            .env_remove("WAYLAND_DISPLAY")
+            .output()
+            .unwrap()
+    }
+}
+
+fn json(output: &Output) -> Value {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn list_prints_every_activity_as_json() {
+    let fixture = Fixture::new();
+
+    let value = json(&fixture.run(&["--no-gui", "list"]));
+
+    assert_eq!(value.as_array().unwrap().len(), 2);
+    assert_eq!(value[0]["id"], "abc-12d-a");
+    assert_eq!(value[0]["name"], "Work");
+    assert_eq!(value[0]["state"], "current");
+    assert_eq!(
+        value[0]["scripts"]["activated"],
+        fixture.script("on.sh").display().to_string()
+    );
+    assert_eq!(value[1]["scripts"], serde_json::json!({}));
+}
+
+#[test]
+fn list_flag_prints_the_same_json_as_the_list_command() {
+    let fixture = Fixture::new();
+
+    let flag = json(&fixture.run(&["--list", "--json"]));
+
+    assert_eq!(flag, json(&fixture.run(&["--no-gui", "list"])));
+}
+
+#[test]
+fn lists_without_a_display_when_no_command_is_given() {
+    let fixture = Fixture::new();
+
+    let value = json(&fixture.run(&[]));
+
+    assert_eq!(value.as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn show_finds_an_activity_by_name() {
+    let fixture = Fixture::new();
+
+    let value = json(&fixture.run(&["show", "work"]));
+
+    assert_eq!(value.as_array().unwrap().len(), 1);
+    assert_eq!(value[0]["id"], "abc-12d-a");
+}
+
+#[test]
+fn set_and_delete_change_one_event_on_disk() {
+    let fixture = Fixture::new();
+    let start = fixture.script("start.sh");
+    let link = fixture.root().join("abc-12d-b/started/kas-script.sh");
+
+    let value = json(&fixture.run(&["set", "Personal", "started", start.to_str().unwrap()]));
+    assert_eq!(value[0]["scripts"]["started"], start.display().to_string());
+    assert_eq!(fs::read_link(&link).unwrap(), start);
+
+    let value = json(&fixture.run(&["delete", "abc-12d-b", "started"]));
+    assert_eq!(value[0]["scripts"], serde_json::json!({}));
+    assert!(fs::symlink_metadata(&link).is_err());
+    assert!(
+        fixture
+            .root()
+            .join("abc-12d-a/activated/kas-script.sh")
+            .exists()
+    );
+}
+
+#[test]
+fn failures_exit_nonzero_without_changing_anything() {
+    let fixture = Fixture::new();
+    let start = fixture.script("start.sh");
+
+    let output = fixture.run(&["show", "Play"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!output.stderr.is_empty());
+
+    let output = fixture.run(&["set", "Work", "exploded", start.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = fixture.run(&[
+        "--read-only",
+        "set",
+        "Work",
+        "started",
+        start.to_str().unwrap(),
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!fixture.root().join("abc-12d-a/started").exists());
+
+    let output = fixture.run(&["set", "Work"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn dry_run_prints_the_change_without_making_it() {
+    let fixture = Fixture::new();
+    let start = fixture.script("start.sh");
+
+    let output = fixture.run(&[
+        "--dry-run",
+        "set",
+        "Personal",
+        "started",
+        start.to_str().unwrap(),
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let link = fixture.root().join("abc-12d-b/started/kas-script.sh");
+    assert!(stdout.contains(&format!(
+        "create Personal started: {} -> {}",
+        link.display(),
+        start.display()
+    )));
+    assert!(fs::symlink_metadata(&link).is_err());
+
+    let output = fixture.run(&["delete", "Work", "activated", "--dry-run"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("remove Work activated: "));
+    assert!(
+        fixture
+            .root()
+            .join("abc-12d-a/activated/kas-script.sh")
+            .exists()
+    );
+}
+
+#[test]
+fn version_prints_the_crate_version() {
+    let fixture = Fixture::new();
+
+    let output = fixture.run(&["--version"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        format!("kas-selector {}", env!("CARGO_PKG_VERSION"))
+    );
+}
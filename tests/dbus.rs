@@ -0,0 +1,38 @@
+//! Lists activities from the real kactivitymanagerd, so it only runs inside a Plasma session:
+//! `cargo test --features dbus-integration --test dbus`
+#![cfg(feature = "dbus-integration")]
+// Allowed in tests
+#![allow(clippy::unwrap_used)]
+
+use kas_selector::{
+    activity::ActivityState,
+    activity_source::{ActivitySource, CliSource, DbusSource},
+};
+
+#[test]
+fn session_bus_lists_the_current_activity() {
+    let listed = DbusSource.list_activities().unwrap();
+
+    assert!(!listed.is_empty());
+    let current = listed
+        .iter()
+        .filter(|a| a.state() == ActivityState::Current)
+        .count();
+    assert_eq!(current, 1);
+}
+
+#[test]
+fn session_bus_agrees_with_kactivities_cli() {
+    let Ok(from_cli) = CliSource.list_activities() else {
+        // Nothing to compare against where kactivities-cli isn't installed
+        return;
+    };
+    let from_bus = DbusSource.list_activities().unwrap();
+
+    let ids = |listed: &[kas_selector::activity_source::ListedActivity]| {
+        let mut ids: Vec<_> = listed.iter().map(|a| a.id().clone()).collect();
+        ids.sort();
+        ids
+    };
+    assert_eq!(ids(&from_bus), ids(&from_cli));
+}